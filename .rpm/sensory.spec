@@ -3,7 +3,7 @@
 %define debug_package %{nil}
 
 Name: sensory
-Summary: A sensor Monitoring tool that fetches data from lm_sensors.
+Summary: A sensor Monitoring tool that reads hwmon sensors directly or through lm_sensors.
 Version: @@VERSION@@
 Release: @@RELEASE@@%{?dist}
 License: MIT
@@ -29,3 +29,4 @@ rm -rf %{buildroot}
 %files
 %defattr(-,root,root,-)
 %{_bindir}/*
+%{_datadir}/polkit-1/actions/org.sensory.helper.policy
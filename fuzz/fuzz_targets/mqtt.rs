@@ -0,0 +1,26 @@
+// Packets from an MQTT broker: framing, then PUBLISH bodies turned into readings.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use monitor_core::mqtt;
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+fuzz_target!(|data: &[u8]| {
+    let mut settings = mqtt::Settings::default();
+    settings.add_topic("# °C");
+    let now = Instant::now();
+    let mut messages = BTreeMap::new();
+    let mut cursor = data;
+    while let Ok((kind, body)) = mqtt::read_packet(&mut cursor) {
+        if kind & 0xf0 == 0x30 {
+            if let Some((topic, payload)) = mqtt::parse_publish(kind, &body) {
+                messages.insert(topic, (payload, now));
+            }
+        }
+    }
+    for entry in mqtt::remote_section(&settings, &messages, now).entries {
+        let _ = entry.display_reading();
+        let _ = entry.in_alarm();
+    }
+});
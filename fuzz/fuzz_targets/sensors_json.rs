@@ -0,0 +1,16 @@
+// `sensors -j` output, through the hand-written JSON parser; deeply nested or
+// truncated documents must come back as errors.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use monitor_core::lm_sensors;
+
+fuzz_target!(|data: &[u8]| {
+    let input = String::from_utf8_lossy(data);
+    if let Ok(sections) = lm_sensors::parse_json_output(&input) {
+        for entry in sections.iter().flat_map(|s| &s.entries) {
+            let _ = entry.display_reading();
+            let _ = entry.in_alarm();
+        }
+    }
+});
@@ -0,0 +1,15 @@
+// `sensors -u` output, parsed by a separate state machine.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use monitor_core::lm_sensors;
+
+fuzz_target!(|data: &[u8]| {
+    let input = String::from_utf8_lossy(data);
+    if let Ok(sections) = lm_sensors::parse_raw_output(&input) {
+        for entry in sections.iter().flat_map(|s| &s.entries) {
+            let _ = entry.display_reading();
+            let _ = entry.in_alarm();
+        }
+    }
+});
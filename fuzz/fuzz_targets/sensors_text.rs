@@ -0,0 +1,18 @@
+// `sensors` output is whatever the installed lm-sensors and drivers print; the parser
+// must return an error for garbage, never panic.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use monitor_core::lm_sensors;
+
+fuzz_target!(|data: &[u8]| {
+    let input = String::from_utf8_lossy(data);
+    if let Ok(sections) = lm_sensors::parse_sensor_output(&input) {
+        for entry in sections.iter().flat_map(|s| &s.entries) {
+            // Formatting runs on every frame, so it is part of the surface too.
+            let _ = entry.display_reading();
+            let _ = entry.in_alarm();
+        }
+    }
+    let _ = lm_sensors::parse_value(&input);
+});
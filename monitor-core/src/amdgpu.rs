@@ -0,0 +1,110 @@
+// AMD GPU load, memory and clocks from the amdgpu files under /sys/class/drm. The
+// card's temperatures (edge, junction, mem), fan and power are on its hwmon chip,
+// which the hwmon and lm-sensors backends already list; this fills in the rest.
+use crate::hwmon::{read_trimmed, reading_entry};
+use crate::model::{Limits, SensorSection};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DRM_ROOT: &str = "/sys/class/drm";
+const PCI_VENDOR_AMD: &str = "0x1002";
+const MIB: f64 = 1024.0 * 1024.0;
+
+// (cardN, its device directory) for every amdgpu card.
+fn cards() -> Vec<(String, PathBuf)> {
+    let mut cards: Vec<(String, PathBuf)> = fs::read_dir(DRM_ROOT)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            // card0, not its connectors (card0-DP-1).
+            name.strip_prefix("card")?.parse::<u32>().ok()?;
+            let device = entry.path().join("device");
+            let amd = read_trimmed(&device.join("vendor")).is_some_and(|vendor| vendor == PCI_VENDOR_AMD);
+            (amd && device.join("gpu_busy_percent").exists()).then_some((name, device))
+        })
+        .collect();
+    cards.sort();
+    cards
+}
+
+pub fn available() -> bool {
+    !cards().is_empty()
+}
+
+pub fn read_amdgpu_data() -> Result<Vec<SensorSection>, String> {
+    let sections: Vec<SensorSection> = cards().iter().map(|(card, device)| read_card(card, device)).collect();
+    if sections.is_empty() {
+        Err("No amdgpu cards found".to_string())
+    } else {
+        Ok(sections)
+    }
+}
+
+fn read_card(card: &str, device: &Path) -> SensorSection {
+    let number = |file: &str| read_trimmed(&device.join(file)).and_then(|raw| raw.parse::<f64>().ok());
+    let mib = |file: &str| number(file).map(|bytes| bytes / MIB);
+    let level = |file: &str| read_trimmed(&device.join(file)).and_then(|levels| current_level(&levels));
+
+    let mut entries = vec![
+        reading_entry("GPU busy", number("gpu_busy_percent"), "%", Limits::default()),
+        reading_entry(
+            "VRAM used",
+            mib("mem_info_vram_used"),
+            "MiB",
+            Limits { max: mib("mem_info_vram_total"), ..Limits::default() },
+        ),
+    ];
+    // APUs share system memory and have no separate clock tables on older kernels.
+    let optional = [
+        ("Memory busy", number("mem_busy_percent"), "%", Limits::default()),
+        (
+            "GTT used",
+            mib("mem_info_gtt_used"),
+            "MiB",
+            Limits { max: mib("mem_info_gtt_total"), ..Limits::default() },
+        ),
+        ("Shader clock", level("pp_dpm_sclk"), "MHz", Limits::default()),
+        ("Memory clock", level("pp_dpm_mclk"), "MHz", Limits::default()),
+    ];
+    for (key, value, unit, limits) in optional {
+        if value.is_some() {
+            entries.push(reading_entry(key, value, unit, limits));
+        }
+    }
+
+    let address = fs::canonicalize(device)
+        .ok()
+        .and_then(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()));
+    SensorSection {
+        name: format!("amdgpu-{}", card),
+        adapter: address.map_or("PCI adapter".to_string(), |address| format!("PCI adapter {}", address)),
+        entries,
+        unparsed: Vec::new(),
+        label: None,
+    }
+}
+
+// The clock of the active DPM level, marked with `*`:
+//   0: 500Mhz
+//   1: 1800Mhz *
+pub fn current_level(levels: &str) -> Option<f64> {
+    let line = levels.lines().find(|line| line.trim_end().ends_with('*'))?;
+    let (_, clock) = line.split_once(':')?;
+    let clock = clock.trim().trim_end_matches('*').trim();
+    let digits = clock.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    digits.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_active_clock_level() {
+        assert_eq!(current_level("0: 500Mhz\n1: 1350Mhz\n2: 1800Mhz *\n"), Some(1800.0));
+        assert_eq!(current_level("0: 96Mhz *\n1: 456Mhz\n"), Some(96.0));
+        assert_eq!(current_level("0: 500Mhz\n1: 1800Mhz\n"), None);
+    }
+}
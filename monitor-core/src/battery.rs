@@ -0,0 +1,143 @@
+// Laptop batteries from /sys/class/power_supply/BAT*: charge, the rate it is charging
+// or discharging at, voltage, cycle count and health (full capacity against design).
+use crate::hwmon::{read_trimmed, reading_entry};
+use crate::model::{Limits, SensorSection};
+use std::fs;
+use std::path::PathBuf;
+
+const POWER_SUPPLY_ROOT: &str = "/sys/class/power_supply";
+
+fn batteries() -> Vec<(String, PathBuf)> {
+    let mut batteries: Vec<(String, PathBuf)> = fs::read_dir(POWER_SUPPLY_ROOT)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            // Peripherals (mice, headsets) report type Battery too but scope Device.
+            let path = entry.path();
+            let battery = read_trimmed(&path.join("type")).is_some_and(|kind| kind == "Battery")
+                && read_trimmed(&path.join("scope")).is_none_or(|scope| scope != "Device");
+            battery.then_some((name, path))
+        })
+        .collect();
+    batteries.sort();
+    batteries
+}
+
+pub fn available() -> bool {
+    !batteries().is_empty()
+}
+
+pub fn read_battery_data() -> Result<Vec<SensorSection>, String> {
+    let sections: Vec<SensorSection> = batteries()
+        .iter()
+        .map(|(name, path)| battery_section(name, |attribute| read_trimmed(&path.join(attribute))))
+        .collect();
+    if sections.is_empty() {
+        Err("No batteries found".to_string())
+    } else {
+        Ok(sections)
+    }
+}
+
+// `attribute` reads one power_supply file. Drivers report either energy (µWh, with
+// power_now in µW) or charge (µAh, with current_now in µA); both are in micro-units.
+pub fn battery_section(name: &str, attribute: impl Fn(&str) -> Option<String>) -> SensorSection {
+    let number = |file: &str| attribute(file).and_then(|raw| raw.parse::<f64>().ok());
+    let micro = |file: &str| number(file).map(|raw| raw / 1e6);
+    let voltage = micro("voltage_now");
+
+    let rate = micro("power_now").or_else(|| Some(micro("current_now")? * voltage?));
+    // Some drivers report a negative current while discharging, others always positive.
+    let discharging = attribute("status").is_some_and(|status| status == "Discharging");
+    let rate = rate.map(|watts| if discharging { -watts.abs() } else { watts.abs() });
+
+    let health = match (number("energy_full"), number("energy_full_design")) {
+        (Some(full), Some(design)) => Some((full, design)),
+        _ => number("charge_full").zip(number("charge_full_design")),
+    }
+    .filter(|&(_, design)| design > 0.0)
+    .map(|(full, design)| full / design * 100.0);
+
+    let mut entries = vec![
+        reading_entry("Charge", number("capacity"), "%", Limits::default()),
+        reading_entry("Charge rate", rate, "W", Limits::default()),
+        reading_entry("Voltage", voltage, "V", Limits { min: micro("voltage_min_design"), ..Limits::default() }),
+    ];
+    // What is left as the driver counts it; the registry follows it with its average
+    // power or current, which also covers drivers that leave power_now at 0.
+    match (micro("energy_now"), micro("charge_now")) {
+        (Some(energy), _) => entries.push(reading_entry("Remaining energy", Some(energy), "Wh", Limits::default())),
+        (None, Some(charge)) => entries.push(reading_entry("Remaining charge", Some(charge), "Ah", Limits::default())),
+        (None, None) => {}
+    }
+    // Many firmwares leave the cycle count at 0.
+    if let Some(cycles) = number("cycle_count").filter(|&cycles| cycles > 0.0) {
+        entries.push(reading_entry("Cycles", Some(cycles), "cycles", Limits::default()));
+    }
+    if health.is_some() {
+        entries.push(reading_entry("Health", health, "%", Limits::default()));
+    }
+    // Charge limits, on laptops whose platform driver (thinkpad_acpi, dell-laptop,
+    // asus-wmi) supports them.
+    for (file, key) in [
+        ("charge_control_start_threshold", "Charge start threshold"),
+        ("charge_control_end_threshold", "Charge stop threshold"),
+    ] {
+        if let Some(threshold) = number(file) {
+            entries.push(reading_entry(key, Some(threshold), "%", Limits::default()));
+        }
+    }
+
+    let model = [attribute("manufacturer"), attribute("model_name")]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let status = attribute("status").unwrap_or_else(|| "Unknown".to_string());
+    SensorSection {
+        name: format!("battery-{}", name),
+        adapter: if model.is_empty() { status } else { format!("{}, {}", model, status) },
+        entries,
+        unparsed: Vec::new(),
+        label: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_battery_attributes() {
+        let files = [
+            ("status", "Discharging"),
+            ("capacity", "83"),
+            ("current_now", "1500000"),
+            ("voltage_now", "12000000"),
+            ("charge_now", "3750000"),
+            ("charge_full", "4500000"),
+            ("charge_full_design", "5000000"),
+            ("cycle_count", "0"),
+            ("charge_control_end_threshold", "80"),
+            ("manufacturer", "SMP"),
+            ("model_name", "5B10W13930"),
+        ];
+        let section = battery_section("BAT0", |file| {
+            files.iter().find(|(name, _)| *name == file).map(|(_, value)| value.to_string())
+        });
+        let reading = |key: &str| section.entries.iter().find(|e| e.key == key).and_then(|e| e.number);
+        assert_eq!(section.name, "battery-BAT0");
+        assert_eq!(section.adapter, "SMP 5B10W13930, Discharging");
+        assert_eq!(reading("Charge"), Some(83.0));
+        assert_eq!(reading("Charge rate"), Some(-18.0));
+        assert_eq!(reading("Voltage"), Some(12.0));
+        assert_eq!(reading("Remaining charge"), Some(3.75));
+        assert_eq!(reading("Health"), Some(90.0));
+        assert!(section.entries.iter().all(|e| e.key != "Cycles"));
+        assert_eq!(reading("Charge stop threshold"), Some(80.0));
+        assert_eq!(reading("Charge start threshold"), None);
+    }
+}
@@ -0,0 +1,136 @@
+// Rates from kernel counters that only ever grow (bytes read, energy used): each
+// sample is compared with the previous one under the same key.
+use crate::hwmon::reading_entry;
+use crate::model::{Limits, SensorSection};
+use std::time::Instant;
+
+// Readings that accumulate, with their size in joules or coulombs, the unit of their
+// rate, and whether they only count up. A battery's stored energy and charge also
+// fall while it discharges; the energy meters of hwmon chips only reset.
+const ACCUMULATED: [(&str, f64, &str, bool); 7] = [
+    ("J", 1.0, "W", true),
+    ("kJ", 1e3, "W", true),
+    ("MJ", 1e6, "W", true),
+    ("Wh", 3600.0, "W", false),
+    ("kWh", 3.6e6, "W", false),
+    ("mAh", 3.6, "A", false),
+    ("Ah", 3600.0, "A", false),
+];
+
+#[derive(Debug, Default)]
+pub struct Counters {
+    samples: Vec<(String, Instant, u64)>,
+}
+
+impl Counters {
+    pub const fn new() -> Self {
+        Counters { samples: Vec::new() }
+    }
+
+    // Records `value` and returns the previous sample with the seconds since; `None`
+    // the first time a key is seen.
+    pub fn advance(&mut self, key: &str, value: u64, now: Instant) -> Option<(u64, f64)> {
+        match self.samples.iter_mut().find(|(known, _, _)| known == key) {
+            Some((_, then, before)) => {
+                let previous = (*before, now.saturating_duration_since(*then).as_secs_f64());
+                (*then, *before) = (now, value);
+                Some(previous)
+            }
+            None => {
+                self.samples.push((key.to_string(), now, value));
+                None
+            }
+        }
+    }
+
+    // Forgets counters that are gone, such as exited processes.
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.samples.retain(|(key, _, _)| keep(key));
+    }
+
+    // Growth per second since the previous sample. A counter that went backwards was
+    // reset (a device re-plugged), which gives no rate for this sample.
+    pub fn rate(&mut self, key: &str, value: u64, now: Instant) -> Option<f64> {
+        let (before, seconds) = self.advance(key, value, now)?;
+        let delta = value.checked_sub(before)?;
+        (seconds > 0.0).then(|| delta as f64 / seconds)
+    }
+}
+
+// Follows every energy or charge reading with "<key> average", the power or current
+// it implies since the previous sample; N/A until there is one.
+pub fn add_averages(sections: &mut [SensorSection], counters: &mut Counters, now: Instant) {
+    for section in sections {
+        let mut i = 0;
+        while i < section.entries.len() {
+            let entry = &section.entries[i];
+            i += 1;
+            let accumulated = ACCUMULATED.iter().find(|(unit, ..)| *unit == entry.unit.symbol());
+            let Some(&(_, scale, unit, monotonic)) = accumulated else {
+                continue;
+            };
+            let Some(number) = entry.number.filter(|&number| number >= 0.0) else {
+                continue;
+            };
+            // Micro-units keep the fractions a printed "12.34 kJ" has.
+            let value = (number * scale * 1e6).round() as u64;
+            let previous = counters.advance(&format!("{}:{}", section.name, entry.key), value, now);
+            let average = previous
+                .filter(|&(before, seconds)| seconds > 0.0 && !(monotonic && value < before))
+                .map(|(before, seconds)| ((value as f64 - before as f64) / 1e6 / seconds, seconds));
+            let key = format!("{} average", entry.key);
+            let mut derived = reading_entry(&key, average.map(|(rate, _)| rate), unit, Limits::default());
+            derived.interval = average.map(|(_, seconds)| seconds);
+            section.entries.insert(i, derived);
+            i += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Unit;
+    use std::time::Duration;
+
+    #[test]
+    fn rates_between_samples() {
+        let mut counters = Counters::new();
+        let start = Instant::now();
+        assert_eq!(counters.rate("sda", 1000, start), None);
+        assert_eq!(counters.rate("sda", 3000, start + Duration::from_secs(2)), Some(1000.0));
+        assert_eq!(counters.rate("sdb", 5, start + Duration::from_secs(2)), None);
+        assert_eq!(counters.rate("sda", 10, start + Duration::from_secs(3)), None);
+        assert_eq!(counters.rate("sda", 20, start + Duration::from_secs(4)), Some(10.0));
+
+        let sample = |energy: f64, charge: f64| SensorSection {
+            name: "meter".to_string(),
+            adapter: "Virtual device".to_string(),
+            entries: vec![
+                reading_entry("energy1", Some(energy), "kJ", Limits::default()),
+                reading_entry("Remaining charge", Some(charge), "Ah", Limits::default()),
+            ],
+            unparsed: Vec::new(),
+            label: None,
+        };
+        let mut sections = [sample(10.0, 3.0)];
+        add_averages(&mut sections, &mut counters, start);
+        let keys: Vec<&str> = sections[0].entries.iter().map(|entry| entry.key.as_str()).collect();
+        assert_eq!(keys, ["energy1", "energy1 average", "Remaining charge", "Remaining charge average"]);
+        assert!(sections[0].entries[1].unavailable);
+
+        let mut sections = [sample(10.1, 2.999)];
+        add_averages(&mut sections, &mut counters, start + Duration::from_secs(2));
+        let average = |index: usize| sections[0].entries[index].number.map(|n| (n * 1000.0).round() / 1000.0);
+        assert_eq!(average(1), Some(50.0));
+        assert_eq!(sections[0].entries[1].unit, Unit::Watts);
+        assert_eq!(sections[0].entries[1].interval, Some(2.0));
+        assert_eq!(average(3), Some(-1.8));
+        assert_eq!(sections[0].entries[3].unit, Unit::Amps);
+
+        // A meter that reset gives no average for that sample.
+        let mut sections = [sample(0.5, 2.999)];
+        add_averages(&mut sections, &mut counters, start + Duration::from_secs(3));
+        assert_eq!(sections[0].entries[1].number, None);
+    }
+}
@@ -0,0 +1,182 @@
+// CPU power settings from /sys/devices/system/cpu: each cpufreq policy's governor and
+// energy-performance preference (EPP), and the turbo switch, with writes that go
+// through the privileged helper when they need root. Also the per-core frequency
+// section.
+use crate::helper;
+use crate::hwmon::{read_trimmed, reading_entry};
+use crate::model::{Limits, SensorEntry, SensorSection};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CPUFREQ_ROOT: &str = "/sys/devices/system/cpu/cpufreq";
+// intel_pstate has its own inverted switch; acpi-cpufreq and amd-pstate use `boost`.
+const NO_TURBO_PATH: &str = "/sys/devices/system/cpu/intel_pstate/no_turbo";
+const BOOST_PATH: &str = "/sys/devices/system/cpu/cpufreq/boost";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuPolicy {
+    pub id: u32,
+    pub path: PathBuf,
+    // The CPUs the policy covers, as the kernel lists them ("0 1 2 3").
+    pub cpus: String,
+    pub driver: Option<String>,
+    pub governor: Option<String>,
+    pub governors: Vec<String>,
+    // Only intel_pstate and amd-pstate in active mode have a preference.
+    pub epp: Option<String>,
+    pub epps: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Turbo {
+    pub path: PathBuf,
+    pub enabled: bool,
+}
+
+pub fn read_policies() -> Vec<CpuPolicy> {
+    let mut policies: Vec<CpuPolicy> = fs::read_dir(CPUFREQ_ROOT)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let id = name.to_str()?.strip_prefix("policy")?.parse().ok()?;
+            Some(read_policy(id, &entry.path()))
+        })
+        .collect();
+    policies.sort_by_key(|policy| policy.id);
+    policies
+}
+
+fn read_policy(id: u32, path: &Path) -> CpuPolicy {
+    let list = |attribute: &str| {
+        read_trimmed(&path.join(attribute))
+            .map(|list| list.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default()
+    };
+    CpuPolicy {
+        id,
+        path: path.to_path_buf(),
+        cpus: read_trimmed(&path.join("related_cpus")).unwrap_or_default(),
+        driver: read_trimmed(&path.join("scaling_driver")),
+        governor: read_trimmed(&path.join("scaling_governor")),
+        governors: list("scaling_available_governors"),
+        epp: read_trimmed(&path.join("energy_performance_preference")),
+        epps: list("energy_performance_available_preferences"),
+    }
+}
+
+pub fn read_turbo() -> Option<Turbo> {
+    if let Some(no_turbo) = read_trimmed(Path::new(NO_TURBO_PATH)) {
+        return Some(Turbo { path: PathBuf::from(NO_TURBO_PATH), enabled: no_turbo == "0" });
+    }
+    let boost = read_trimmed(Path::new(BOOST_PATH))?;
+    Some(Turbo { path: PathBuf::from(BOOST_PATH), enabled: boost == "1" })
+}
+
+pub fn available() -> bool {
+    Path::new(CPUFREQ_ROOT).exists()
+}
+
+// One entry per CPU, at its policy's current frequency. cpuinfo_cur_freq is what the
+// hardware reports but is root-only on most drivers, so scaling_cur_freq (the
+// kernel's last request or its APERF/MPERF estimate) is the fallback.
+pub fn read_frequency_data() -> Result<Vec<SensorSection>, String> {
+    let policies = read_policies();
+    let mut entries: Vec<(u32, SensorEntry)> = Vec::new();
+    for policy in &policies {
+        let khz = |file: &str| {
+            read_trimmed(&policy.path.join(file))
+                .and_then(|raw| raw.parse::<f64>().ok())
+                .map(|khz| khz / 1000.0)
+        };
+        let current = khz("cpuinfo_cur_freq").or_else(|| khz("scaling_cur_freq"));
+        let limits = Limits { min: khz("scaling_min_freq"), max: khz("scaling_max_freq"), ..Limits::default() };
+        for cpu in policy.cpus.split_whitespace().filter_map(|cpu| cpu.parse::<u32>().ok()) {
+            entries.push((cpu, frequency_entry(cpu, current, limits, policy.governor.as_deref())));
+        }
+    }
+    if entries.is_empty() {
+        return Err("No cpufreq policies found".to_string());
+    }
+    entries.sort_by_key(|(cpu, _)| *cpu);
+    let driver = policies.iter().find_map(|policy| policy.driver.clone());
+    Ok(vec![SensorSection {
+        name: "cpufreq-virtual-0".to_string(),
+        adapter: driver.map_or("Virtual device".to_string(), |driver| format!("Virtual device ({})", driver)),
+        entries: entries.into_iter().map(|(_, entry)| entry).collect(),
+        unparsed: Vec::new(),
+        label: None,
+    }])
+}
+
+fn frequency_entry(cpu: u32, mhz: Option<f64>, limits: Limits, governor: Option<&str>) -> SensorEntry {
+    let mut entry = reading_entry(&format!("cpu{}", cpu), mhz, "MHz", limits);
+    if let Some(governor) = governor {
+        entry.additional_info = Some(match entry.additional_info {
+            Some(info) => format!("{}, {}", info, governor),
+            None => governor.to_string(),
+        });
+    }
+    entry
+}
+
+// "0-3, 8" style summary of a policy's CPUs, for labels.
+pub fn cpu_ranges(cpus: &str) -> String {
+    let mut numbers: Vec<u32> = cpus.split_whitespace().filter_map(|cpu| cpu.parse().ok()).collect();
+    numbers.sort_unstable();
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for cpu in numbers {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == cpu => *end = cpu,
+            _ => ranges.push((cpu, cpu)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|&(start, end)| if start == end { start.to_string() } else { format!("{}-{}", start, end) })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn set_governor(policy: &CpuPolicy, governor: &str) -> Result<(), String> {
+    write_attribute(&policy.path.join("scaling_governor"), governor)
+}
+
+// The kernel refuses a preference (EBUSY) while the performance governor is active.
+pub fn set_epp(policy: &CpuPolicy, epp: &str) -> Result<(), String> {
+    write_attribute(&policy.path.join("energy_performance_preference"), epp)
+}
+
+pub fn set_turbo(turbo: &Turbo, enabled: bool) -> Result<(), String> {
+    let inverted = turbo.path == Path::new(NO_TURBO_PATH);
+    write_attribute(&turbo.path, if enabled != inverted { "1" } else { "0" })
+}
+
+fn write_attribute(path: &Path, value: &str) -> Result<(), String> {
+    match fs::write(path, value) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => helper::write(path, value),
+        Err(e) => Err(format!("Failed to write {}: {}", path.display(), e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_cpu_lists() {
+        assert_eq!(cpu_ranges("0 1 2 3"), "0-3");
+        assert_eq!(cpu_ranges("8 0 1 5"), "0-1, 5, 8");
+        assert_eq!(cpu_ranges(""), "");
+    }
+
+    #[test]
+    fn notes_the_governor_next_to_the_limits() {
+        let limits = Limits { min: Some(800.0), max: Some(4200.0), ..Limits::default() };
+        let entry = frequency_entry(3, Some(3612.5), limits, Some("powersave"));
+        assert_eq!((entry.key.as_str(), entry.number), ("cpu3", Some(3612.5)));
+        assert_eq!(entry.additional_info.as_deref(), Some("min = 800.00 MHz, max = 4200.00 MHz, powersave"));
+    }
+}
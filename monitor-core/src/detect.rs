@@ -0,0 +1,263 @@
+// Running `sensors-detect` and reading back what it found: the drivers for each
+// detected chip and the modules it says to load. Also guesses, without probing, which
+// drivers are missing for hardware that usually has sensors.
+use crate::hwmon::{read_trimmed, HWMON_ROOT};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detection {
+    pub drivers: Vec<DetectedDriver>,
+    // Modules to load, from the "#----cut here----" block; autoloaded drivers are
+    // not listed.
+    pub modules: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedDriver {
+    pub name: String,
+    // The kernel loads it by itself (e.g. k10temp, coretemp).
+    pub autoloaded: bool,
+    pub chips: Vec<String>,
+}
+
+// `sensors-detect --auto` as root, taking the default answer to every question;
+// those skip the riskier ISA and I2C probes and never write any file.
+pub fn run_sensors_detect() -> Result<String, String> {
+    let output = Command::new("pkexec")
+        .args(["sensors-detect", "--auto"])
+        .output()
+        .map_err(|e| format!("Failed to run pkexec sensors-detect: {}", e))?;
+    match output.status.code() {
+        Some(0) => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        // pkexec's own exit codes.
+        Some(126) => Err("Authorization was refused; sensors-detect did not run".to_string()),
+        Some(127) => Err("sensors-detect is not installed, or authorization failed".to_string()),
+        _ => Err(format!(
+            "sensors-detect failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+pub fn parse_sensors_detect(output: &str) -> Detection {
+    let mut drivers: Vec<DetectedDriver> = Vec::new();
+    let mut modules = Vec::new();
+    let mut in_cut = false;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed == "#----cut here----" {
+            in_cut = !in_cut;
+            continue;
+        }
+        if in_cut {
+            if !trimmed.is_empty() && !trimmed.starts_with('#') {
+                modules.push(trimmed.to_string());
+            }
+            continue;
+        }
+        // Driver `nct6775':   /   Driver `k10temp' (autoloaded):
+        if let Some(rest) = trimmed.strip_prefix("Driver `") {
+            if let Some((name, tail)) = rest.split_once('\'') {
+                if tail.trim_end().ends_with(':') {
+                    drivers.push(DetectedDriver {
+                        name: name.to_string(),
+                        autoloaded: tail.contains("(autoloaded)"),
+                        chips: Vec::new(),
+                    });
+                }
+            }
+            continue;
+        }
+        //     Chip `Nuvoton NCT6779D Super IO Sensors' (confidence: 9)
+        let chip = trimmed.strip_prefix("* Chip `").or_else(|| trimmed.strip_prefix("Chip `"));
+        if let (Some(chip), Some(driver)) = (chip, drivers.last_mut()) {
+            if let Some((chip, _)) = chip.split_once('\'') {
+                driver.chips.push(chip.to_string());
+            }
+        }
+    }
+    Detection { drivers, modules }
+}
+
+// What /proc/cpuinfo, DMI and PCI say about the machine.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Hardware {
+    pub cpu_vendor: String,
+    pub cpu_family: u32,
+    pub board_vendor: String,
+    // (vendor, class) of every PCI device.
+    pub pci: Vec<(u16, u32)>,
+}
+
+impl Hardware {
+    pub fn read() -> Self {
+        let cpuinfo = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+        let field = |name: &str| {
+            cpuinfo
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .find(|(key, _)| key.trim() == name)
+                .map(|(_, value)| value.trim().to_string())
+                .unwrap_or_default()
+        };
+        let mut pci = Vec::new();
+        for device in fs::read_dir("/sys/bus/pci/devices").into_iter().flatten().flatten() {
+            let hex = |attribute: &str| {
+                read_trimmed(&device.path().join(attribute))
+                    .and_then(|raw| u32::from_str_radix(raw.trim_start_matches("0x"), 16).ok())
+            };
+            if let (Some(vendor), Some(class)) = (hex("vendor"), hex("class")) {
+                pci.push((vendor as u16, class));
+            }
+        }
+        Hardware {
+            cpu_vendor: field("vendor_id"),
+            cpu_family: field("cpu family").parse().unwrap_or(0),
+            board_vendor: read_trimmed(Path::new("/sys/class/dmi/id/board_vendor")).unwrap_or_default(),
+            pci,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingDriver {
+    pub module: &'static str,
+    pub reason: String,
+}
+
+const PCI_VENDOR_AMD: u16 = 0x1002;
+const PCI_CLASS_DISPLAY: u32 = 0x03;
+const PCI_CLASS_NVME: u32 = 0x010802;
+
+// Chip name prefixes of the Super I/O and board drivers; any of them means the
+// board's sensors are already covered.
+const BOARD_CHIPS: [&str; 6] = ["nct6", "it8", "asus", "f71", "w83", "dell_smm"];
+
+// Drivers this hardware usually needs, minus those whose chips already show up in
+// hwmon.
+pub fn suggest_drivers(hardware: &Hardware, chips: &[String]) -> Vec<MissingDriver> {
+    let has_chip = |prefix: &str| chips.iter().any(|chip| chip.starts_with(prefix));
+    let mut missing = Vec::new();
+    let mut suggest = |module, reason: String| missing.push(MissingDriver { module, reason });
+
+    // The CPU's own driver, and the chips that cover its temperatures.
+    let cpu = match hardware.cpu_vendor.as_str() {
+        "AuthenticAMD" | "HygonGenuine" if hardware.cpu_family >= 0x10 => Some((
+            "k10temp",
+            &["k10temp", "zenpower"][..],
+            format!("AMD family {:x}h CPU without temperature readings", hardware.cpu_family),
+        )),
+        "AuthenticAMD" if hardware.cpu_family == 0xf => {
+            Some(("k8temp", &["k8temp"][..], "AMD K8 CPU without temperature readings".to_string()))
+        }
+        "GenuineIntel" => Some(("coretemp", &["coretemp"][..], "Intel CPU without core temperatures".to_string())),
+        _ => None,
+    };
+    if let Some((module, covered_by, reason)) = cpu {
+        if !covered_by.iter().any(|prefix| has_chip(prefix)) {
+            suggest(module, reason);
+        }
+    }
+
+    if !BOARD_CHIPS.iter().any(|prefix| has_chip(prefix)) {
+        let board = hardware.board_vendor.as_str();
+        // What the boards of each vendor mostly carry; sensors-detect can confirm.
+        if ["ASUSTeK", "ASRock", "Micro-Star"].iter().any(|vendor| board.starts_with(vendor)) {
+            suggest("nct6775", format!("{} boards usually have a Nuvoton Super I/O sensor chip", board));
+        } else if board.starts_with("Gigabyte") {
+            suggest("it87", format!("{} boards usually have an ITE Super I/O sensor chip", board));
+        }
+    }
+
+    let has_device = |vendor: Option<u16>, class: u32, shift: u32| {
+        hardware
+            .pci
+            .iter()
+            .any(|(v, c)| vendor.is_none_or(|vendor| vendor == *v) && c >> shift == class)
+    };
+    if has_device(Some(PCI_VENDOR_AMD), PCI_CLASS_DISPLAY, 16) && !has_chip("amdgpu") && !has_chip("radeon") {
+        suggest("amdgpu", "AMD graphics card without GPU sensors".to_string());
+    }
+    if has_device(None, PCI_CLASS_NVME, 0) && !has_chip("nvme") {
+        suggest("nvme", "NVMe drive without a temperature sensor".to_string());
+    }
+    missing
+}
+
+// Suggestions for this machine; modules that are already loaded are left out, since
+// loading them again would not help.
+pub fn find_missing_drivers() -> Vec<MissingDriver> {
+    let chips: Vec<String> = fs::read_dir(HWMON_ROOT)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|chip| read_trimmed(&chip.path().join("name")))
+        .collect();
+    suggest_drivers(&Hardware::read(), &chips)
+        .into_iter()
+        .filter(|missing| !Path::new("/sys/module").join(missing.module).exists())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_summary() {
+        let detection = parse_sensors_detect(include_str!("testdata/sensors-detect-3.6.0-auto.txt"));
+        assert_eq!(
+            detection.drivers,
+            [
+                DetectedDriver {
+                    name: "nct6775".to_string(),
+                    autoloaded: false,
+                    chips: vec!["Nuvoton NCT6779D Super IO Sensors".to_string()],
+                },
+                DetectedDriver {
+                    name: "k10temp".to_string(),
+                    autoloaded: true,
+                    chips: vec!["AMD Family 17h thermal sensors".to_string()],
+                },
+            ]
+        );
+        assert_eq!(detection.modules, ["nct6775"]);
+    }
+
+    #[test]
+    fn finds_nothing_without_a_summary() {
+        let detection = parse_sensors_detect("Sorry, no sensors were detected.\n");
+        assert!(detection.drivers.is_empty());
+        assert!(detection.modules.is_empty());
+    }
+
+    #[test]
+    fn suggests_missing_drivers() {
+        let ryzen = Hardware {
+            cpu_vendor: "AuthenticAMD".to_string(),
+            cpu_family: 0x19,
+            board_vendor: "ASUSTeK COMPUTER INC.".to_string(),
+            pci: vec![(0x1002, 0x030000), (0x144d, 0x010802)],
+        };
+        let modules = |chips: &[&str]| {
+            let chips: Vec<String> = chips.iter().map(|chip| chip.to_string()).collect();
+            suggest_drivers(&ryzen, &chips).into_iter().map(|missing| missing.module).collect::<Vec<_>>()
+        };
+        assert_eq!(modules(&[]), ["k10temp", "nct6775", "amdgpu", "nvme"]);
+        assert_eq!(modules(&["k10temp", "nct6798", "amdgpu", "nvme"]), Vec::<&str>::new());
+        assert_eq!(modules(&["zenpower", "asusec", "nvme", "amdgpu"]), Vec::<&str>::new());
+
+        let intel = Hardware {
+            cpu_vendor: "GenuineIntel".to_string(),
+            cpu_family: 6,
+            board_vendor: "Gigabyte Technology Co., Ltd.".to_string(),
+            pci: Vec::new(),
+        };
+        let missing = suggest_drivers(&intel, &["acpitz".to_string()]);
+        assert_eq!(missing.iter().map(|missing| missing.module).collect::<Vec<_>>(), ["coretemp", "it87"]);
+    }
+}
@@ -0,0 +1,111 @@
+// Per-disk throughput and IOPS, from the growth of the /proc/diskstats counters
+// between refreshes. The first read after startup has nothing to compare with and
+// shows N/A.
+use crate::counters::Counters;
+use crate::hwmon::reading_entry;
+use crate::model::{Limits, SensorSection};
+use crate::smart;
+use std::fs;
+use std::sync::Mutex;
+use std::time::Instant;
+
+const DISKSTATS_PATH: &str = "/proc/diskstats";
+// diskstats counts 512-byte sectors whatever the disk's own sector size.
+const SECTOR_BYTES: u64 = 512;
+
+static SAMPLES: Mutex<Counters> = Mutex::new(Counters::new());
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiskCounters {
+    pub name: String,
+    pub reads: u64,
+    pub sectors_read: u64,
+    pub writes: u64,
+    pub sectors_written: u64,
+}
+
+pub fn read_disk_io_data() -> Result<Vec<SensorSection>, String> {
+    let stats = fs::read_to_string(DISKSTATS_PATH).map_err(|e| format!("Failed to read {}: {}", DISKSTATS_PATH, e))?;
+    // Whole disks only; partitions would count the same I/O twice.
+    let drives = smart::drives();
+    let disks: Vec<DiskCounters> = parse_diskstats(&stats).into_iter().filter(|disk| drives.contains(&disk.name)).collect();
+    if disks.is_empty() {
+        return Err("No disks in /proc/diskstats".to_string());
+    }
+
+    let now = Instant::now();
+    let mut samples = SAMPLES.lock().map_err(|_| "Disk I/O samples are unavailable".to_string())?;
+    let mut rate = |disk: &str, counter: &str, value: u64| samples.rate(&format!("{}/{}", disk, counter), value, now);
+    Ok(disks
+        .iter()
+        .map(|disk| {
+            let bytes = |sectors: u64| sectors * SECTOR_BYTES;
+            let read = rate(&disk.name, "read", bytes(disk.sectors_read)).map(|rate| rate / 1e6);
+            let written = rate(&disk.name, "written", bytes(disk.sectors_written)).map(|rate| rate / 1e6);
+            let iops = rate(&disk.name, "ios", disk.reads + disk.writes);
+            SensorSection {
+                name: format!("io-{}", disk.name),
+                adapter: "Block device".to_string(),
+                entries: vec![
+                    reading_entry("Read", read, "MB/s", Limits::default()),
+                    reading_entry("Write", written, "MB/s", Limits::default()),
+                    reading_entry("IOPS", iops, "IOPS", Limits::default()),
+                ],
+                unparsed: Vec::new(),
+                label: None,
+            }
+        })
+        .collect())
+}
+
+// "   8       0 sda 184362 51355 11265419 71116 280453 ..." — reads completed, merged,
+// sectors read and ms reading come first, then the same four for writes.
+pub fn parse_diskstats(stats: &str) -> Vec<DiskCounters> {
+    stats
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let number = |i: usize| fields.get(i)?.parse::<u64>().ok();
+            Some(DiskCounters {
+                name: fields.get(2)?.to_string(),
+                reads: number(3)?,
+                sectors_read: number(5)?,
+                writes: number(7)?,
+                sectors_written: number(9)?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_diskstats() {
+        let stats = "\
+ 259       0 nvme0n1 184362 51355 11265419 71116 280453 183322 18757156 246244 0 166896 333365 0 0 0 0 21493 16004
+ 259       1 nvme0n1p1 312 1104 12016 88 2 0 2 0 0 96 88 0 0 0 0 0 0
+   8      16 sdb broken
+";
+        assert_eq!(
+            parse_diskstats(stats),
+            vec![
+                DiskCounters {
+                    name: "nvme0n1".to_string(),
+                    reads: 184362,
+                    sectors_read: 11265419,
+                    writes: 280453,
+                    sectors_written: 18757156,
+                },
+                DiskCounters {
+                    name: "nvme0n1p1".to_string(),
+                    reads: 312,
+                    sectors_read: 12016,
+                    writes: 2,
+                    sectors_written: 2,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,118 @@
+// Embedded controller registers, decoded with a map the user writes, for laptops
+// whose fans and temperatures never reach hwmon. The EC's 256-byte register space is
+// read through ec_sys (`modprobe ec_sys`, then debugfs) or the out-of-tree acpi_ec
+// module's /dev/ec; both are root-only.
+use crate::hwmon::reading_entry;
+use crate::model::{Limits, SensorSection};
+use std::fs;
+
+const EC_DEVICES: [&str; 2] = ["/sys/kernel/debug/ec/ec0/io", "/dev/ec"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Width {
+    U8,
+    S8,
+    // Two registers, low byte first, or high byte first ("be").
+    U16,
+    U16Be,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Register {
+    pub name: String,
+    pub offset: u8,
+    pub width: Width,
+    pub unit: String,
+    // The decoded number is multiplied by this, e.g. 0.5 for half-degree steps.
+    pub scale: f64,
+}
+
+impl Register {
+    // "<offset> <u8|s8|u16|u16be> [<unit>] [<scale>]": "0x58 u8 °C", "0x84 u16be RPM".
+    pub fn parse(name: &str, value: &str) -> Option<Register> {
+        let mut words = value.split_whitespace();
+        let offset = words.next()?;
+        let offset = match offset.strip_prefix("0x") {
+            Some(hex) => u8::from_str_radix(hex, 16).ok()?,
+            None => offset.parse().ok()?,
+        };
+        let width = match words.next()? {
+            "u8" => Width::U8,
+            "s8" => Width::S8,
+            "u16" => Width::U16,
+            "u16be" => Width::U16Be,
+            _ => return None,
+        };
+        let unit = words.next().unwrap_or("").to_string();
+        let scale = match words.next() {
+            Some(scale) => scale.parse().ok()?,
+            None => 1.0,
+        };
+        Some(Register { name: name.to_string(), offset, width, unit, scale })
+    }
+
+    pub fn decode(&self, registers: &[u8]) -> Option<f64> {
+        let byte = |i: usize| registers.get(i).copied();
+        let at = self.offset as usize;
+        let raw = match self.width {
+            Width::U8 => byte(at)? as f64,
+            Width::S8 => byte(at)? as i8 as f64,
+            Width::U16 => u16::from_le_bytes([byte(at)?, byte(at + 1)?]) as f64,
+            Width::U16Be => u16::from_be_bytes([byte(at)?, byte(at + 1)?]) as f64,
+        };
+        Some(raw * self.scale)
+    }
+}
+
+pub fn read_ec_data(registers: &[Register]) -> Result<Vec<SensorSection>, String> {
+    if registers.is_empty() {
+        return Err("No EC registers configured".to_string());
+    }
+    let mut errors = Vec::new();
+    for device in EC_DEVICES {
+        match fs::read(device) {
+            Ok(space) => return Ok(vec![ec_section(registers, &space)]),
+            Err(e) => errors.push(format!("{}: {}", device, e)),
+        }
+    }
+    Err(format!("Failed to read the embedded controller ({}); load ec_sys and run as root", errors.join(", ")))
+}
+
+pub fn ec_section(registers: &[Register], space: &[u8]) -> SensorSection {
+    SensorSection {
+        name: "ec-acpi-0".to_string(),
+        adapter: "Embedded controller".to_string(),
+        entries: registers
+            .iter()
+            .map(|register| reading_entry(&register.name, register.decode(space), &register.unit, Limits::default()))
+            .collect(),
+        unparsed: Vec::new(),
+        label: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_mapped_registers() {
+        let mut space = [0u8; 256];
+        space[0x58] = 61;
+        space[0x84] = 0x0b;
+        space[0x85] = 0xb8;
+        space[0x90] = 0xfb;
+        let registers: Vec<Register> = [("CPU", "0x58 u8 °C"), ("Fan", "0x84 u16be RPM"), ("Offset", "144 s8 °C 0.5")]
+            .iter()
+            .map(|(name, value)| Register::parse(name, value).unwrap())
+            .collect();
+        let section = ec_section(&registers, &space);
+        let numbers: Vec<Option<f64>> = section.entries.iter().map(|entry| entry.number).collect();
+        assert_eq!(numbers, [Some(61.0), Some(3000.0), Some(-2.5)]);
+
+        assert_eq!(Register::parse("x", "0x100 u8"), None);
+        assert_eq!(Register::parse("x", "0x10 u32"), None);
+        let last = Register { offset: 0xff, ..Register::parse("x", "0 u16").unwrap() };
+        assert_eq!(last.decode(&space), None);
+    }
+}
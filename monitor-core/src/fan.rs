@@ -0,0 +1,674 @@
+// Fan control: the fans a driver lets us set, through /sys/class/hwmon or NVML, and
+// the temperature-to-duty curves the app drives them with.
+use crate::helper;
+use crate::hwmon::{chip_name, numbered, read_trimmed, HWMON_ROOT};
+#[cfg(unix)]
+use crate::nvml;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+// `pwmN_enable` values: 1 hands the duty cycle to userspace; 2 and up are the
+// driver's automatic modes, 0 runs the fan at full speed. NVIDIA fans map their
+// NVML policy onto the first two.
+pub const MODE_MANUAL: u8 = 1;
+pub const MODE_AUTOMATIC: u8 = 2;
+
+// hwmon drivers of graphics cards.
+const GPU_DRIVERS: [&str; 3] = ["amdgpu", "radeon", "nouveau"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FanOutput {
+    // The `pwmN` attribute of an hwmon chip.
+    Hwmon(PathBuf),
+    // Fan `fan` of NVIDIA GPU `gpu`, in NVML's numbering.
+    Nvml { gpu: u32, fan: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PwmFan {
+    // "<chip>/pwmN", with the chip named as the hwmon backend names its section, or
+    // "nvidia-gpuN/fanM".
+    pub id: String,
+    // The matching fan input's label ("CPU_FAN"), "pwmN" without one, or the GPU name.
+    pub label: String,
+    // Key of the fan's RPM reading within its section, e.g. "fan2".
+    pub tach: Option<String>,
+    pub output: FanOutput,
+    pub gpu: bool,
+    // Percent of full speed.
+    pub duty: Option<f64>,
+    pub mode: Option<u8>,
+}
+
+impl PwmFan {
+    pub fn duty_percent(&self) -> Option<f64> {
+        self.duty
+    }
+
+    pub fn is_manual(&self) -> bool {
+        self.mode == Some(MODE_MANUAL)
+    }
+
+    pub fn section(&self) -> &str {
+        self.id.rsplit_once('/').map_or(&self.id, |(chip, _)| chip)
+    }
+
+    fn enable_path(path: &Path) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push("_enable");
+        path.with_file_name(name)
+    }
+
+    pub fn set_mode(&self, mode: u8) -> Result<(), String> {
+        match &self.output {
+            FanOutput::Hwmon(path) => write_attribute(&PwmFan::enable_path(path), &mode.to_string()),
+            // Setting a speed switches NVML to manual by itself.
+            FanOutput::Nvml { .. } if mode == MODE_MANUAL => Ok(()),
+            FanOutput::Nvml { gpu, fan } => set_nvml(*gpu, *fan, None),
+        }
+    }
+
+    pub fn set_duty_percent(&self, percent: f64) -> Result<(), String> {
+        match &self.output {
+            FanOutput::Hwmon(path) => write_attribute(path, &raw_duty(percent).to_string()),
+            FanOutput::Nvml { gpu, fan } => set_nvml(*gpu, *fan, Some(percent.clamp(0.0, 100.0).round() as u32)),
+        }
+    }
+
+    // Starts a process that puts the fan back in `mode` once this one is gone, so a
+    // crash or kill -9 can not leave it parked at a low duty cycle. Writes that went
+    // through the privileged helper, NVML's included, are restored by the helper
+    // itself, so NVIDIA fans need no watchdog here.
+    pub fn watchdog(&self, mode: u8) -> Result<Option<Watchdog>, String> {
+        let FanOutput::Hwmon(path) = &self.output else {
+            return Ok(None);
+        };
+        Command::new("sh")
+            .arg("-c")
+            .arg(r#"while kill -0 "$1" 2>/dev/null; do sleep 1; done; printf %s "$2" > "$3""#)
+            .arg("sh")
+            .arg(std::process::id().to_string())
+            .arg(mode.to_string())
+            .arg(PwmFan::enable_path(path))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map(|child| Some(Watchdog(child)))
+            .map_err(|e| format!("Failed to start the fan watchdog: {}", e))
+    }
+}
+
+#[derive(Debug)]
+pub struct Watchdog(Child);
+
+impl Watchdog {
+    // For when the fan was handed back normally.
+    pub fn disarm(mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+// The fans the app has switched to manual, with the mode each goes back to and the
+// watchdog that restores it if the app dies first.
+#[derive(Debug, Default)]
+pub struct Takeover {
+    restore: HashMap<String, u8>,
+    watchdogs: HashMap<String, Watchdog>,
+}
+
+impl Takeover {
+    // Remembers the mode `fan` is in before the app first drives it. A fan found
+    // already in manual (left there by a crashed run) goes back to the driver.
+    pub fn take_over(&mut self, fan: &PwmFan) -> Result<(), String> {
+        if self.restore.contains_key(&fan.id) {
+            return Ok(());
+        }
+        let mode = fan.mode.filter(|&mode| mode != MODE_MANUAL).unwrap_or(MODE_AUTOMATIC);
+        self.restore.insert(fan.id.clone(), mode);
+        if let Some(watchdog) = fan.watchdog(mode)? {
+            self.watchdogs.insert(fan.id.clone(), watchdog);
+        }
+        Ok(())
+    }
+
+    // Takes each fan over and sets its duty, in percent; fans that are gone are
+    // skipped. Returns the outcome per fan that was written.
+    pub fn drive(&mut self, fans: &[PwmFan], duties: Vec<(String, f64)>) -> Vec<(String, Result<(), String>)> {
+        let mut results = Vec::new();
+        for (id, duty) in duties {
+            let Some(fan) = fans.iter().find(|fan| fan.id == id) else {
+                continue;
+            };
+            let taken = self.take_over(fan);
+            let manual = if fan.is_manual() { Ok(()) } else { fan.set_mode(MODE_MANUAL) };
+            results.push((id, taken.and(manual).and_then(|_| fan.set_duty_percent(duty))));
+        }
+        results
+    }
+
+    // Hands the fan back to the mode it had before the app took it over.
+    pub fn release(&mut self, fans: &[PwmFan], id: &str) -> Result<(), String> {
+        let mode = self.restore.remove(id).unwrap_or(MODE_AUTOMATIC);
+        if let Some(watchdog) = self.watchdogs.remove(id) {
+            watchdog.disarm();
+        }
+        match fans.iter().find(|fan| fan.id == id) {
+            Some(fan) => fan.set_mode(mode),
+            None => Ok(()),
+        }
+    }
+
+    pub fn restore_mode(&self, id: &str) -> Option<u8> {
+        self.restore.get(id).copied()
+    }
+
+    pub fn taken(&self) -> Vec<String> {
+        self.restore.keys().cloned().collect()
+    }
+}
+
+pub fn find_fans() -> Vec<PwmFan> {
+    let mut fans = find_hwmon_fans();
+    fans.extend(find_nvml_fans());
+    fans
+}
+
+#[cfg(unix)]
+fn find_nvml_fans() -> Vec<PwmFan> {
+    nvml::fans()
+        .into_iter()
+        .map(|fan| PwmFan {
+            id: format!("nvidia-gpu{}/fan{}", fan.gpu, fan.fan),
+            label: if fan.fan == 0 { fan.name } else { format!("{} fan {}", fan.name, fan.fan + 1) },
+            tach: None,
+            output: FanOutput::Nvml { gpu: fan.gpu, fan: fan.fan },
+            gpu: true,
+            duty: fan.speed.map(f64::from),
+            mode: Some(if fan.manual { MODE_MANUAL } else { MODE_AUTOMATIC }),
+        })
+        .collect()
+}
+
+#[cfg(not(unix))]
+fn find_nvml_fans() -> Vec<PwmFan> {
+    Vec::new()
+}
+
+fn find_hwmon_fans() -> Vec<PwmFan> {
+    let Ok(dir) = fs::read_dir(HWMON_ROOT) else {
+        return Vec::new();
+    };
+    let mut chips: Vec<_> = dir.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect();
+    chips.sort();
+
+    let mut fans = Vec::new();
+    for path in chips {
+        let Some(name) = read_trimmed(&path.join("name")) else {
+            continue;
+        };
+        let chip = chip_name(&path, &name);
+        // Only channels with a mode switch can be handed back to the driver.
+        for channel in numbered(&path, "pwm", "_enable") {
+            let pwm = path.join(format!("pwm{}", channel));
+            if !pwm.exists() {
+                continue;
+            }
+            let tach = format!("fan{}", channel);
+            let label = read_trimmed(&path.join(format!("{}_label", tach))).unwrap_or_else(|| format!("pwm{}", channel));
+            fans.push(PwmFan {
+                id: format!("{}/pwm{}", chip, channel),
+                label,
+                tach: Some(tach),
+                gpu: GPU_DRIVERS.contains(&name.as_str()),
+                duty: read_trimmed(&pwm)
+                    .and_then(|raw| raw.parse::<f64>().ok())
+                    .map(|raw| raw * 100.0 / 255.0),
+                mode: read_trimmed(&path.join(format!("pwm{}_enable", channel))).and_then(|raw| raw.parse().ok()),
+                output: FanOutput::Hwmon(pwm),
+            });
+        }
+    }
+    fans
+}
+
+// NVML refuses unprivileged writes, which then go through the helper.
+#[cfg(unix)]
+fn set_nvml(gpu: u32, fan: u32, speed: Option<u32>) -> Result<(), String> {
+    let result = match speed {
+        Some(speed) => nvml::set_speed(gpu, fan, speed),
+        None => nvml::set_default(gpu, fan),
+    };
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) if e.needs_root() => helper::set_nvml(gpu, fan, speed),
+        Err(e) => Err(format!("Failed to set fan {} of GPU {}: {}", fan, gpu, e)),
+    }
+}
+
+#[cfg(not(unix))]
+fn set_nvml(gpu: u32, fan: u32, _speed: Option<u32>) -> Result<(), String> {
+    Err(format!("Fan {} of GPU {} can only be set through NVML on Linux", fan, gpu))
+}
+
+fn raw_duty(percent: f64) -> u8 {
+    (percent.clamp(0.0, 100.0) * 255.0 / 100.0).round() as u8
+}
+
+// The `tempN_input` of the hwmon chip named `section` whose label (or bare name) is
+// `key`, the way both backends key temperatures.
+pub fn find_temperature_input(section: &str, key: &str) -> Option<PathBuf> {
+    let dir = fs::read_dir(HWMON_ROOT).ok()?;
+    dir.filter_map(|entry| entry.ok()).map(|entry| entry.path()).find_map(|path| {
+        let name = read_trimmed(&path.join("name"))?;
+        if chip_name(&path, &name) != section {
+            return None;
+        }
+        numbered(&path, "temp", "_input").into_iter().find_map(|channel| {
+            let base = format!("temp{}", channel);
+            let label = read_trimmed(&path.join(format!("{}_label", base))).unwrap_or_else(|| base.clone());
+            (label == key).then(|| path.join(format!("{}_input", base)))
+        })
+    })
+}
+
+// A named set of curve points for the controlled fans, e.g. "silent". Switching to
+// a profile replaces the points of each fan it covers; sources stay per fan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FanProfile {
+    pub name: String,
+    // Curve points by fan id.
+    pub curves: Vec<(String, Vec<(f64, f64)>)>,
+    // Switch to this profile while the (section, entry) temperature is at or above
+    // the limit.
+    pub trigger: Option<((String, String), f64)>,
+}
+
+impl FanProfile {
+    pub fn new(name: &str) -> FanProfile {
+        FanProfile {
+            name: name.to_string(),
+            curves: Vec::new(),
+            trigger: None,
+        }
+    }
+}
+
+pub const BUILTIN_PROFILES: [&str; 3] = ["silent", "balanced", "performance"];
+
+// How far (°C) a trigger temperature has to drop before its profile lets go, so a
+// reading hovering at the limit does not flip profiles every sample.
+const TRIGGER_HYSTERESIS: f64 = 5.0;
+
+// The profile whose trigger fires, given the one currently triggered: that one
+// holds until its temperature falls below the hysteresis band, otherwise the first
+// profile at or past its limit wins.
+pub fn triggered_profile<'a>(
+    profiles: &'a [FanProfile],
+    current: Option<&str>,
+    temperature: impl Fn(&(String, String)) -> Option<f64>,
+) -> Option<&'a str> {
+    let holding = profiles.iter().find(|profile| Some(profile.name.as_str()) == current).filter(|profile| {
+        profile.trigger.as_ref().is_some_and(|(source, limit)| {
+            temperature(source).is_some_and(|value| value >= limit - TRIGGER_HYSTERESIS)
+        })
+    });
+    holding
+        .or_else(|| {
+            profiles.iter().find(|profile| {
+                profile
+                    .trigger
+                    .as_ref()
+                    .is_some_and(|(source, limit)| temperature(source).is_some_and(|value| value >= *limit))
+            })
+        })
+        .map(|profile| profile.name.as_str())
+}
+
+// One fan of an `/etc/fancontrol` file, with paths relative to /sys/class/hwmon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FancontrolChannel {
+    pub pwm: String,
+    pub temperature: String,
+    pub tach: Option<String>,
+    pub curve: FanCurve,
+}
+
+// A config in the format pwmconfig writes. fancontrol only knows a straight line
+// from MINTEMP/MINSTOP to MAXTEMP/MAXPWM, so curves with more than two points are
+// approximated by their end points. A zero-RPM threshold becomes MINPWM=0 below a
+// MINTEMP of at least the threshold. `devices` are (hwmonN, DEVPATH, DEVNAME).
+pub fn format_fancontrol(interval: u32, devices: &[(String, String, String)], channels: &[FancontrolChannel]) -> String {
+    let assign = |key: &str, values: Vec<String>| format!("{}={}\n", key, values.join(" "));
+    let per_fan = |value: &dyn Fn(&FancontrolChannel) -> Option<String>| {
+        channels
+            .iter()
+            .filter_map(|channel| value(channel).map(|value| format!("{}={}", channel.pwm, value)))
+            .collect::<Vec<_>>()
+    };
+    let first = |channel: &FancontrolChannel| channel.curve.points.first().copied().unwrap_or((0.0, 100.0));
+    let last = |channel: &FancontrolChannel| channel.curve.points.last().copied().unwrap_or((0.0, 100.0));
+
+    let mut config = String::from("# Generated by sensory from its fan curves; the format pwmconfig writes.\n");
+    for channel in channels.iter().filter(|channel| channel.curve.points.len() > 2) {
+        let ((t0, d0), (t1, d1)) = (first(channel), last(channel));
+        config.push_str(&format!(
+            "# {}: approximated by a line from {}°C at {}% to {}°C at {}%\n",
+            channel.pwm, t0, d0, t1, d1
+        ));
+    }
+    config.push_str(&format!("INTERVAL={}\n", interval));
+    config.push_str(&assign("DEVPATH", devices.iter().map(|(hwmon, path, _)| format!("{}={}", hwmon, path)).collect()));
+    config.push_str(&assign("DEVNAME", devices.iter().map(|(hwmon, _, name)| format!("{}={}", hwmon, name)).collect()));
+    config.push_str(&assign("FCTEMPS", per_fan(&|channel| Some(channel.temperature.clone()))));
+    config.push_str(&assign("FCFANS", per_fan(&|channel| channel.tach.clone())));
+    config.push_str(&assign(
+        "MINTEMP",
+        per_fan(&|channel| {
+            let threshold = channel.curve.zero_rpm.unwrap_or(f64::MIN);
+            Some(format!("{:.0}", first(channel).0.max(threshold)))
+        }),
+    ));
+    config.push_str(&assign("MAXTEMP", per_fan(&|channel| Some(format!("{:.0}", last(channel).0)))));
+    // Starting a stopped fan takes more than keeping it turning.
+    config.push_str(&assign(
+        "MINSTART",
+        per_fan(&|channel| Some(raw_duty(first(channel).1).max(raw_duty(40.0)).to_string())),
+    ));
+    config.push_str(&assign("MINSTOP", per_fan(&|channel| Some(raw_duty(first(channel).1).to_string()))));
+    config.push_str(&assign(
+        "MINPWM",
+        per_fan(&|channel| {
+            let duty = if channel.curve.zero_rpm.is_some() { 0.0 } else { first(channel).1 };
+            Some(raw_duty(duty).to_string())
+        }),
+    ));
+    config.push_str(&assign("MAXPWM", per_fan(&|channel| Some(raw_duty(last(channel).1).to_string()))));
+    config
+}
+
+// Resolves saved curves (by fan id) to sysfs paths. Curves whose fan or temperature
+// is not on this machine are listed in the error when nothing could be exported, and
+// as comments otherwise.
+pub fn export_fancontrol(curves: &[(String, FanCurve)]) -> Result<String, String> {
+    let fans = find_fans();
+    let relative = |path: &Path| {
+        let hwmon = path.parent()?.file_name()?.to_string_lossy().into_owned();
+        Some(format!("{}/{}", hwmon, path.file_name()?.to_string_lossy()))
+    };
+
+    let mut channels = Vec::new();
+    let mut devices: Vec<(String, String, String)> = Vec::new();
+    let mut skipped = Vec::new();
+    for (id, curve) in curves {
+        let Some(fan) = fans.iter().find(|fan| fan.id == *id) else {
+            skipped.push(format!("{}: no such fan", id));
+            continue;
+        };
+        let FanOutput::Hwmon(pwm) = &fan.output else {
+            skipped.push(format!("{}: fancontrol only drives hwmon fans", id));
+            continue;
+        };
+        let Some(temperature) = find_temperature_input(&curve.source.0, &curve.source.1) else {
+            skipped.push(format!("{}: {}/{} is not an hwmon temperature", id, curve.source.0, curve.source.1));
+            continue;
+        };
+        for path in [pwm, &temperature] {
+            let Some(dir) = path.parent() else { continue };
+            let hwmon = dir.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            let device = fs::canonicalize(dir.join("device")).ok();
+            let device = device.as_deref().and_then(|device| device.strip_prefix("/sys").ok());
+            if let (Some(device), Some(name)) = (device, read_trimmed(&dir.join("name"))) {
+                if !devices.iter().any(|(known, _, _)| *known == hwmon) {
+                    devices.push((hwmon, device.display().to_string(), name));
+                }
+            }
+        }
+        let tach = fan.tach.as_ref().map(|tach| pwm.with_file_name(format!("{}_input", tach)));
+        channels.push(FancontrolChannel {
+            pwm: relative(pwm).unwrap_or_default(),
+            temperature: relative(&temperature).unwrap_or_default(),
+            tach: tach.filter(|tach| tach.exists()).and_then(|tach| relative(&tach)),
+            curve: curve.clone(),
+        });
+    }
+    if channels.is_empty() {
+        return Err(if skipped.is_empty() {
+            "No fan curves to export".to_string()
+        } else {
+            format!("No fan curve could be exported ({})", skipped.join("; "))
+        });
+    }
+    devices.sort();
+
+    let mut config = format_fancontrol(2, &devices, &channels);
+    for skipped in skipped {
+        config.push_str(&format!("# Skipped {}\n", skipped));
+    }
+    Ok(config)
+}
+
+// Falls back to the pkexec helper when the GUI itself may not write the attribute.
+fn write_attribute(path: &Path, value: &str) -> Result<(), String> {
+    match fs::write(path, value) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => helper::write(path, value),
+        Err(e) => Err(format!("Failed to write {}: {}", path.display(), e)),
+    }
+}
+
+// Duty cycle (percent) as a function of one temperature reading: linear between
+// points, flat beyond the first and last.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FanCurve {
+    // (section, entry) of the temperature that drives the fan.
+    pub source: (String, String),
+    // (°C, duty %), kept sorted by temperature.
+    pub points: Vec<(f64, f64)>,
+    // Below this temperature the fan stops, the way GPUs idle silently.
+    pub zero_rpm: Option<f64>,
+    // Whether the app is driving the fan with this curve.
+    pub enabled: bool,
+}
+
+impl Default for FanCurve {
+    fn default() -> Self {
+        FanCurve {
+            source: (String::new(), String::new()),
+            points: vec![(30.0, 20.0), (50.0, 35.0), (70.0, 70.0), (85.0, 100.0)],
+            zero_rpm: None,
+            enabled: false,
+        }
+    }
+}
+
+impl FanCurve {
+    pub fn duty_at(&self, temperature: f64) -> f64 {
+        let (Some(&first), Some(&last)) = (self.points.first(), self.points.last()) else {
+            return 100.0;
+        };
+        if self.zero_rpm.is_some_and(|threshold| temperature < threshold) {
+            return 0.0;
+        }
+        if temperature <= first.0 {
+            return first.1;
+        }
+        if temperature >= last.0 {
+            return last.1;
+        }
+        self.points
+            .windows(2)
+            .find(|pair| temperature <= pair[1].0)
+            .map(|pair| {
+                let ((t0, d0), (t1, d1)) = (pair[0], pair[1]);
+                if t1 == t0 {
+                    d1
+                } else {
+                    d0 + (d1 - d0) * (temperature - t0) / (t1 - t0)
+                }
+            })
+            .unwrap_or(last.1)
+    }
+
+    pub fn sort(&mut self) {
+        self.points.sort_by(|a, b| a.0.total_cmp(&b.0));
+    }
+
+    // "30:20, 50:35, 70:70", as stored in the config.
+    pub fn format_points(&self) -> String {
+        self.points
+            .iter()
+            .map(|(temperature, duty)| format!("{}:{}", temperature, duty))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn parse_points(text: &str) -> Option<Vec<(f64, f64)>> {
+        let mut points = text
+            .split(',')
+            .map(|point| {
+                let (temperature, duty) = point.split_once(':')?;
+                let temperature: f64 = temperature.trim().parse().ok()?;
+                let duty: f64 = duty.trim().parse().ok()?;
+                (temperature.is_finite() && (0.0..=100.0).contains(&duty)).then_some((temperature, duty))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        (!points.is_empty()).then_some(points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolates_between_points() {
+        let curve = FanCurve::default();
+        assert_eq!(curve.duty_at(10.0), 20.0);
+        assert_eq!(curve.duty_at(40.0), 27.5);
+        assert_eq!(curve.duty_at(70.0), 70.0);
+        assert_eq!(curve.duty_at(120.0), 100.0);
+        let silent = FanCurve { zero_rpm: Some(45.0), ..curve.clone() };
+        assert_eq!(silent.duty_at(44.9), 0.0);
+        assert_eq!(silent.duty_at(50.0), 35.0);
+        assert_eq!(FanCurve { points: Vec::new(), ..curve }.duty_at(40.0), 100.0);
+    }
+
+    #[test]
+    fn records_restore_modes_of_driven_fans() {
+        // Plain files standing in for the pwm attributes, so no hardware or helper is involved.
+        let chip = std::env::temp_dir().join(format!("sensory-takeover-{}", std::process::id()));
+        fs::create_dir_all(&chip).unwrap();
+        let fan = |n: u32, mode: u8| {
+            let path = chip.join(format!("pwm{}", n));
+            fs::write(&path, "102").unwrap();
+            fs::write(PwmFan::enable_path(&path), mode.to_string()).unwrap();
+            PwmFan {
+                id: format!("nct6775-isa-0290/pwm{}", n),
+                label: format!("pwm{}", n),
+                tach: None,
+                output: FanOutput::Hwmon(path),
+                gpu: false,
+                duty: Some(40.0),
+                mode: Some(mode),
+            }
+        };
+        let fans = [fan(1, 5), fan(2, MODE_MANUAL)];
+        let mut takeover = Takeover::default();
+        // A saved, enabled curve drives the fans from the first refresh after startup.
+        let duties = vec![("nct6775-isa-0290/pwm1".to_string(), 100.0), ("nct6775-isa-0290/pwm2".to_string(), 0.0)];
+        let driven = takeover.drive(&fans, duties);
+        assert!(driven.iter().all(|(_, result)| result.is_ok()), "{:?}", driven);
+        assert_eq!(takeover.restore_mode("nct6775-isa-0290/pwm1"), Some(5));
+        assert_eq!(takeover.restore_mode("nct6775-isa-0290/pwm2"), Some(MODE_AUTOMATIC));
+        assert_eq!(read_trimmed(&chip.join("pwm1_enable")).as_deref(), Some("1"));
+        assert_eq!(read_trimmed(&chip.join("pwm1")).as_deref(), Some("255"));
+        assert!(takeover.drive(&fans, vec![("gone/pwm1".to_string(), 50.0)]).is_empty());
+
+        takeover.release(&fans, "nct6775-isa-0290/pwm1").unwrap();
+        assert_eq!(takeover.restore_mode("nct6775-isa-0290/pwm1"), None);
+        assert_eq!(read_trimmed(&chip.join("pwm1_enable")).as_deref(), Some("5"));
+        assert_eq!(takeover.taken(), ["nct6775-isa-0290/pwm2"]);
+        takeover.release(&fans, "nct6775-isa-0290/pwm2").unwrap();
+        let _ = fs::remove_dir_all(&chip);
+    }
+
+    #[test]
+    fn round_trips_points() {
+        let curve = FanCurve::default();
+        assert_eq!(FanCurve::parse_points(&curve.format_points()), Some(curve.points));
+        assert_eq!(FanCurve::parse_points("60:50, 40 : 30"), Some(vec![(40.0, 30.0), (60.0, 50.0)]));
+        assert_eq!(FanCurve::parse_points("40:130"), None);
+        assert_eq!(FanCurve::parse_points(""), None);
+    }
+
+    #[test]
+    fn triggers_profiles_with_hysteresis() {
+        let cpu = ("coretemp-isa-0000".to_string(), "Package id 0".to_string());
+        let profiles = [
+            FanProfile::new("silent"),
+            FanProfile {
+                trigger: Some((cpu.clone(), 80.0)),
+                ..FanProfile::new("performance")
+            },
+        ];
+        let at = |celsius: f64| {
+            let cpu = cpu.clone();
+            move |source: &(String, String)| (*source == cpu).then_some(celsius)
+        };
+        assert_eq!(triggered_profile(&profiles, None, at(79.0)), None);
+        assert_eq!(triggered_profile(&profiles, None, at(80.0)), Some("performance"));
+        assert_eq!(triggered_profile(&profiles, Some("performance"), at(76.0)), Some("performance"));
+        assert_eq!(triggered_profile(&profiles, Some("performance"), at(74.9)), None);
+        assert_eq!(triggered_profile(&profiles, None, |_| None), None);
+    }
+
+    #[test]
+    fn formats_fancontrol() {
+        let devices = [
+            ("hwmon1".to_string(), "devices/platform/coretemp.0".to_string(), "coretemp".to_string()),
+            ("hwmon3".to_string(), "devices/platform/nct6775.656".to_string(), "nct6775".to_string()),
+        ];
+        let channels = [
+            FancontrolChannel {
+                pwm: "hwmon3/pwm1".to_string(),
+                temperature: "hwmon1/temp1_input".to_string(),
+                tach: Some("hwmon3/fan1_input".to_string()),
+                curve: FanCurve::default(),
+            },
+            FancontrolChannel {
+                pwm: "hwmon3/pwm2".to_string(),
+                temperature: "hwmon3/temp1_input".to_string(),
+                tach: None,
+                curve: FanCurve {
+                    points: vec![(40.0, 50.0), (60.0, 100.0)],
+                    zero_rpm: Some(45.0),
+                    ..FanCurve::default()
+                },
+            },
+        ];
+        let config = format_fancontrol(2, &devices, &channels);
+        let lines: Vec<&str> = config.lines().filter(|line| !line.starts_with('#')).collect();
+        assert_eq!(
+            lines,
+            [
+                "INTERVAL=2",
+                "DEVPATH=hwmon1=devices/platform/coretemp.0 hwmon3=devices/platform/nct6775.656",
+                "DEVNAME=hwmon1=coretemp hwmon3=nct6775",
+                "FCTEMPS=hwmon3/pwm1=hwmon1/temp1_input hwmon3/pwm2=hwmon3/temp1_input",
+                "FCFANS=hwmon3/pwm1=hwmon3/fan1_input",
+                "MINTEMP=hwmon3/pwm1=30 hwmon3/pwm2=45",
+                "MAXTEMP=hwmon3/pwm1=85 hwmon3/pwm2=60",
+                "MINSTART=hwmon3/pwm1=102 hwmon3/pwm2=128",
+                "MINSTOP=hwmon3/pwm1=51 hwmon3/pwm2=128",
+                "MINPWM=hwmon3/pwm1=51 hwmon3/pwm2=0",
+                "MAXPWM=hwmon3/pwm1=255 hwmon3/pwm2=255",
+            ]
+        );
+        assert!(config.contains("# hwmon3/pwm1: approximated by a line from 30°C at 20% to 85°C at 100%"));
+    }
+}
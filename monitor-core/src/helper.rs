@@ -0,0 +1,285 @@
+// Root-only sysfs writes and driver loading, done by `sensory-helper` (src/bin/sensory-helper.rs) run
+// through pkexec, so the GUI itself never runs as root. The helper reads one request
+// per line and answers `ok` or `error: <reason>`:
+//   set <path> <value>           a hwmon or cpufreq attribute `check_write` allows
+//   nvml <gpu> <fan> <percent>   an NVIDIA fan's speed, or `default` for its own curve
+//   modprobe <module>            load a sensor driver
+//   boot-modules <module>...     load these drivers at boot (modules-load.d)
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::Mutex;
+
+pub const HELPER_NAME: &str = "sensory-helper";
+// Written by `boot-modules`; systemd-modules-load reads it at boot.
+pub const BOOT_MODULES_PATH: &str = "/etc/modules-load.d/sensory.conf";
+
+struct Helper {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+// Started on the first write that needs it; `Refused` sticks for the session so a
+// dismissed password prompt does not come back every sample.
+enum State {
+    Idle,
+    Running(Helper),
+    Refused,
+}
+
+static HELPER: Mutex<State> = Mutex::new(State::Idle);
+
+// The attributes the helper may write, and the values each takes: fan pwm under
+// /sys/class/hwmon, and the cpufreq governor, energy-performance preference and turbo
+// switches under /sys/devices/system/cpu.
+pub fn check_write(path: &Path, value: &str) -> Result<(), String> {
+    let components: Vec<Component> = path.components().collect();
+    let names: Option<Vec<&str>> = match components.split_first() {
+        Some((Component::RootDir, rest)) => rest
+            .iter()
+            .map(|component| match component {
+                Component::Normal(name) => name.to_str(),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    };
+    match names.as_deref() {
+        Some(["sys", "class", "hwmon", chip, attribute])
+            if chip.strip_prefix("hwmon").is_some_and(is_number) =>
+        {
+            check_pwm(attribute, value)
+        }
+        Some(["sys", "devices", "system", "cpu", "cpufreq", policy, attribute])
+            if policy.strip_prefix("policy").is_some_and(is_number) =>
+        {
+            match *attribute {
+                "scaling_governor" | "energy_performance_preference" if is_word(value) => Ok(()),
+                "scaling_governor" | "energy_performance_preference" => {
+                    Err(format!("{:?} is not a valid value for {}", value, attribute))
+                }
+                _ => Err(format!("{} is not a writable attribute", attribute)),
+            }
+        }
+        Some(["sys", "devices", "system", "cpu", "intel_pstate", "no_turbo"])
+        | Some(["sys", "devices", "system", "cpu", "cpufreq", "boost"]) => match value {
+            "0" | "1" => Ok(()),
+            _ => Err(format!("{:?} is not a valid value for {}", value, path.display())),
+        },
+        _ => Err(format!("{} is not a hwmon or cpufreq attribute", path.display())),
+    }
+}
+
+fn check_pwm(attribute: &str, value: &str) -> Result<(), String> {
+    let range = match attribute.strip_prefix("pwm") {
+        Some(rest) if is_number(rest) => 0..=255,
+        Some(rest) if rest.strip_suffix("_enable").is_some_and(is_number) => 0..=5,
+        _ => return Err(format!("{} is not a writable attribute", attribute)),
+    };
+    match value.parse::<u32>() {
+        Ok(value) if range.contains(&value) => Ok(()),
+        _ => Err(format!("{:?} is not a valid value for {}", value, attribute)),
+    }
+}
+
+// Module names as modprobe takes them; the helper also checks the module is a
+// hardware monitoring, I2C or platform driver before loading it.
+fn check_module(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_' || b == b'-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("{:?} is not a module name", name))
+    }
+}
+
+fn is_number(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit())
+}
+
+// Governor and preference names: "powersave", "balance_performance".
+fn is_word(text: &str) -> bool {
+    !text.is_empty() && text.len() <= 32 && text.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_')
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Request {
+    Set(PathBuf, String),
+    // `None` hands the fan back to the driver.
+    Nvml { gpu: u32, fan: u32, speed: Option<u32> },
+    LoadModule(String),
+    BootModules(Vec<String>),
+}
+
+impl Request {
+    fn line(&self) -> String {
+        match self {
+            Request::Set(path, value) => format!("set {} {}", path.display(), value),
+            Request::Nvml { gpu, fan, speed: Some(speed) } => format!("nvml {} {} {}", gpu, fan, speed),
+            Request::Nvml { gpu, fan, speed: None } => format!("nvml {} {} default", gpu, fan),
+            Request::LoadModule(module) => format!("modprobe {}", module),
+            Request::BootModules(modules) => format!("boot-modules {}", modules.join(" ")),
+        }
+    }
+}
+
+pub fn parse_request(line: &str) -> Result<Request, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+        ["set", path, value] => {
+            let path = PathBuf::from(path);
+            check_write(&path, value)?;
+            Ok(Request::Set(path, value.to_string()))
+        }
+        ["nvml", gpu, fan, speed] => {
+            let (Ok(gpu), Ok(fan)) = (gpu.parse(), fan.parse()) else {
+                return Err(format!("unknown fan {} of GPU {}", fan, gpu));
+            };
+            let speed = match *speed {
+                "default" => None,
+                speed => match speed.parse::<u32>() {
+                    Ok(speed) if speed <= 100 => Some(speed),
+                    _ => return Err(format!("{:?} is not a fan speed", speed)),
+                },
+            };
+            Ok(Request::Nvml { gpu, fan, speed })
+        }
+        ["modprobe", module] => {
+            check_module(module)?;
+            Ok(Request::LoadModule(module.to_string()))
+        }
+        ["boot-modules", modules @ ..] if !modules.is_empty() => {
+            modules.iter().try_for_each(|module| check_module(module))?;
+            Ok(Request::BootModules(modules.iter().map(|module| module.to_string()).collect()))
+        }
+        _ => Err(format!("unknown request {:?}", line.trim())),
+    }
+}
+
+pub fn write(path: &Path, value: &str) -> Result<(), String> {
+    send(&Request::Set(path.to_path_buf(), value.to_string()))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+pub fn set_nvml(gpu: u32, fan: u32, speed: Option<u32>) -> Result<(), String> {
+    send(&Request::Nvml { gpu, fan, speed }).map_err(|e| format!("Failed to set fan {} of GPU {}: {}", fan, gpu, e))
+}
+
+pub fn load_module(module: &str) -> Result<(), String> {
+    send(&Request::LoadModule(module.to_string())).map_err(|e| format!("Failed to load {}: {}", module, e))
+}
+
+pub fn save_boot_modules(modules: &[String]) -> Result<(), String> {
+    send(&Request::BootModules(modules.to_vec())).map_err(|e| format!("Failed to write {}: {}", BOOT_MODULES_PATH, e))
+}
+
+fn send(request: &Request) -> Result<(), String> {
+    let mut state = HELPER.lock().map_err(|_| "The privileged helper is unavailable".to_string())?;
+    if matches!(*state, State::Refused) {
+        return Err("This needs root, and authorization was refused".to_string());
+    }
+    if matches!(*state, State::Idle) {
+        *state = State::Running(spawn()?);
+    }
+    let State::Running(helper) = &mut *state else {
+        unreachable!();
+    };
+
+    let mut reply = String::new();
+    let sent = writeln!(helper.stdin, "{}", request.line()).and_then(|_| helper.stdin.flush());
+    let read = sent.and_then(|_| helper.stdout.read_line(&mut reply));
+    match (read, reply.trim()) {
+        (Ok(_), "ok") => Ok(()),
+        (Ok(_), reply) if reply.starts_with("error: ") => Err(reply["error: ".len()..].to_string()),
+        // pkexec exits without starting the helper when authorization fails.
+        _ => {
+            if let State::Running(mut helper) = std::mem::replace(&mut *state, State::Refused) {
+                let _ = helper.child.wait();
+            }
+            Err("This needs root, and authorization was refused".to_string())
+        }
+    }
+}
+
+fn spawn() -> Result<Helper, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to locate {}: {}", HELPER_NAME, e))?;
+    let helper = exe.with_file_name(HELPER_NAME);
+    let mut child = Command::new("pkexec")
+        .arg(&helper)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run pkexec {}: {}", helper.display(), e))?;
+    let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+        return Err(format!("Failed to talk to {}", HELPER_NAME));
+    };
+    Ok(Helper {
+        child,
+        stdin,
+        stdout: BufReader::new(stdout),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_only_pwm_attributes() {
+        assert!(check_write(Path::new("/sys/class/hwmon/hwmon3/pwm1"), "128").is_ok());
+        assert!(check_write(Path::new("/sys/class/hwmon/hwmon3/pwm2_enable"), "2").is_ok());
+        assert!(check_write(Path::new("/sys/class/hwmon/hwmon3/pwm1"), "256").is_err());
+        assert!(check_write(Path::new("/sys/class/hwmon/hwmon3/pwm1_enable"), "9").is_err());
+        assert!(check_write(Path::new("/sys/class/hwmon/hwmon3/pwm1"), "-1").is_err());
+        assert!(check_write(Path::new("/sys/class/hwmon/hwmon3/temp1_max"), "1").is_err());
+        assert!(check_write(Path::new("/sys/class/hwmon/hwmon3/../../../../etc/pwm1"), "1").is_err());
+        assert!(check_write(Path::new("/sys/class/hwmon/hwmon3/device/pwm1"), "1").is_err());
+        assert!(check_write(Path::new("/etc/pwm1"), "1").is_err());
+        assert!(check_write(Path::new("sys/class/hwmon/hwmon3/pwm1"), "1").is_err());
+    }
+
+    #[test]
+    fn allows_cpufreq_switches() {
+        let policy = Path::new("/sys/devices/system/cpu/cpufreq/policy0");
+        assert!(check_write(&policy.join("scaling_governor"), "powersave").is_ok());
+        assert!(check_write(&policy.join("energy_performance_preference"), "balance_performance").is_ok());
+        assert!(check_write(&policy.join("scaling_governor"), "../x").is_err());
+        assert!(check_write(&policy.join("scaling_max_freq"), "800000").is_err());
+        assert!(check_write(Path::new("/sys/devices/system/cpu/cpufreq/boost"), "1").is_ok());
+        assert!(check_write(Path::new("/sys/devices/system/cpu/intel_pstate/no_turbo"), "2").is_err());
+        assert!(check_write(Path::new("/sys/devices/system/cpu/cpufreq/policyx/scaling_governor"), "powersave").is_err());
+    }
+
+    #[test]
+    fn parses_requests() {
+        assert_eq!(
+            parse_request("set /sys/class/hwmon/hwmon0/pwm1 77\n"),
+            Ok(Request::Set(PathBuf::from("/sys/class/hwmon/hwmon0/pwm1"), "77".to_string()))
+        );
+        assert!(parse_request("set /sys/class/hwmon/hwmon0/pwm1 77 extra").is_err());
+        assert_eq!(parse_request("nvml 0 1 45"), Ok(Request::Nvml { gpu: 0, fan: 1, speed: Some(45) }));
+        assert_eq!(parse_request("nvml 1 0 default"), Ok(Request::Nvml { gpu: 1, fan: 0, speed: None }));
+        assert!(parse_request("nvml 0 0 101").is_err());
+        assert!(parse_request("nvml x 0 50").is_err());
+        assert_eq!(parse_request("modprobe nct6775"), Ok(Request::LoadModule("nct6775".to_string())));
+        assert!(parse_request("modprobe nct6775 force_id=0xd420").is_err());
+        assert!(parse_request("modprobe ../../evil").is_err());
+        assert_eq!(
+            parse_request("boot-modules nct6775 i2c-dev"),
+            Ok(Request::BootModules(vec!["nct6775".to_string(), "i2c-dev".to_string()]))
+        );
+        assert!(parse_request("boot-modules").is_err());
+        let requests = [
+            Request::Nvml { gpu: 2, fan: 0, speed: None },
+            Request::Set(PathBuf::from("/sys/class/hwmon/hwmon1/pwm1_enable"), "2".to_string()),
+        ];
+        for request in requests {
+            assert_eq!(parse_request(&request.line()), Ok(request));
+        }
+        assert!(parse_request("run /bin/sh").is_err());
+        assert!(parse_request("").is_err());
+    }
+}
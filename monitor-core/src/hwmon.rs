@@ -0,0 +1,376 @@
+// Reads sensors straight from /sys/class/hwmon, without lm-sensors installed.
+use crate::{k10temp, laptop, psu, sensors_conf};
+use crate::model::{Limits, SensorEntry, SensorSection, Status, Unit};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub(crate) const HWMON_ROOT: &str = "/sys/class/hwmon";
+
+// Attribute prefixes we understand, with the unit each raw value is scaled to.
+const CHANNEL_KINDS: [(&str, &str, f64); 7] = [
+    ("temp", "°C", 1000.0),
+    ("fan", "RPM", 1.0),
+    ("in", "V", 1000.0),
+    ("power", "W", 1_000_000.0),
+    ("curr", "A", 1000.0),
+    // Meters that count microjoules since the chip was reset.
+    ("energy", "J", 1_000_000.0),
+    // amdgpu exposes its clocks as freqN_input, in Hz.
+    ("freq", "Hz", 1.0),
+];
+
+pub fn read_hwmon_data() -> Result<Vec<SensorSection>, String> {
+    let dir = fs::read_dir(HWMON_ROOT).map_err(|e| format!("Failed to read {}: {}", HWMON_ROOT, e))?;
+
+    let mut chips: Vec<_> = dir.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect();
+    chips.sort_by_key(|path| hwmon_index(path));
+
+    let mut sections: Vec<_> = chips.iter().filter_map(|path| read_chip(path)).collect();
+    laptop::annotate(&mut sections);
+    psu::annotate(&mut sections);
+    k10temp::annotate(&mut sections);
+    if sections.is_empty() {
+        Err("No sensor data found".to_string())
+    } else {
+        Ok(sections)
+    }
+}
+
+fn hwmon_index(path: &Path) -> u32 {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_prefix("hwmon"))
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(u32::MAX)
+}
+
+fn read_chip(path: &Path) -> Option<SensorSection> {
+    let name = chip_name(path, &read_trimmed(&path.join("name"))?);
+    // The labels, ignores and computes `sensors` would apply to this chip.
+    let config = sensors_conf::chip_config(sensors_conf::system_blocks(), &name);
+
+    let mut entries = Vec::new();
+    for (prefix, unit, scale) in CHANNEL_KINDS {
+        for channel in channels(path, prefix) {
+            let base = format!("{}{}", prefix, channel);
+            if config.ignored(&base) {
+                continue;
+            }
+            let key = match config.label(&base) {
+                Some(label) => label.to_string(),
+                None => read_trimmed(&path.join(format!("{}_label", base))).unwrap_or_else(|| base.clone()),
+            };
+            // Power meters without an instantaneous input report an average over
+            // `<base>_average_interval` milliseconds instead.
+            let averaged = prefix == "power" && !path.join(format!("{}_input", base)).exists();
+            let input = if averaged { "average" } else { "input" };
+            let interval = averaged
+                .then(|| read_trimmed(&path.join(format!("{}_average_interval", base))))
+                .flatten()
+                .and_then(|ms| ms.parse::<f64>().ok())
+                .map(|ms| ms / 1000.0);
+            // The input file exists, so a failed read (EIO, ENODATA) is what lm-sensors
+            // prints as N/A.
+            let raw = read_trimmed(&path.join(format!("{}_{}", base, input))).and_then(|raw| raw.parse::<f64>().ok());
+            let limits = read_limits(path, prefix, &base, scale);
+            let limits = computed_limits(limits, |limit| config.compute(&base, limit));
+            let additional_info = describe_limits(&limits, unit);
+            let alarm = read_flag(path, &base, "alarm");
+            let fault = read_flag(path, &base, "fault");
+            let Some(raw) = raw else {
+                entries.push(SensorEntry {
+                    key,
+                    value: "N/A".to_string(),
+                    number: None,
+                    unit: Unit::Unitless,
+                    unavailable: true,
+                    alarm,
+                    fault,
+                    additional_info,
+                    limits,
+                    status: None,
+                    interval,
+                });
+                continue;
+            };
+            let value = config.compute(&base, raw / scale);
+            entries.push(SensorEntry {
+                key,
+                value: format_value(value, unit),
+                number: Some(value),
+                unit: Unit::parse(unit),
+                unavailable: false,
+                alarm,
+                fault,
+                additional_info,
+                limits,
+                status: None,
+                interval,
+            });
+        }
+    }
+
+    // Switch-like attributes that lm-sensors also lists.
+    for channel in numbered(path, "intrusion", "_alarm") {
+        let base = format!("intrusion{}", channel);
+        if config.ignored(&base) {
+            continue;
+        }
+        let status = if read_flag(path, &base, "alarm") { Status::Alarm } else { Status::Ok };
+        entries.push(SensorEntry::from_status(config.label(&base).unwrap_or(&base), status));
+    }
+    if let Some(beep) = read_trimmed(&path.join("beep_enable")).filter(|_| !config.ignored("beep_enable")) {
+        let status = if beep == "1" { Status::Enabled } else { Status::Disabled };
+        entries.push(SensorEntry::from_status("beep_enable", status));
+    }
+
+    Some(SensorSection {
+        name,
+        adapter: adapter_name(path),
+        entries,
+        unparsed: Vec::new(),
+        label: fs::canonicalize(path.join("device")).ok().and_then(|device| drive_label(&device)),
+    })
+}
+
+// Channel numbers present for a prefix, e.g. [1, 2, 3] for temp1_input..temp3_input.
+fn channels(path: &Path, prefix: &str) -> Vec<u32> {
+    let mut found = numbered(path, prefix, "_input");
+    if prefix == "power" {
+        found.extend(numbered(path, prefix, "_average"));
+        found.sort_unstable();
+        found.dedup();
+    }
+    found
+}
+
+// Numbers N of the `<prefix>N<suffix>` attributes in a chip directory.
+pub(crate) fn numbered(path: &Path, prefix: &str, suffix: &str) -> Vec<u32> {
+    let mut found: Vec<u32> = fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file = entry.file_name().into_string().ok()?;
+            let rest = file.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            rest.parse().ok()
+        })
+        .collect();
+    found.sort_unstable();
+    found
+}
+
+// The lm-sensors chip name, with the bus address that tells identical chips apart:
+// "nvme-pci-0400", "nct6775-isa-0290", "jc42-i2c-0-18".
+pub(crate) fn chip_name(path: &Path, name: &str) -> String {
+    let Ok(device) = fs::canonicalize(path.join("device")) else {
+        return format!("{}-virtual-0", name);
+    };
+    let subsystem = fs::read_link(device.join("subsystem"))
+        .ok()
+        .and_then(|link| link.file_name().map(|name| name.to_string_lossy().into_owned()));
+    let device_name = device.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+
+    match subsystem.as_deref() {
+        // NVMe controllers hang off their PCI function.
+        Some("pci") | Some("nvme") => {
+            let address = device
+                .ancestors()
+                .find_map(|dir| dir.file_name().and_then(|n| pci_address(&n.to_string_lossy())));
+            format!("{}-pci-{:04x}", name, address.unwrap_or(0))
+        }
+        Some("platform") => {
+            let id = device_name.rsplit_once('.').and_then(|(_, id)| id.parse::<u32>().ok());
+            format!("{}-isa-{:04x}", name, id.unwrap_or(0))
+        }
+        Some("i2c") => match device_name.split_once('-') {
+            Some((bus, address)) => {
+                let address = u32::from_str_radix(address, 16).unwrap_or(0);
+                format!("{}-i2c-{}-{:02x}", name, bus, address)
+            }
+            None => format!("{}-i2c-0", name),
+        },
+        // drivetemp sits on the disk's host:channel:id:lun.
+        Some("scsi") => {
+            let address: Vec<u32> = device_name.split(':').filter_map(|part| part.parse().ok()).collect();
+            match address[..] {
+                [host, _, id, _] => format!("{}-scsi-{}-{:x}", name, host, id),
+                _ => format!("{}-scsi-0-0", name),
+            }
+        }
+        Some(other) => format!("{}-{}-0", name, other),
+        None => format!("{}-virtual-0", name),
+    }
+}
+
+// The scsi_device behind a "drivetemp-scsi-<host>-<id>" chip; disks are always on
+// channel 0, LUN 0.
+pub(crate) fn scsi_device(chip: &str) -> Option<PathBuf> {
+    let (_, address) = chip.split_once("-scsi-")?;
+    let (host, id) = address.split_once('-')?;
+    let host: u32 = host.parse().ok()?;
+    let id = u32::from_str_radix(id, 16).ok()?;
+    Some(PathBuf::from(format!("/sys/class/scsi_device/{}:0:{}:0/device", host, id)))
+}
+
+// "sda (Samsung SSD 860 EVO 500GB)" for a SCSI device directory with a disk on it.
+pub(crate) fn drive_label(device: &Path) -> Option<String> {
+    let block = fs::read_dir(device.join("block")).ok()?.flatten().next()?.file_name();
+    let block = block.to_string_lossy();
+    Some(match read_trimmed(&device.join("model")).filter(|model| !model.is_empty()) {
+        Some(model) => format!("{} ({})", block, model),
+        None => block.into_owned(),
+    })
+}
+
+// "0000:04:00.0" -> 0x0400, encoded the way libsensors prints PCI chips.
+pub(crate) fn pci_address(name: &str) -> Option<u32> {
+    let mut parts = name.split(':');
+    let domain = u32::from_str_radix(parts.next()?, 16).ok()?;
+    let bus = u32::from_str_radix(parts.next()?, 16).ok()?;
+    let (slot, function) = parts.next()?.split_once('.')?;
+    let slot = u32::from_str_radix(slot, 16).ok()?;
+    let function = u32::from_str_radix(function, 16).ok()?;
+    Some((domain << 16) + (bus << 8) + (slot << 3) + function)
+}
+
+// Mirrors the adapter names lm-sensors prints for each bus.
+fn adapter_name(path: &Path) -> String {
+    let subsystem = fs::read_link(path.join("device/subsystem"))
+        .ok()
+        .and_then(|link| link.file_name().map(|name| name.to_string_lossy().into_owned()));
+
+    match subsystem.as_deref() {
+        Some("pci") | Some("nvme") => "PCI adapter".to_string(),
+        Some("platform") => "ISA adapter".to_string(),
+        Some("acpi") => "ACPI interface".to_string(),
+        Some("scsi") => "SCSI adapter".to_string(),
+        Some("i2c") => read_trimmed(&path.join("device/../name"))
+            .unwrap_or_else(|| "I2C adapter".to_string()),
+        Some(other) => format!("{} adapter", other),
+        None => "Virtual device".to_string(),
+    }
+}
+
+pub fn format_value(value: f64, unit: &str) -> String {
+    match unit {
+        "°C" => format!("{:+.1}°C", value),
+        "RPM" => format!("{:.0} RPM", value),
+        "V" => format!("{:.2} V", value),
+        _ => format!("{:.2} {}", value, unit),
+    }
+}
+
+// An entry for a reading another tool or file reported; `None` shows as N/A.
+pub(crate) fn reading_entry(key: &str, number: Option<f64>, unit: &str, limits: Limits) -> SensorEntry {
+    SensorEntry {
+        key: key.to_string(),
+        value: number.map_or("N/A".to_string(), |number| format_value(number, unit)),
+        number,
+        unit: if number.is_some() { Unit::parse(unit) } else { Unit::Unitless },
+        unavailable: number.is_none(),
+        alarm: false,
+        fault: false,
+        additional_info: describe_limits(&limits, unit),
+        limits,
+        status: None,
+        interval: None,
+    }
+}
+
+fn read_limits(path: &Path, prefix: &str, base: &str, scale: f64) -> Limits {
+    let read = |attribute: &str| {
+        read_trimmed(&path.join(format!("{}_{}", base, attribute)))
+            .and_then(|raw| raw.parse::<f64>().ok())
+            .map(|raw| raw / scale)
+    };
+    temperature_names(prefix, Limits {
+        min: read("min"),
+        max: read("max"),
+        crit: read("crit"),
+        crit_low: read("lcrit"),
+        hyst: read("max_hyst"),
+        crit_hyst: read("crit_hyst"),
+        ..Limits::default()
+    })
+}
+
+// libsensors runs a channel's limits through its compute expression too.
+fn computed_limits(limits: Limits, compute: impl Fn(f64) -> f64) -> Limits {
+    let compute = |limit: Option<f64>| limit.map(&compute);
+    Limits {
+        min: compute(limits.min),
+        max: compute(limits.max),
+        low: compute(limits.low),
+        high: compute(limits.high),
+        crit: compute(limits.crit),
+        crit_low: compute(limits.crit_low),
+        hyst: compute(limits.hyst),
+        crit_hyst: compute(limits.crit_hyst),
+    }
+}
+
+// lm-sensors calls a temperature's min/max "low"/"high"; match it so every backend
+// fills the same fields.
+pub fn temperature_names(prefix: &str, limits: Limits) -> Limits {
+    if prefix != "temp" {
+        return limits;
+    }
+    Limits {
+        min: None,
+        max: None,
+        low: limits.min,
+        high: limits.max,
+        ..limits
+    }
+}
+
+// The details text lm-sensors would print for these limits.
+pub fn describe_limits(limits: &Limits, unit: &str) -> Option<String> {
+    let parts: Vec<String> = [
+        ("low", limits.low),
+        ("high", limits.high),
+        ("min", limits.min),
+        ("max", limits.max),
+        ("crit low", limits.crit_low),
+        ("crit", limits.crit),
+        ("hyst", limits.hyst),
+        ("crit hyst", limits.crit_hyst),
+    ]
+    .into_iter()
+    .filter_map(|(name, limit)| limit.map(|limit| format!("{} = {}", name, format_value(limit, unit))))
+    .collect();
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+// `<base>_alarm` / `<base>_fault` hold 1 while the driver raises the flag.
+fn read_flag(path: &Path, base: &str, flag: &str) -> bool {
+    read_trimmed(&path.join(format!("{}_{}", base, flag))).is_some_and(|value| value == "1")
+}
+
+pub(crate) fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+// Whether `program` is installed, for sources that run one.
+pub(crate) fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_disk_behind_a_drivetemp_chip() {
+        assert_eq!(
+            scsi_device("drivetemp-scsi-0-0"),
+            Some(PathBuf::from("/sys/class/scsi_device/0:0:0:0/device"))
+        );
+        assert_eq!(
+            scsi_device("drivetemp-scsi-5-a"),
+            Some(PathBuf::from("/sys/class/scsi_device/5:0:10:0/device"))
+        );
+        assert_eq!(scsi_device("nvme-pci-0400"), None);
+    }
+}
@@ -0,0 +1,80 @@
+// Intel integrated GPUs: actual and requested frequency from the i915 (or xe) files
+// under /sys/class/drm, and the GPU's power from the RAPL "uncore" zone.
+use crate::hwmon::{read_trimmed, reading_entry};
+use crate::model::{Limits, SensorSection};
+use crate::rapl;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DRM_ROOT: &str = "/sys/class/drm";
+const PCI_VENDOR_INTEL: &str = "0x8086";
+
+// Where a driver keeps each frequency, in MHz: (actual, requested, min, max).
+const I915_FILES: [&str; 4] = ["gt_act_freq_mhz", "gt_cur_freq_mhz", "gt_min_freq_mhz", "gt_max_freq_mhz"];
+const XE_FILES: [&str; 4] = [
+    "device/tile0/gt0/freq0/act_freq",
+    "device/tile0/gt0/freq0/cur_freq",
+    "device/tile0/gt0/freq0/min_freq",
+    "device/tile0/gt0/freq0/max_freq",
+];
+
+// (cardN, its directory, the driver's frequency files) for every Intel card.
+fn cards() -> Vec<(String, PathBuf, &'static [&'static str; 4])> {
+    let mut cards: Vec<_> = fs::read_dir(DRM_ROOT)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            name.strip_prefix("card")?.parse::<u32>().ok()?;
+            let path = entry.path();
+            let intel = read_trimmed(&path.join("device/vendor")).is_some_and(|vendor| vendor == PCI_VENDOR_INTEL);
+            let files = [&I915_FILES, &XE_FILES].into_iter().find(|files| path.join(files[1]).exists())?;
+            intel.then_some((name, path, files))
+        })
+        .collect();
+    cards.sort();
+    cards
+}
+
+pub fn available() -> bool {
+    !cards().is_empty()
+}
+
+pub fn read_intel_gpu_data() -> Result<Vec<SensorSection>, String> {
+    let uncore = rapl::zones().into_iter().find(|zone| zone.name == "uncore");
+    let sections: Vec<SensorSection> = cards()
+        .iter()
+        .map(|(card, path, files)| read_card(card, path, files, uncore.as_ref().map(|zone| zone.path.as_path())))
+        .collect();
+    if sections.is_empty() {
+        Err("No Intel GPUs found".to_string())
+    } else {
+        Ok(sections)
+    }
+}
+
+fn read_card(card: &str, path: &Path, files: &[&str; 4], uncore: Option<&Path>) -> SensorSection {
+    let mhz = |file: &str| read_trimmed(&path.join(file)).and_then(|raw| raw.parse::<f64>().ok());
+    let [actual, requested, min, max] = files.map(mhz);
+    let limits = Limits { min, max, ..Limits::default() };
+
+    let mut entries = vec![
+        reading_entry("Actual frequency", actual, "MHz", limits),
+        reading_entry("Requested frequency", requested, "MHz", limits),
+    ];
+    let address = fs::canonicalize(path.join("device"))
+        .ok()
+        .and_then(|device| device.file_name().map(|name| name.to_string_lossy().into_owned()));
+    // The uncore zone is the integrated GPU at 00:02.0, not a discrete Arc card.
+    if let Some(uncore) = uncore.filter(|_| address.as_deref().is_some_and(|address| address.ends_with("00:02.0"))) {
+        entries.push(reading_entry("GPU power", rapl::power("intel-gpu", uncore), "W", Limits::default()));
+    }
+    SensorSection {
+        name: format!("intel_gpu-{}", card),
+        adapter: address.map_or("PCI adapter".to_string(), |address| format!("PCI adapter {}", address)),
+        entries,
+        unparsed: Vec::new(),
+        label: None,
+    }
+}
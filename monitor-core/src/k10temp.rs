@@ -0,0 +1,92 @@
+// AMD Zen CPUs, through k10temp or zenpower ("k10temp-pci-00c3"): their temperatures
+// are named after the registers ("Tctl", "Tccd1"), listed in driver order. This
+// labels each one, keeps the control and die readings ahead of the per-CCD ones, and
+// adds the hottest CCD, which is what throttles first on multi-die parts.
+use crate::hwmon::reading_entry;
+use crate::model::{Limits, SensorEntry, SensorSection};
+use crate::sysinfo;
+use std::fs;
+use std::sync::OnceLock;
+
+const CHIPS: [&str; 2] = ["k10temp", "zenpower"];
+
+pub fn annotate(sections: &mut [SensorSection]) {
+    for section in sections {
+        let chip = section.name.split('-').next().unwrap_or_default();
+        if CHIPS.contains(&chip) {
+            section.entries = label_dies(std::mem::take(&mut section.entries));
+            if section.label.is_none() {
+                section.label = cpu_model();
+            }
+        }
+    }
+}
+
+fn cpu_model() -> Option<String> {
+    static MODEL: OnceLock<Option<String>> = OnceLock::new();
+    MODEL
+        .get_or_init(|| fs::read_to_string("/proc/cpuinfo").ok().and_then(|cpuinfo| sysinfo::cpu_model(&cpuinfo)))
+        .clone()
+}
+
+pub fn label_dies(entries: Vec<SensorEntry>) -> Vec<SensorEntry> {
+    let mut control = Vec::new();
+    let mut dies: Vec<(u32, SensorEntry)> = Vec::new();
+    let mut others = Vec::new();
+    for mut entry in entries {
+        if let Some(ccd) = entry.key.strip_prefix("Tccd").and_then(|n| n.parse::<u32>().ok()) {
+            entry.key = format!("CCD {} (Tccd{})", ccd, ccd);
+            dies.push((ccd, entry));
+            continue;
+        }
+        match entry.key.as_str() {
+            // Tctl carries a fixed offset on some models (Threadripper: +27 °C); it is
+            // the value the fan control firmware sees rather than a real temperature.
+            "Tctl" => entry.key = "Control (Tctl)".to_string(),
+            "Tdie" => entry.key = "Die (Tdie)".to_string(),
+            _ => {
+                others.push(entry);
+                continue;
+            }
+        }
+        control.push(entry);
+    }
+    dies.sort_by_key(|(ccd, _)| *ccd);
+
+    let hottest = dies
+        .iter()
+        .filter_map(|(ccd, entry)| Some((*ccd, entry.number?)))
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+    let mut labelled = control;
+    // Only worth a row with more than one die to compare.
+    if let Some((ccd, celsius)) = hottest.filter(|_| dies.len() > 1) {
+        let mut entry = reading_entry("Hottest CCD", Some(celsius), "°C", Limits::default());
+        entry.additional_info = Some(format!("CCD {}", ccd));
+        labelled.push(entry);
+    }
+    labelled.extend(dies.into_iter().map(|(_, entry)| entry));
+    labelled.extend(others);
+    labelled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_ccds_and_finds_the_hottest() {
+        let readings = [("Tccd2", 61.5), ("Tctl", 64.0), ("Tccd1", 58.25), ("Tdie", 64.0)];
+        let entries = readings
+            .iter()
+            .map(|&(key, celsius)| reading_entry(key, Some(celsius), "°C", Limits::default()))
+            .collect();
+        let labelled = label_dies(entries);
+        let keys: Vec<&str> = labelled.iter().map(|entry| entry.key.as_str()).collect();
+        assert_eq!(keys, ["Control (Tctl)", "Die (Tdie)", "Hottest CCD", "CCD 1 (Tccd1)", "CCD 2 (Tccd2)"]);
+        assert_eq!(labelled[2].number, Some(61.5));
+        assert_eq!(labelled[2].additional_info.as_deref(), Some("CCD 2"));
+
+        let single = label_dies(vec![reading_entry("Tccd1", Some(50.0), "°C", Limits::default())]);
+        assert_eq!(single.len(), 1);
+    }
+}
@@ -0,0 +1,88 @@
+// Extras for the laptop platform chips, thinkpad_acpi ("thinkpad-isa-0000") and
+// dell-smm ("dell_smm-isa-0000"): names for the ThinkPad's unlabelled temperatures and
+// the fan mode each driver reports. Both backends list the chips the same way, so
+// this runs on their output.
+use crate::hwmon::{numbered, read_trimmed, HWMON_ROOT};
+use crate::model::{SensorEntry, SensorSection};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const THINKPAD_FAN: &str = "/proc/acpi/ibm/fan";
+
+// What thinkpad_acpi's tempN measure on most models (from thinkwiki); the others vary.
+const THINKPAD_TEMPS: [(&str, &str); 7] = [
+    ("temp1", "CPU"),
+    ("temp2", "Mini PCI / APS"),
+    ("temp3", "HDD"),
+    ("temp4", "GPU"),
+    ("temp5", "Battery"),
+    ("temp7", "Battery 2"),
+    ("temp9", "Bay battery"),
+];
+
+pub fn annotate(sections: &mut [SensorSection]) {
+    for section in sections {
+        let chip = section.name.split('-').next().unwrap_or_default();
+        match chip {
+            "thinkpad" => {
+                for entry in &mut section.entries {
+                    if let Some(&(_, label)) = THINKPAD_TEMPS.iter().find(|(key, _)| *key == entry.key) {
+                        entry.key = label.to_string();
+                    }
+                }
+                if let Some(level) = read_trimmed(Path::new(THINKPAD_FAN)).and_then(|fan| fan_level(&fan)) {
+                    section.entries.push(SensorEntry::from_text("Fan mode", &level));
+                }
+            }
+            "dell_smm" | "i8k" => {
+                let Some(path) = chip_path("dell_smm") else { continue };
+                for channel in numbered(&path, "pwm", "_enable") {
+                    let mode = read_trimmed(&path.join(format!("pwm{}_enable", channel)));
+                    if let Some(mode) = mode.as_deref().and_then(dell_fan_mode) {
+                        section.entries.push(SensorEntry::from_text(&format!("Fan {} mode", channel), mode));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn chip_path(name: &str) -> Option<PathBuf> {
+    fs::read_dir(HWMON_ROOT)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| read_trimmed(&path.join("name")).is_some_and(|chip| chip == name))
+}
+
+// /proc/acpi/ibm/fan: "level:\t\tauto", a number 0-7, "disengaged" or "full-speed".
+pub fn fan_level(fan: &str) -> Option<String> {
+    let level = fan.lines().find_map(|line| line.strip_prefix("level:"))?.trim();
+    Some(match level {
+        "auto" => "auto".to_string(),
+        "disengaged" | "full-speed" => "full speed".to_string(),
+        level => format!("level {}", level),
+    })
+}
+
+fn dell_fan_mode(pwm_enable: &str) -> Option<&'static str> {
+    match pwm_enable {
+        "1" => Some("manual"),
+        "2" => Some("auto"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_thinkpad_fan_level() {
+        assert_eq!(fan_level("status:\t\tenabled\nspeed:\t\t2950\nlevel:\t\tauto\n").as_deref(), Some("auto"));
+        assert_eq!(fan_level("level:\t\t3\n").as_deref(), Some("level 3"));
+        assert_eq!(fan_level("level:\t\tdisengaged\n").as_deref(), Some("full speed"));
+        assert_eq!(fan_level("status:\t\tenabled\n"), None);
+    }
+}
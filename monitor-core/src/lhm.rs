@@ -0,0 +1,123 @@
+// Windows, where there is no lm-sensors: the sensors LibreHardwareMonitor publishes
+// in its WMI namespace (root\LibreHardwareMonitor) while it runs, queried through
+// PowerShell. Each hardware node ("/amdcpu/0", "/lpc/nct6798d/0") becomes a section.
+use crate::hwmon::{on_path, reading_entry};
+use crate::json::{self, Json};
+use crate::model::{Limits, SensorSection};
+use std::process::Command;
+use std::time::Duration;
+
+// PowerShell takes hundreds of milliseconds to start, so it runs on a background
+// thread at this interval.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+const QUERY: &str = "$namespace = 'root/LibreHardwareMonitor'; \
+    $hardware = @(Get-CimInstance -Namespace $namespace -ClassName Hardware | Select-Object Identifier, Name); \
+    $sensors = @(Get-CimInstance -Namespace $namespace -ClassName Sensor | \
+        Select-Object Identifier, Name, SensorType, Value, Parent); \
+    @{ hardware = $hardware; sensors = $sensors } | ConvertTo-Json -Compress -Depth 3";
+
+// LibreHardwareMonitor's sensor types, with the unit each is in. Its Min and Max are
+// the lowest and highest values seen, not limits, so they are left out.
+const SENSOR_TYPES: [(&str, &str); 7] = [
+    ("Temperature", "°C"),
+    ("Fan", "RPM"),
+    ("Voltage", "V"),
+    ("Current", "A"),
+    ("Power", "W"),
+    ("Clock", "MHz"),
+    ("Load", "%"),
+];
+
+pub fn available() -> bool {
+    cfg!(windows) && on_path("powershell.exe")
+}
+
+pub fn read_lhm_data() -> Result<Vec<SensorSection>, String> {
+    let output = Command::new("powershell.exe")
+        .args(["-NoProfile", "-NonInteractive", "-Command", QUERY])
+        .output()
+        .map_err(|e| format!("Failed to execute powershell: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to query LibreHardwareMonitor (is it running?): {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    parse_wmi_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+// {"hardware": [{"Identifier": "/amdcpu/0", "Name": "AMD Ryzen 7 5800X"}],
+//  "sensors": [{"Identifier": "/amdcpu/0/temperature/2", "Name": "Core (Tctl/Tdie)",
+//               "SensorType": "Temperature", "Value": 45.5, "Parent": "/amdcpu/0"}]}
+pub fn parse_wmi_output(output: &str) -> Result<Vec<SensorSection>, String> {
+    let root = json::parse(output).map_err(|e| format!("Failed to parse LibreHardwareMonitor output: {}", e))?;
+    let list = |key: &str| match root.get(key) {
+        Some(Json::Array(items)) => items.as_slice(),
+        _ => &[],
+    };
+    let text = |item: &Json, key: &str| item.get(key).and_then(Json::as_str).map(str::to_string);
+
+    let mut sections: Vec<(String, SensorSection)> = Vec::new();
+    for sensor in list("sensors") {
+        let fields = (text(sensor, "Parent"), text(sensor, "Name"), text(sensor, "SensorType"));
+        let (Some(parent), Some(key), Some(kind)) = fields else { continue };
+        let Some(&(_, unit)) = SENSOR_TYPES.iter().find(|(name, _)| *name == kind) else { continue };
+        let entry = reading_entry(&key, sensor.get("Value").and_then(Json::as_f64), unit, Limits::default());
+        match sections.iter_mut().find(|(identifier, _)| *identifier == parent) {
+            Some((_, section)) => section.entries.push(entry),
+            None => {
+                let label = list("hardware")
+                    .iter()
+                    .find(|hardware| text(hardware, "Identifier").as_deref() == Some(parent.as_str()))
+                    .and_then(|hardware| text(hardware, "Name"));
+                let section = SensorSection {
+                    name: section_name(&parent),
+                    adapter: "WMI (LibreHardwareMonitor)".to_string(),
+                    entries: vec![entry],
+                    unparsed: Vec::new(),
+                    label,
+                };
+                sections.push((parent, section));
+            }
+        }
+    }
+    if sections.is_empty() {
+        return Err("LibreHardwareMonitor reported no sensors".to_string());
+    }
+    sections.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(sections.into_iter().map(|(_, section)| section).collect())
+}
+
+// "/lpc/nct6798d/0" -> "lpc_nct6798d-wmi-0", like lm-sensors' chip names.
+fn section_name(identifier: &str) -> String {
+    let mut parts: Vec<&str> = identifier.trim_matches('/').split('/').collect();
+    let index = match parts.last() {
+        Some(last) if last.parse::<u32>().is_ok() => parts.pop().unwrap_or("0"),
+        _ => "0",
+    };
+    format!("{}-wmi-{}", parts.join("_"), index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_sensors_by_hardware() {
+        let output = r#"{"sensors":[
+            {"Name":"Fan #2","SensorType":"Fan","Value":812.0,"Parent":"/lpc/nct6798d/0"},
+            {"Name":"Core (Tctl/Tdie)","SensorType":"Temperature","Value":45.5,"Parent":"/amdcpu/0"},
+            {"Name":"Bus Speed","SensorType":"Factor","Value":1.0,"Parent":"/amdcpu/0"},
+            {"Name":"CPU Total","SensorType":"Load","Value":null,"Parent":"/amdcpu/0"}],
+            "hardware":[{"Identifier":"/amdcpu/0","Name":"AMD Ryzen 7 5800X"}]}"#;
+        let sections = parse_wmi_output(output).unwrap();
+        let names: Vec<&str> = sections.iter().map(|section| section.name.as_str()).collect();
+        assert_eq!(names, ["amdcpu-wmi-0", "lpc_nct6798d-wmi-0"]);
+        assert_eq!(sections[0].label.as_deref(), Some("AMD Ryzen 7 5800X"));
+        assert_eq!(sections[0].entries.len(), 2);
+        assert_eq!((sections[0].entries[0].number, sections[0].entries[0].unit.symbol()), (Some(45.5), "°C"));
+        assert!(sections[0].entries[1].unavailable);
+        assert!(parse_wmi_output(r#"{"hardware":[],"sensors":[]}"#).is_err());
+    }
+}
@@ -0,0 +1,55 @@
+// The sensor model, the readers that produce it, fan control, CPU power settings, RGB lighting and driver
+// setup, free of the GUI so the viewer, the fuzz targets in fuzz/ and other tools can share them.
+//
+// Every reader returns a `source::ReadResult`: one `model::SensorSection` per chip, each a list of
+// `model::SensorEntry` readings with their number, unit and limits. `source::Registry` polls a set of
+// readers and merges what they return; the `lm_sensors::parse_*` functions and their siblings in the
+// other modules parse captured output without running anything. Readers that call into Linux or Unix
+// system interfaces (ioctl, dlopen, sysconf) are only built there, so the rest also builds for Windows.
+pub mod amdgpu;
+pub mod battery;
+pub mod counters;
+pub mod cpufreq;
+pub mod detect;
+pub mod diskstats;
+pub mod ec;
+pub mod fan;
+pub mod helper;
+pub mod hwmon;
+pub mod intel_gpu;
+pub mod json;
+pub mod k10temp;
+pub mod laptop;
+pub mod lhm;
+pub mod liquidctl;
+pub mod lm_sensors;
+pub mod loadavg;
+pub mod meminfo;
+pub mod model;
+pub mod mqtt;
+pub mod net;
+pub mod nvidia_smi;
+#[cfg(target_os = "linux")]
+pub mod nvme;
+#[cfg(unix)]
+pub mod nvml;
+pub mod openrgb;
+#[cfg(target_os = "linux")]
+pub mod processes;
+pub mod prometheus;
+pub mod psu;
+pub mod rapl;
+pub mod script;
+pub mod sensors_conf;
+pub mod smart;
+pub mod smc;
+pub mod smoothing;
+pub mod source;
+pub mod sysctl;
+pub mod sysinfo;
+pub mod thermal;
+pub mod throttle;
+pub mod upower;
+pub mod ups;
+pub mod vcgencmd;
+pub mod wireless;
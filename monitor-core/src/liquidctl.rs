@@ -0,0 +1,96 @@
+// AIO coolers, fan hubs and smart PSUs through `liquidctl status --json`: coolant
+// temperature, pump and fan speeds on the Corsair and NZXT devices lm-sensors can't
+// see (they are USB HID devices without a kernel driver).
+use crate::hwmon::{on_path, reading_entry};
+use crate::json::{self, Json};
+use crate::model::{Limits, SensorEntry, SensorSection};
+use std::process::Command;
+use std::time::Duration;
+
+// A status call talks USB HID to every device and often takes hundreds of
+// milliseconds, so it runs on a background thread at this interval.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+pub fn available() -> bool {
+    on_path("liquidctl")
+}
+
+pub fn read_liquidctl_data() -> Result<Vec<SensorSection>, String> {
+    let output = Command::new("liquidctl")
+        .args(["status", "--json"])
+        .output()
+        .map_err(|e| format!("Failed to execute liquidctl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("liquidctl failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    parse_status(&String::from_utf8_lossy(&output.stdout))
+}
+
+// [{"bus": "hid", "address": "/dev/hidraw3", "description": "NZXT Kraken X53",
+//   "status": [{"key": "Liquid temperature", "value": 31.1, "unit": "°C"}, ...]}]
+pub fn parse_status(output: &str) -> Result<Vec<SensorSection>, String> {
+    let Json::Array(devices) = json::parse(output).map_err(|e| format!("Failed to parse liquidctl output: {}", e))?
+    else {
+        return Err("Unexpected liquidctl output".to_string());
+    };
+    let mut sections = Vec::new();
+    for (index, device) in devices.iter().enumerate() {
+        let text = |key: &str| device.get(key).and_then(Json::as_str);
+        let Some(Json::Array(status)) = device.get("status") else { continue };
+        let entries = status
+            .iter()
+            .filter_map(|item| {
+                let key = item.get("key")?.as_str()?;
+                match item.get("value")? {
+                    Json::Number(value) => {
+                        let unit = item.get("unit").and_then(Json::as_str).unwrap_or("");
+                        // liquidctl prints "rpm"; everything else already matches lm-sensors.
+                        let unit = if unit == "rpm" { "RPM" } else { unit };
+                        Some(reading_entry(key, Some(*value), unit, Limits::default()))
+                    }
+                    Json::String(value) => Some(SensorEntry::from_text(key, value)),
+                    _ => None,
+                }
+            })
+            .collect();
+        sections.push(SensorSection {
+            name: format!("liquidctl-{}-{}", text("bus").unwrap_or("usb"), index),
+            adapter: text("description").unwrap_or("liquidctl device").to_string(),
+            entries,
+            unparsed: Vec::new(),
+            label: None,
+        });
+    }
+    if sections.is_empty() {
+        Err("liquidctl found no devices".to_string())
+    } else {
+        Ok(sections)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_status_json() {
+        let output = r#"[
+  {"bus": "hid", "address": "/dev/hidraw3", "description": "NZXT Kraken X (X53, X63 or X73)",
+   "status": [{"key": "Liquid temperature", "value": 31.1, "unit": "°C"},
+              {"key": "Pump speed", "value": 2064, "unit": "rpm"},
+              {"key": "Pump duty", "value": 73, "unit": "%"}]},
+  {"bus": "hid", "address": "/dev/hidraw5", "description": "Corsair HX750i",
+   "status": [{"key": "Fan control mode", "value": "Hardware", "unit": ""},
+              {"key": "Total power output", "value": 211.5, "unit": "W"}]}
+]"#;
+        let sections = parse_status(output).unwrap();
+        assert_eq!(sections[0].name, "liquidctl-hid-0");
+        assert_eq!(sections[0].adapter, "NZXT Kraken X (X53, X63 or X73)");
+        let pump = &sections[0].entries[1];
+        assert_eq!((pump.number, pump.unit.symbol()), (Some(2064.0), "RPM"));
+        assert_eq!(sections[1].entries[0].value, "Hardware");
+        assert_eq!(sections[1].entries[1].number, Some(211.5));
+        assert!(parse_status("[]").is_err());
+        assert!(parse_status("{").is_err());
+    }
+}
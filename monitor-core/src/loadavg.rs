@@ -0,0 +1,50 @@
+// The 1, 5 and 15 minute load averages and the task counts from /proc/loadavg.
+use crate::hwmon::reading_entry;
+use crate::model::{Limits, SensorSection};
+use std::fs;
+
+const LOADAVG_PATH: &str = "/proc/loadavg";
+
+pub fn read_load_data() -> Result<Vec<SensorSection>, String> {
+    let loadavg = fs::read_to_string(LOADAVG_PATH).map_err(|e| format!("Failed to read {}: {}", LOADAVG_PATH, e))?;
+    Ok(vec![parse_loadavg(&loadavg)?])
+}
+
+// "0.52 0.58 0.59 2/1234 56789": the averages, running/total tasks, the last PID.
+pub fn parse_loadavg(loadavg: &str) -> Result<SensorSection, String> {
+    let fields: Vec<&str> = loadavg.split_whitespace().collect();
+    let [one, five, fifteen, tasks, ..] = fields[..] else {
+        return Err(format!("Unexpected {} contents: {}", LOADAVG_PATH, loadavg.trim()));
+    };
+    let (running, total) = tasks.split_once('/').unwrap_or(("", ""));
+    let entries = [
+        ("1 min", one, ""),
+        ("5 min", five, ""),
+        ("15 min", fifteen, ""),
+        ("Running", running, "tasks"),
+        ("Total", total, "tasks"),
+    ]
+    .into_iter()
+    .map(|(key, value, unit)| reading_entry(key, value.parse().ok(), unit, Limits::default()))
+    .collect();
+    Ok(SensorSection {
+        name: "load-virtual-0".to_string(),
+        adapter: "Virtual device".to_string(),
+        entries,
+        unparsed: Vec::new(),
+        label: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_loadavg() {
+        let section = parse_loadavg("0.52 1.58 2.00 3/1234 56789\n").unwrap();
+        let numbers: Vec<Option<f64>> = section.entries.iter().map(|entry| entry.number).collect();
+        assert_eq!(numbers, [Some(0.52), Some(1.58), Some(2.0), Some(3.0), Some(1234.0)]);
+        assert!(parse_loadavg("0.52 1.58").is_err());
+    }
+}
@@ -0,0 +1,74 @@
+// RAM and swap usage from /proc/meminfo.
+use crate::hwmon::reading_entry;
+use crate::model::{Limits, SensorSection};
+use std::fs;
+
+const MEMINFO_PATH: &str = "/proc/meminfo";
+
+pub fn read_memory_data() -> Result<Vec<SensorSection>, String> {
+    let meminfo = fs::read_to_string(MEMINFO_PATH).map_err(|e| format!("Failed to read {}: {}", MEMINFO_PATH, e))?;
+    Ok(vec![parse_meminfo(&meminfo)?])
+}
+
+// "MemTotal:       32594372 kB" lines; every size is in KiB.
+pub fn parse_meminfo(meminfo: &str) -> Result<SensorSection, String> {
+    let field = |name: &str| {
+        let kib = meminfo.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key == name).then(|| value.trim().trim_end_matches("kB").trim().parse::<f64>().ok())?
+        });
+        kib.map(|kib| kib / 1024.0)
+    };
+    let total = field("MemTotal").ok_or("No MemTotal in /proc/meminfo")?;
+    // Kernels before 3.14 have no MemAvailable; free plus page cache is close to it.
+    let available = field("MemAvailable")
+        .or_else(|| Some(field("MemFree")? + field("Cached").unwrap_or(0.0) + field("Buffers").unwrap_or(0.0)));
+    let of_total = Limits { max: Some(total), ..Limits::default() };
+
+    let mut entries = vec![
+        reading_entry("RAM used", available.map(|available| total - available), "MiB", of_total),
+        reading_entry("RAM available", available, "MiB", of_total),
+        reading_entry("Cached", field("Cached"), "MiB", of_total),
+    ];
+    if let Some(swap) = field("SwapTotal").filter(|&swap| swap > 0.0) {
+        let used = field("SwapFree").map(|free| swap - free);
+        entries.push(reading_entry("Swap used", used, "MiB", Limits { max: Some(swap), ..Limits::default() }));
+    }
+    Ok(SensorSection {
+        name: "memory-virtual-0".to_string(),
+        adapter: "Virtual device".to_string(),
+        entries,
+        unparsed: Vec::new(),
+        label: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_meminfo() {
+        let meminfo = "\
+MemTotal:       16384000 kB
+MemFree:         2048000 kB
+MemAvailable:    8192000 kB
+Buffers:          512000 kB
+Cached:          4096000 kB
+SwapTotal:       4096000 kB
+SwapFree:        3072000 kB
+HugePages_Total:       0
+";
+        let section = parse_meminfo(meminfo).unwrap();
+        let reading = |key: &str| section.entries.iter().find(|e| e.key == key).and_then(|e| e.number);
+        assert_eq!(reading("RAM used"), Some(8000.0));
+        assert_eq!(reading("RAM available"), Some(8000.0));
+        assert_eq!(reading("Cached"), Some(4000.0));
+        assert_eq!(reading("Swap used"), Some(1000.0));
+        assert_eq!(section.entries[0].limits.max, Some(16000.0));
+
+        let no_swap = parse_meminfo("MemTotal: 1024 kB\nMemFree: 512 kB\nSwapTotal: 0 kB\n").unwrap();
+        assert_eq!(no_swap.entries.len(), 3);
+        assert!(parse_meminfo("").is_err());
+    }
+}
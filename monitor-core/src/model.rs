@@ -0,0 +1,484 @@
+// The sensor data every backend produces and the UI renders.
+
+#[derive(Debug, Clone)]
+pub struct SensorSection {
+    pub name: String,
+    pub adapter: String,
+    pub entries: Vec<SensorEntry>,
+    // Raw lines under the chip that no entry could be parsed from.
+    pub unparsed: Vec<Unparsed>,
+    // What the chip measures, when its name doesn't say: the disk behind a drivetemp
+    // chip, "sda (Samsung SSD 860 EVO 500GB)".
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Unparsed {
+    pub line: String,
+    // Why the parser gave up, e.g. "unknown unit \"dB\"".
+    pub reason: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct SensorEntry {
+    pub key: String,
+    // The reading as printed, plus its parsed magnitude and unit when it has them.
+    pub value: String,
+    pub number: Option<f64>,
+    pub unit: Unit,
+    // Printed as "N/A": the chip has the channel but could not read it.
+    pub unavailable: bool,
+    // Status flags the driver raised for the channel (lm-sensors' ALARM / FAULT).
+    pub alarm: bool,
+    pub fault: bool,
+    // The limits as printed, and parsed into the reading's unit.
+    pub additional_info: Option<String>,
+    pub limits: Limits,
+    // Set for channels that report a state instead of a number.
+    pub status: Option<Status>,
+    // Seconds the reading is averaged over, for power meters that print
+    // "(interval = 1.00 s)"; such samples are not instantaneous.
+    pub interval: Option<f64>,
+}
+
+// States of non-numeric channels: chassis intrusion switches ("OK" / "ALARM") and
+// switches such as beep_enable ("enabled" / "disabled"). Derived health checks, like
+// a drive's SMART attributes, report "Warning" rather than a hardware alarm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Alarm,
+    Warning,
+    Enabled,
+    Disabled,
+}
+
+impl Status {
+    pub fn parse(text: &str) -> Option<Status> {
+        match text.trim() {
+            "OK" => Some(Status::Ok),
+            "ALARM" => Some(Status::Alarm),
+            "enabled" => Some(Status::Enabled),
+            "disabled" => Some(Status::Disabled),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Status::Ok => "OK",
+            Status::Alarm => "ALARM",
+            Status::Warning => "Warning",
+            Status::Enabled => "enabled",
+            Status::Disabled => "disabled",
+        }
+    }
+}
+
+// The unit a reading's `number` is in. Backends name units by their usual symbols; ones
+// nothing here treats specially (counts like "sectors", rates like "Mbit/s") keep theirs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Unit {
+    #[default]
+    Unitless,
+    Celsius,
+    Fahrenheit,
+    Rpm,
+    Volts,
+    Millivolts,
+    Microvolts,
+    Amps,
+    Milliamps,
+    Microamps,
+    Watts,
+    Milliwatts,
+    Microwatts,
+    Kilowatts,
+    Megawatts,
+    Joules,
+    Kilojoules,
+    Megajoules,
+    Hertz,
+    Kilohertz,
+    Megahertz,
+    Gigahertz,
+    Percent,
+    Mebibytes,
+    Hours,
+    Minutes,
+    Other(String),
+}
+
+impl Unit {
+    pub fn parse(symbol: &str) -> Unit {
+        match symbol {
+            "" => Unit::Unitless,
+            "°C" => Unit::Celsius,
+            "°F" => Unit::Fahrenheit,
+            "RPM" => Unit::Rpm,
+            "V" => Unit::Volts,
+            "mV" => Unit::Millivolts,
+            "µV" => Unit::Microvolts,
+            "A" => Unit::Amps,
+            "mA" => Unit::Milliamps,
+            "µA" => Unit::Microamps,
+            "W" => Unit::Watts,
+            "mW" => Unit::Milliwatts,
+            "µW" => Unit::Microwatts,
+            "kW" => Unit::Kilowatts,
+            "MW" => Unit::Megawatts,
+            "J" => Unit::Joules,
+            "kJ" => Unit::Kilojoules,
+            "MJ" => Unit::Megajoules,
+            "Hz" => Unit::Hertz,
+            "kHz" => Unit::Kilohertz,
+            "MHz" => Unit::Megahertz,
+            "GHz" => Unit::Gigahertz,
+            "%" => Unit::Percent,
+            "MiB" => Unit::Mebibytes,
+            "h" => Unit::Hours,
+            "min" => Unit::Minutes,
+            other => Unit::Other(other.to_string()),
+        }
+    }
+
+    pub fn symbol(&self) -> &str {
+        match self {
+            Unit::Unitless => "",
+            Unit::Celsius => "°C",
+            Unit::Fahrenheit => "°F",
+            Unit::Rpm => "RPM",
+            Unit::Volts => "V",
+            Unit::Millivolts => "mV",
+            Unit::Microvolts => "µV",
+            Unit::Amps => "A",
+            Unit::Milliamps => "mA",
+            Unit::Microamps => "µA",
+            Unit::Watts => "W",
+            Unit::Milliwatts => "mW",
+            Unit::Microwatts => "µW",
+            Unit::Kilowatts => "kW",
+            Unit::Megawatts => "MW",
+            Unit::Joules => "J",
+            Unit::Kilojoules => "kJ",
+            Unit::Megajoules => "MJ",
+            Unit::Hertz => "Hz",
+            Unit::Kilohertz => "kHz",
+            Unit::Megahertz => "MHz",
+            Unit::Gigahertz => "GHz",
+            Unit::Percent => "%",
+            Unit::Mebibytes => "MiB",
+            Unit::Hours => "h",
+            Unit::Minutes => "min",
+            Unit::Other(symbol) => symbol,
+        }
+    }
+
+    // Factor to the base unit, e.g. 0.001 for mV.
+    pub fn scale(&self) -> f64 {
+        match self {
+            Unit::Millivolts | Unit::Milliwatts | Unit::Milliamps => 1e-3,
+            Unit::Microvolts | Unit::Microwatts | Unit::Microamps => 1e-6,
+            Unit::Kilowatts | Unit::Kilojoules | Unit::Kilohertz => 1e3,
+            Unit::Megawatts | Unit::Megajoules | Unit::Megahertz => 1e6,
+            Unit::Gigahertz => 1e9,
+            _ => 1.0,
+        }
+    }
+}
+
+// What a channel's reading amounts to, for colouring it: a number, a state or word, a channel
+// that could not be read (N/A, or a fan header reading 0 RPM with no minimum set) or one that
+// failed (FAULT, or a fan stopped below its minimum).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SensorValue {
+    Number(f64),
+    Text,
+    Unavailable,
+    Faulty,
+}
+
+// Thresholds set on a channel, named as lm-sensors prints them. Temperatures use
+// low/high; voltages, fans and power use min/max.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Limits {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub low: Option<f64>,
+    pub high: Option<f64>,
+    pub crit: Option<f64>,
+    pub crit_low: Option<f64>,
+    pub hyst: Option<f64>,
+    pub crit_hyst: Option<f64>,
+}
+
+impl SensorEntry {
+    // A setting reported as words, like a fan mode ("auto", "level 3").
+    pub fn from_text(key: &str, text: &str) -> SensorEntry {
+        SensorEntry {
+            key: key.to_string(),
+            value: text.to_string(),
+            number: None,
+            unit: Unit::Unitless,
+            unavailable: false,
+            alarm: false,
+            fault: false,
+            additional_info: None,
+            limits: Limits::default(),
+            status: None,
+            interval: None,
+        }
+    }
+
+    pub fn from_status(key: &str, status: Status) -> SensorEntry {
+        SensorEntry {
+            key: key.to_string(),
+            value: status.label().to_string(),
+            number: None,
+            unit: Unit::Unitless,
+            unavailable: false,
+            alarm: matches!(status, Status::Alarm | Status::Warning),
+            fault: false,
+            additional_info: None,
+            limits: Limits::default(),
+            status: Some(status),
+            interval: None,
+        }
+    }
+
+    // The number column: fixed decimals per unit so values line up.
+    pub fn display_number(&self) -> String {
+        if let Some((number, unit)) = self.scaled_frequency() {
+            return group_thousands(&format!("{:.*}", unit_decimals(unit), number));
+        }
+        match self.number {
+            Some(number) => format!("{:.*}", unit_decimals(&self.unit), number),
+            None => self.value.clone(),
+        }
+    }
+
+    // The unit column, which differs from `unit` when a frequency is rescaled.
+    pub fn display_unit(&self) -> &str {
+        self.shown_unit().symbol()
+    }
+
+    fn shown_unit(&self) -> &Unit {
+        match self.scaled_frequency() {
+            Some((_, unit)) => unit,
+            None => &self.unit,
+        }
+    }
+
+    // Frequencies are shown in the prefix that suits their magnitude, topping out at
+    // MHz so clocks read the usual way ("4,550 MHz" rather than "4.55 GHz").
+    fn scaled_frequency(&self) -> Option<(f64, &'static Unit)> {
+        let hz = self.number? * frequency_scale(&self.unit)?;
+        Some(if hz.abs() >= 1e6 {
+            (hz / 1e6, &Unit::Megahertz)
+        } else if hz.abs() >= 1e3 {
+            (hz / 1e3, &Unit::Kilohertz)
+        } else {
+            (hz, &Unit::Hertz)
+        })
+    }
+
+    pub fn sensor_value(&self) -> SensorValue {
+        match self.number {
+            _ if self.fault || self.is_dead_fan() => SensorValue::Faulty,
+            _ if self.unavailable => SensorValue::Unavailable,
+            Some(n) if n == 0.0 && self.unit == Unit::Rpm => SensorValue::Unavailable,
+            Some(n) => SensorValue::Number(n),
+            None => SensorValue::Text,
+        }
+    }
+
+    // A fan that should be spinning, with a minimum set, but reads 0 RPM.
+    fn is_dead_fan(&self) -> bool {
+        self.number == Some(0.0) && self.unit == Unit::Rpm && self.limits.min.is_some_and(|min| min > 0.0)
+    }
+
+    // Readings that mean nothing is plugged into the channel: stopped fan headers,
+    // floating voltage inputs, and the sentinel values of open thermistor inputs. A
+    // stopped fan with a minimum set is a dead fan instead, which stays visible.
+    pub fn is_disconnected(&self) -> bool {
+        if self.is_dead_fan() {
+            return false;
+        }
+        match (self.number, &self.unit) {
+            (
+                Some(n),
+                Unit::Rpm
+                | Unit::Volts
+                | Unit::Millivolts
+                | Unit::Microvolts
+                | Unit::Amps
+                | Unit::Milliamps
+                | Unit::Microamps
+                | Unit::Watts
+                | Unit::Milliwatts
+                | Unit::Microwatts,
+            ) => n == 0.0,
+            (Some(n), Unit::Celsius) => n <= -100.0 || n >= 127.0,
+            _ => false,
+        }
+    }
+
+    // Whether the driver flagged the channel, or the reading is past one of its limits.
+    pub fn in_alarm(&self) -> bool {
+        if self.alarm || self.fault {
+            return true;
+        }
+        let (Some(number), limits) = (self.number, &self.limits) else {
+            return false;
+        };
+        let below = [limits.min, limits.low, limits.crit_low]
+            .into_iter()
+            .flatten()
+            .any(|limit| number < limit);
+        let above = [limits.max, limits.high, limits.crit]
+            .into_iter()
+            .flatten()
+            .any(|limit| number >= limit);
+        below || above
+    }
+
+    // The share of a memory pool in use, 0.0-1.0, for "... used" readings whose max
+    // is the pool's size (VRAM, GTT, RAM, swap).
+    pub fn usage(&self) -> Option<f64> {
+        if self.unit != Unit::Mebibytes || !self.key.ends_with("used") {
+            return None;
+        }
+        let total = self.limits.max.filter(|&total| total > 0.0)?;
+        Some((self.number? / total).clamp(0.0, 1.0))
+    }
+
+    // Number and unit together, e.g. "67.0°C" or "1200 RPM".
+    pub fn display_reading(&self) -> String {
+        let number = self.display_number();
+        match self.shown_unit() {
+            Unit::Unitless => number,
+            unit @ (Unit::Celsius | Unit::Fahrenheit | Unit::Percent) => format!("{}{}", number, unit.symbol()),
+            Unit::Other(unit) if unit.starts_with('°') => format!("{}{}", number, unit),
+            unit => format!("{} {}", number, unit.symbol()),
+        }
+    }
+}
+
+const COUNTS: [&str; 7] = ["cycles", "IOPS", "tasks", "sectors", "errors", "dBm", "events"];
+
+pub fn unit_decimals(unit: &Unit) -> usize {
+    match unit {
+        Unit::Rpm
+        | Unit::Milliamps
+        | Unit::Microamps
+        | Unit::Millivolts
+        | Unit::Microvolts
+        | Unit::Milliwatts
+        | Unit::Microwatts
+        | Unit::Hertz
+        | Unit::Megahertz
+        | Unit::Mebibytes
+        | Unit::Hours => 0,
+        Unit::Celsius | Unit::Fahrenheit | Unit::Percent | Unit::Kilohertz | Unit::Minutes => 1,
+        // Counts, and signal levels that are only ever whole.
+        Unit::Other(unit) if COUNTS.contains(&unit.as_str()) => 0,
+        _ => 2,
+    }
+}
+
+// Hz per unit, for the frequency family only.
+fn frequency_scale(unit: &Unit) -> Option<f64> {
+    match unit {
+        Unit::Hertz | Unit::Kilohertz | Unit::Megahertz | Unit::Gigahertz => Some(unit.scale()),
+        _ => None,
+    }
+}
+
+// "4550" -> "4,550"; leaves the sign and any decimals alone.
+fn group_thousands(number: &str) -> String {
+    let (sign, rest) = number.split_at(usize::from(number.starts_with('-')));
+    let (integer, fraction) = rest.split_at(rest.find('.').unwrap_or(rest.len()));
+    let mut grouped = String::new();
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    format!("{}{}{}", sign, grouped, fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(number: f64, unit: &str) -> SensorEntry {
+        SensorEntry {
+            key: "freq1".to_string(),
+            value: String::new(),
+            number: Some(number),
+            unit: Unit::parse(unit),
+            unavailable: false,
+            alarm: false,
+            fault: false,
+            additional_info: None,
+            limits: Limits::default(),
+            status: None,
+            interval: None,
+        }
+    }
+
+    #[test]
+    fn classifies_missing_and_faulty_readings() {
+        assert_eq!(reading(1200.0, "RPM").sensor_value(), SensorValue::Number(1200.0));
+        let stopped = reading(0.0, "RPM");
+        assert_eq!(stopped.sensor_value(), SensorValue::Unavailable);
+        assert!(stopped.is_disconnected());
+        let dead = SensorEntry { limits: Limits { min: Some(300.0), ..Limits::default() }, ..stopped };
+        assert_eq!(dead.sensor_value(), SensorValue::Faulty);
+        assert!(!dead.is_disconnected());
+        let missing = SensorEntry { number: None, unavailable: true, ..reading(0.0, "") };
+        assert_eq!(missing.sensor_value(), SensorValue::Unavailable);
+        assert_eq!(SensorEntry { fault: true, ..missing }.sensor_value(), SensorValue::Faulty);
+        assert_eq!(SensorEntry::from_text("mode", "auto").sensor_value(), SensorValue::Text);
+    }
+
+    #[test]
+    fn names_units() {
+        for symbol in ["°C", "RPM", "µV", "kJ", "GHz", "MiB", "", "Mbit/s"] {
+            assert_eq!(Unit::parse(symbol).symbol(), symbol);
+        }
+        assert_eq!(Unit::parse("mV").scale(), 1e-3);
+        assert_eq!(Unit::parse("sectors"), Unit::Other("sectors".to_string()));
+        assert_eq!(unit_decimals(&Unit::Other("sectors".to_string())), 0);
+        assert_eq!(reading(12.0, "Mbit/s").display_reading(), "12.00 Mbit/s");
+    }
+
+    #[test]
+    fn scales_frequencies() {
+        assert_eq!(reading(4_550_000_000.0, "Hz").display_reading(), "4,550 MHz");
+        assert_eq!(reading(4.55, "GHz").display_reading(), "4,550 MHz");
+        assert_eq!(reading(800.0, "MHz").display_reading(), "800 MHz");
+        assert_eq!(reading(32_768.0, "Hz").display_reading(), "32.8 kHz");
+        assert_eq!(reading(50.0, "Hz").display_reading(), "50 Hz");
+    }
+
+    #[test]
+    fn measures_memory_usage() {
+        let vram = SensorEntry {
+            key: "VRAM used".to_string(),
+            limits: Limits { max: Some(8192.0), ..Limits::default() },
+            ..reading(2048.0, "MiB")
+        };
+        assert_eq!(vram.usage(), Some(0.25));
+        assert_eq!(SensorEntry { key: "RAM available".to_string(), ..vram.clone() }.usage(), None);
+        assert_eq!(SensorEntry { limits: Limits::default(), ..vram }.usage(), None);
+    }
+
+    #[test]
+    fn groups_thousands() {
+        assert_eq!(group_thousands("1234567"), "1,234,567");
+        assert_eq!(group_thousands("-1234.5"), "-1,234.5");
+        assert_eq!(group_thousands("999"), "999");
+    }
+}
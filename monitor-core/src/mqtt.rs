@@ -0,0 +1,351 @@
+// Remote sensors over MQTT, such as ESPHome or Zigbee2MQTT temperature probes: a
+// small MQTT 3.1.1 client that subscribes to the configured topics at QoS 0 on a
+// background thread and keeps the last message on each topic. A plain number
+// ("21.4") is one reading; a JSON object ({"temperature": 21.4, "humidity": 40})
+// is one reading per numeric member.
+use crate::hwmon::reading_entry;
+use crate::json::{self, Json};
+use crate::model::{Limits, SensorEntry, SensorSection};
+use crate::source::{ReadResult, SensorSource};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+// Messages older than this are shown as N/A: the probe has gone quiet.
+const STALE_AFTER: Duration = Duration::from_secs(600);
+// Sensor readings are a few bytes; a larger remaining length is a broken or hostile broker.
+const MAX_PACKET: usize = 1 << 20;
+
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const SUBSCRIBE: u8 = 0x82;
+const PINGREQ: u8 = 0xc0;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Settings {
+    // "host:port"
+    pub server: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    // (topic filter, unit of the readings on it).
+    pub topics: Vec<(String, String)>,
+}
+
+impl Settings {
+    // "<filter> [<unit>]": "home/+/temperature °C".
+    pub fn add_topic(&mut self, value: &str) {
+        let mut words = value.split_whitespace();
+        if let Some(filter) = words.next() {
+            self.topics.push((filter.to_string(), words.next().unwrap_or("").to_string()));
+        }
+    }
+}
+
+type Messages = Arc<Mutex<BTreeMap<String, (String, Instant)>>>;
+
+// The subscription, which lives as long as the source.
+pub struct MqttSource {
+    settings: Settings,
+    messages: Messages,
+    error: Arc<Mutex<Option<String>>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl MqttSource {
+    pub fn start(settings: Settings) -> Self {
+        let source = MqttSource {
+            settings,
+            messages: Messages::default(),
+            error: Arc::default(),
+            stop: Arc::default(),
+        };
+        let (settings, messages, error, stop) =
+            (source.settings.clone(), source.messages.clone(), source.error.clone(), source.stop.clone());
+        thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                let result = subscribe(&settings, &messages, &stop);
+                *error.lock().unwrap_or_else(|e| e.into_inner()) = result.err();
+                thread::sleep(RECONNECT_DELAY);
+            }
+        });
+        source
+    }
+}
+
+impl Drop for MqttSource {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl SensorSource for MqttSource {
+    fn name(&self) -> &str {
+        "MQTT"
+    }
+
+    fn poll(&mut self) -> ReadResult {
+        let messages = self.messages.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if messages.is_empty() {
+            let error = self.error.lock().unwrap_or_else(|e| e.into_inner()).clone();
+            return match error {
+                Some(e) => Err(e),
+                // Connected, but nothing published yet.
+                None => Ok(Vec::new()),
+            };
+        }
+        Ok(vec![remote_section(&self.settings, &messages, Instant::now())])
+    }
+}
+
+pub fn remote_section(
+    settings: &Settings,
+    messages: &BTreeMap<String, (String, Instant)>,
+    now: Instant,
+) -> SensorSection {
+    let mut entries = Vec::new();
+    for (topic, (payload, received)) in messages {
+        let unit = settings
+            .topics
+            .iter()
+            .find(|(filter, _)| topic_matches(filter, topic))
+            .map_or("", |(_, unit)| unit.as_str());
+        let stale = now.duration_since(*received) > STALE_AFTER;
+        entries.extend(payload_entries(topic, payload, unit, stale));
+    }
+    SensorSection {
+        name: "mqtt-remote-0".to_string(),
+        adapter: format!("MQTT {}", settings.server),
+        entries,
+        unparsed: Vec::new(),
+        label: Some("Remote sensors".to_string()),
+    }
+}
+
+// ESPHome's "<node>/sensor/<name>/state" reads as "<node> <name>"; other topics are
+// shown as they are.
+fn topic_key(topic: &str) -> String {
+    match topic.split('/').collect::<Vec<_>>()[..] {
+        [node, "sensor", name, "state"] => format!("{} {}", node, name),
+        _ => topic.to_string(),
+    }
+}
+
+fn payload_entries(topic: &str, payload: &str, unit: &str, stale: bool) -> Vec<SensorEntry> {
+    let key = topic_key(topic);
+    let reading = |key: &str, value: Option<f64>, unit: &str| {
+        reading_entry(key, value.filter(|_| !stale), unit, Limits::default())
+    };
+    if let Ok(value) = payload.trim().parse::<f64>() {
+        return vec![reading(&key, Some(value), unit)];
+    }
+    match json::parse(payload) {
+        Ok(Json::Object(members)) => members
+            .iter()
+            .filter_map(|(name, value)| {
+                let unit = match name.as_str() {
+                    "temperature" => "°C",
+                    "humidity" | "battery" => "%",
+                    "pressure" => "hPa",
+                    _ => unit,
+                };
+                Some(reading(&format!("{} {}", key, name), Some(value.as_f64()?), unit))
+            })
+            .collect(),
+        _ => vec![SensorEntry::from_text(&key, payload.trim())],
+    }
+}
+
+// MQTT topic filters: `+` matches one level, a trailing `#` any number.
+pub fn topic_matches(filter: &str, topic: &str) -> bool {
+    let mut levels = topic.split('/');
+    for part in filter.split('/') {
+        match (part, levels.next()) {
+            ("#", _) => return true,
+            ("+", Some(_)) => {}
+            (part, Some(level)) if part == level => {}
+            _ => return false,
+        }
+    }
+    levels.next().is_none()
+}
+
+// Connects, subscribes and stores messages until the connection drops or `stop` is
+// set.
+fn subscribe(settings: &Settings, messages: &Messages, stop: &AtomicBool) -> Result<(), String> {
+    let fail = |e: std::io::Error| format!("MQTT {}: {}", settings.server, e);
+    let address = settings
+        .server
+        .to_socket_addrs()
+        .map_err(fail)?
+        .next()
+        .ok_or_else(|| format!("Failed to resolve {}", settings.server))?;
+    let mut stream = TcpStream::connect_timeout(&address, Duration::from_secs(2)).map_err(fail)?;
+    stream.set_read_timeout(Some(KEEP_ALIVE / 2)).map_err(fail)?;
+
+    stream.write_all(&connect_packet(settings)).map_err(fail)?;
+    let (kind, body) = read_packet(&mut stream).map_err(fail)?;
+    if kind & 0xf0 != CONNACK || body.get(1) != Some(&0) {
+        return Err(format!("MQTT {} refused the connection (code {:?})", settings.server, body.get(1)));
+    }
+    stream.write_all(&subscribe_packet(&settings.topics)).map_err(fail)?;
+    let mut last_write = Instant::now();
+
+    while !stop.load(Ordering::Relaxed) {
+        // The broker drops clients that send nothing for a keep-alive period, however
+        // much it publishes to them, so ping every half period since the last write.
+        let ping_in = (KEEP_ALIVE / 2).saturating_sub(last_write.elapsed());
+        if ping_in.is_zero() {
+            stream.write_all(&[PINGREQ, 0]).map_err(fail)?;
+            last_write = Instant::now();
+            continue;
+        }
+        stream.set_read_timeout(Some(ping_in)).map_err(fail)?;
+        match read_packet(&mut stream) {
+            Ok((kind, body)) if kind & 0xf0 == PUBLISH => {
+                if let Some((topic, payload)) = parse_publish(kind, &body) {
+                    let mut messages = messages.lock().unwrap_or_else(|e| e.into_inner());
+                    messages.insert(topic, (payload, Instant::now()));
+                }
+            }
+            Ok(_) => {}
+            // Nothing until the next ping is due.
+            Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {}
+            Err(e) => return Err(fail(e)),
+        }
+    }
+    Ok(())
+}
+
+fn push_string(packet: &mut Vec<u8>, text: &str) {
+    packet.extend_from_slice(&(text.len() as u16).to_be_bytes());
+    packet.extend_from_slice(text.as_bytes());
+}
+
+// A fixed header (type and flags, then the remaining length) in front of `body`.
+fn packet(kind: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![kind];
+    let mut length = body.len();
+    loop {
+        let byte = (length % 128) as u8;
+        length /= 128;
+        packet.push(if length > 0 { byte | 0x80 } else { byte });
+        if length == 0 {
+            break;
+        }
+    }
+    packet.extend_from_slice(body);
+    packet
+}
+
+fn connect_packet(settings: &Settings) -> Vec<u8> {
+    let mut body = Vec::new();
+    push_string(&mut body, "MQTT");
+    body.push(4); // protocol level 3.1.1
+    let mut flags = 0x02; // clean session
+    if settings.username.is_some() {
+        flags |= 0x80;
+        if settings.password.is_some() {
+            flags |= 0x40;
+        }
+    }
+    body.push(flags);
+    body.extend_from_slice(&(KEEP_ALIVE.as_secs() as u16).to_be_bytes());
+    push_string(&mut body, &format!("sensory-{}", std::process::id()));
+    if let Some(username) = &settings.username {
+        push_string(&mut body, username);
+        if let Some(password) = &settings.password {
+            push_string(&mut body, password);
+        }
+    }
+    packet(CONNECT, &body)
+}
+
+fn subscribe_packet(topics: &[(String, String)]) -> Vec<u8> {
+    let mut body = 1u16.to_be_bytes().to_vec(); // packet id
+    for (filter, _) in topics {
+        push_string(&mut body, filter);
+        body.push(0); // QoS 0
+    }
+    packet(SUBSCRIBE, &body)
+}
+
+pub fn read_packet(stream: &mut impl Read) -> std::io::Result<(u8, Vec<u8>)> {
+    let mut byte = [0; 1];
+    stream.read_exact(&mut byte)?;
+    let kind = byte[0];
+    let mut length = 0usize;
+    for shift in (0..28).step_by(7) {
+        stream.read_exact(&mut byte)?;
+        length |= ((byte[0] & 0x7f) as usize) << shift;
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+    }
+    if length > MAX_PACKET {
+        let message = format!("{} byte packet is over the {} byte limit", length, MAX_PACKET);
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, message));
+    }
+    let mut body = vec![0; length];
+    stream.read_exact(&mut body)?;
+    Ok((kind, body))
+}
+
+// (topic, payload) of a PUBLISH body; QoS 1 and 2 messages carry a packet id too.
+pub fn parse_publish(kind: u8, body: &[u8]) -> Option<(String, String)> {
+    let length = u16::from_be_bytes([*body.first()?, *body.get(1)?]) as usize;
+    let topic = String::from_utf8_lossy(body.get(2..2 + length)?).into_owned();
+    let qos = (kind >> 1) & 0x03;
+    let payload = body.get(2 + length + if qos > 0 { 2 } else { 0 }..)?;
+    Some((topic, String::from_utf8_lossy(payload).into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_published_readings() {
+        let mut body = Vec::new();
+        push_string(&mut body, "garage/sensor/ambient/state");
+        body.extend_from_slice(b"18.5");
+        let publish = packet(PUBLISH, &body);
+        let (kind, body) = read_packet(&mut publish.as_slice()).unwrap();
+        let (topic, payload) = parse_publish(kind, &body).unwrap();
+        assert_eq!((topic.as_str(), payload.as_str()), ("garage/sensor/ambient/state", "18.5"));
+        assert_eq!(packet(PUBLISH, &[0; 200])[1..3], [0xc8, 0x01]);
+        let oversized = read_packet(&mut [PUBLISH, 0xff, 0xff, 0xff, 0x7f].as_slice()).unwrap_err();
+        assert_eq!(oversized.kind(), std::io::ErrorKind::InvalidData);
+
+        assert!(topic_matches("garage/sensor/+/state", &topic));
+        assert!(topic_matches("garage/#", &topic));
+        assert!(!topic_matches("garage/+", &topic));
+
+        let mut settings = Settings { server: "broker:1883".to_string(), ..Settings::default() };
+        settings.add_topic("garage/sensor/+/state °C");
+        let now = Instant::now();
+        let bedroom = r#"{"temperature":21.4,"humidity":40,"linkquality":80}"#.to_string();
+        let messages =
+            BTreeMap::from([(topic, (payload, now)), ("zigbee2mqtt/bedroom".to_string(), (bedroom, now))]);
+        let section = remote_section(&settings, &messages, now);
+        let readings: Vec<(&str, Option<f64>, &str)> =
+            section.entries.iter().map(|entry| (entry.key.as_str(), entry.number, entry.unit.symbol())).collect();
+        assert_eq!(
+            readings,
+            [
+                ("garage ambient", Some(18.5), "°C"),
+                ("zigbee2mqtt/bedroom temperature", Some(21.4), "°C"),
+                ("zigbee2mqtt/bedroom humidity", Some(40.0), "%"),
+                ("zigbee2mqtt/bedroom linkquality", Some(80.0), ""),
+            ]
+        );
+        let later = remote_section(&settings, &messages, now + STALE_AFTER * 2);
+        assert!(later.entries[0].unavailable);
+    }
+}
@@ -0,0 +1,90 @@
+// Per-interface bandwidth from the /sys/class/net byte counters, as the growth
+// between refreshes; N/A until the second read.
+use crate::counters::Counters;
+use crate::hwmon::{read_trimmed, reading_entry};
+use crate::model::{Limits, SensorSection};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+
+const NET_ROOT: &str = "/sys/class/net";
+
+static SAMPLES: Mutex<Counters> = Mutex::new(Counters::new());
+
+// Every interface but loopback and the veth pairs containers create.
+fn interfaces() -> Vec<String> {
+    let mut interfaces: Vec<String> = fs::read_dir(NET_ROOT)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name != "lo" && !name.starts_with("veth"))
+        .collect();
+    interfaces.sort();
+    interfaces
+}
+
+pub fn read_network_data() -> Result<Vec<SensorSection>, String> {
+    let mut samples = SAMPLES.lock().map_err(|_| "Network samples are unavailable".to_string())?;
+    let now = Instant::now();
+    let sections: Vec<SensorSection> = interfaces()
+        .iter()
+        .filter_map(|name| {
+            let path = Path::new(NET_ROOT).join(name);
+            let counter = |file: &str| read_trimmed(&path.join("statistics").join(file))?.parse::<u64>().ok();
+            let state = read_trimmed(&path.join("operstate")).unwrap_or_else(|| "unknown".to_string());
+            Some(interface_section(&mut samples, name, counter("rx_bytes")?, counter("tx_bytes")?, &state, now))
+        })
+        .collect();
+    if sections.is_empty() {
+        Err("No network interfaces found".to_string())
+    } else {
+        Ok(sections)
+    }
+}
+
+pub fn interface_section(
+    samples: &mut Counters,
+    name: &str,
+    rx_bytes: u64,
+    tx_bytes: u64,
+    state: &str,
+    now: Instant,
+) -> SensorSection {
+    let mut megabits = |direction: &str, bytes: u64| {
+        samples
+            .rate(&format!("{}/{}", name, direction), bytes, now)
+            .map(|rate| rate * 8.0 / 1e6)
+    };
+    SensorSection {
+        name: format!("net-{}", name),
+        adapter: format!("Network interface ({})", state),
+        entries: vec![
+            reading_entry("Download", megabits("rx", rx_bytes), "Mbit/s", Limits::default()),
+            reading_entry("Upload", megabits("tx", tx_bytes), "Mbit/s", Limits::default()),
+        ],
+        unparsed: Vec::new(),
+        label: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn measures_bandwidth_between_reads() {
+        let mut samples = Counters::new();
+        let start = Instant::now();
+        let first = interface_section(&mut samples, "eth0", 1_000_000, 0, "up", start);
+        assert!(first.entries.iter().all(|entry| entry.unavailable));
+
+        let second = interface_section(&mut samples, "eth0", 3_500_000, 125_000, "up", start + Duration::from_secs(2));
+        assert_eq!(second.name, "net-eth0");
+        assert_eq!(second.adapter, "Network interface (up)");
+        assert_eq!(second.entries[0].number, Some(10.0));
+        assert_eq!(second.entries[1].number, Some(0.5));
+    }
+}
@@ -0,0 +1,109 @@
+// NVIDIA GPUs through `nvidia-smi`, which ships with the proprietary driver; its
+// cards have no hwmon chip, so lm-sensors and the hwmon backend never see them.
+use crate::hwmon::{pci_address, reading_entry};
+use crate::model::{Limits, SensorSection};
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+// Queried in this order; `parse_query` expects the same columns.
+const FIELDS: &str = "index,name,pci.bus_id,temperature.gpu,fan.speed,power.draw,power.limit,\
+clocks.gr,clocks.mem,memory.used,memory.total,utilization.gpu";
+
+// nvidia-smi takes 100-500 ms to start, so it runs on a background thread at this
+// interval.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// The proprietary driver is loaded; nouveau cards show up through hwmon instead.
+pub fn available() -> bool {
+    Path::new("/proc/driver/nvidia/version").exists()
+}
+
+pub fn read_nvidia_data() -> Result<Vec<SensorSection>, String> {
+    let output = Command::new("nvidia-smi")
+        .args([&format!("--query-gpu={}", FIELDS), "--format=csv,noheader,nounits"])
+        .output()
+        .map_err(|e| format!("Failed to execute nvidia-smi: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "nvidia-smi failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    parse_query(&String::from_utf8_lossy(&output.stdout))
+}
+
+// One CSV line per GPU:
+//   0, NVIDIA GeForce RTX 3080, 00000000:01:00.0, 54, 30, 112.34, 320.00, 1710, 9501, 1234, 10240, 12
+pub fn parse_query(output: &str) -> Result<Vec<SensorSection>, String> {
+    let mut sections = Vec::new();
+    for line in output.lines().filter(|line| !line.trim().is_empty()) {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [index, gpu, bus, temperature, fan, power, power_limit, graphics, memory, used, total, busy] =
+            fields[..]
+        else {
+            return Err(format!("Unexpected nvidia-smi output: {}", line.trim()));
+        };
+
+        let mut entries = Vec::new();
+        let mut push = |key: &str, value: &str, unit: &str, limits: Limits| {
+            // "[Not Supported]": the card has no such sensor, e.g. laptop GPU fans.
+            if value != "[Not Supported]" {
+                entries.push(reading_entry(key, value.parse().ok(), unit, limits));
+            }
+        };
+        push("GPU", temperature, "°C", Limits::default());
+        push("Fan", fan, "%", Limits::default());
+        push("Power", power, "W", Limits { max: power_limit.parse().ok(), ..Limits::default() });
+        push("Graphics clock", graphics, "MHz", Limits::default());
+        push("Memory clock", memory, "MHz", Limits::default());
+        push("VRAM used", used, "MiB", Limits { max: total.parse().ok(), ..Limits::default() });
+        push("Utilization", busy, "%", Limits::default());
+
+        // Named like the chips lm-sensors lists, after the card's PCI address.
+        let name = match pci_address(bus) {
+            Some(address) => format!("nvidia-pci-{:04x}", address),
+            None => format!("nvidia-gpu{}", index),
+        };
+        sections.push(SensorSection {
+            name,
+            adapter: gpu.to_string(),
+            entries,
+            unparsed: Vec::new(),
+            label: None,
+        });
+    }
+
+    if sections.is_empty() {
+        Err("nvidia-smi found no GPUs".to_string())
+    } else {
+        Ok(sections)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_query_output() {
+        let output = "\
+0, NVIDIA GeForce RTX 3080, 00000000:01:00.0, 54, 30, 112.34, 320.00, 1710, 9501, 1234, 10240, 12
+1, NVIDIA GeForce GTX 1650, 00000000:0A:00.0, 41, [Not Supported], [N/A], [N/A], 300, 405, 5, 4096, 0
+";
+        let sections = parse_query(output).unwrap();
+        assert_eq!(sections[0].name, "nvidia-pci-0100");
+        assert_eq!(sections[0].adapter, "NVIDIA GeForce RTX 3080");
+        let power = sections[0].entries.iter().find(|e| e.key == "Power").unwrap();
+        assert_eq!((power.number, power.limits.max), (Some(112.34), Some(320.0)));
+        let vram = sections[0].entries.iter().find(|e| e.key == "VRAM used").unwrap();
+        assert_eq!((vram.number, vram.unit.symbol()), (Some(1234.0), "MiB"));
+
+        assert_eq!(sections[1].name, "nvidia-pci-0a00");
+        assert!(sections[1].entries.iter().all(|e| e.key != "Fan"));
+        assert!(sections[1].entries.iter().find(|e| e.key == "Power").unwrap().unavailable);
+
+        assert!(parse_query("0, only, three\n").is_err());
+        assert!(parse_query("").is_err());
+    }
+}
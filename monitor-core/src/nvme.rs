@@ -0,0 +1,237 @@
+// NVMe temperatures and wear straight from the controller, through the admin-command
+// ioctl on /dev/nvmeN: the SMART / health log page for readings and Identify
+// Controller for the model and temperature thresholds. The device nodes are usually
+// root-only; controllers that cannot be opened are skipped.
+use crate::hwmon::reading_entry;
+use crate::model::{Limits, SensorSection};
+use std::ffi::{c_int, c_ulong, c_void};
+use std::fs::{self, File};
+use std::os::fd::AsRawFd;
+
+extern "C" {
+    fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+}
+
+// _IOWR('N', 0x41, struct nvme_admin_cmd)
+const NVME_IOCTL_ADMIN_CMD: c_ulong = 0xc048_4e41;
+const ADMIN_GET_LOG_PAGE: u8 = 0x02;
+const ADMIN_IDENTIFY: u8 = 0x06;
+const LOG_SMART_HEALTH: u32 = 0x02;
+const IDENTIFY_CONTROLLER: u32 = 0x01;
+const ALL_NAMESPACES: u32 = 0xffff_ffff;
+const KELVIN: f64 = 273.15;
+// The log counts data in thousands of 512-byte blocks.
+const DATA_UNIT_BYTES: f64 = 512_000.0;
+
+// struct nvme_passthru_cmd from <linux/nvme_ioctl.h>.
+#[repr(C)]
+#[derive(Default)]
+struct AdminCommand {
+    opcode: u8,
+    flags: u8,
+    rsvd1: u16,
+    nsid: u32,
+    cdw2: u32,
+    cdw3: u32,
+    metadata: u64,
+    addr: u64,
+    metadata_len: u32,
+    data_len: u32,
+    cdw10: u32,
+    cdw11: u32,
+    cdw12: u32,
+    cdw13: u32,
+    cdw14: u32,
+    cdw15: u32,
+    timeout_ms: u32,
+    result: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Controller {
+    pub model: String,
+    // Warning and critical composite temperature thresholds, °C.
+    pub warning: Option<f64>,
+    pub critical: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthLog {
+    pub composite: f64,
+    // Temperature sensors 1-8 the controller implements, by number.
+    pub sensors: Vec<(usize, f64)>,
+    pub available_spare: f64,
+    pub spare_threshold: f64,
+    pub percentage_used: f64,
+    // Terabytes written by the host, the figure endurance ratings (TBW) are given in.
+    pub data_written: f64,
+}
+
+// Controller character devices: nvme0, not the nvme0n1 namespaces.
+fn controllers() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir("/dev")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.strip_prefix("nvme").is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit())))
+        .collect();
+    names.sort();
+    names
+}
+
+pub fn available() -> bool {
+    controllers().iter().any(|name| File::open(format!("/dev/{}", name)).is_ok())
+}
+
+pub fn read_nvme_data() -> Result<Vec<SensorSection>, String> {
+    let mut sections = Vec::new();
+    let mut errors = Vec::new();
+    for name in controllers() {
+        match read_controller(&name) {
+            Ok(section) => sections.push(section),
+            Err(e) => errors.push(e),
+        }
+    }
+    if sections.is_empty() {
+        Err(errors.first().cloned().unwrap_or_else(|| "No NVMe controllers found".to_string()))
+    } else {
+        Ok(sections)
+    }
+}
+
+fn read_controller(name: &str) -> Result<SensorSection, String> {
+    let path = format!("/dev/{}", name);
+    let device = File::open(&path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+
+    let mut log = [0u8; 512];
+    // NUMDL counts dwords from zero.
+    let numd = (log.len() / 4 - 1) as u32;
+    admin(&device, ADMIN_GET_LOG_PAGE, ALL_NAMESPACES, LOG_SMART_HEALTH | (numd << 16), &mut log)
+        .map_err(|e| format!("Failed to read the health log of {}: {}", path, e))?;
+    let mut identify = [0u8; 4096];
+    let controller = admin(&device, ADMIN_IDENTIFY, 0, IDENTIFY_CONTROLLER, &mut identify)
+        .ok()
+        .map(|()| parse_identify(&identify));
+    Ok(health_section(name, controller.as_ref(), &parse_health_log(&log)))
+}
+
+fn admin(device: &File, opcode: u8, nsid: u32, cdw10: u32, data: &mut [u8]) -> std::io::Result<()> {
+    let mut command = AdminCommand {
+        opcode,
+        nsid,
+        addr: data.as_mut_ptr() as u64,
+        data_len: data.len() as u32,
+        cdw10,
+        ..AdminCommand::default()
+    };
+    // SAFETY: `command` matches the kernel's struct and `addr` points at `data`,
+    // which outlives the call and is `data_len` bytes long.
+    let status = unsafe {
+        ioctl(device.as_raw_fd(), NVME_IOCTL_ADMIN_CMD, &mut command as *mut AdminCommand as *mut c_void)
+    };
+    match status {
+        0 => Ok(()),
+        // Positive: the controller's own status code.
+        status if status > 0 => Err(std::io::Error::other(format!("NVMe status {:#x}", status))),
+        _ => Err(std::io::Error::last_os_error()),
+    }
+}
+
+fn u16_at(data: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([data[at], data[at + 1]])
+}
+
+// Temperatures are in kelvin; 0 means not implemented.
+fn celsius(kelvin: u16) -> Option<f64> {
+    (kelvin != 0).then_some(kelvin as f64 - KELVIN)
+}
+
+pub fn parse_health_log(log: &[u8; 512]) -> HealthLog {
+    HealthLog {
+        composite: u16_at(log, 1) as f64 - KELVIN,
+        sensors: (0..8)
+            .filter_map(|i| Some((i + 1, celsius(u16_at(log, 200 + 2 * i))?)))
+            .collect(),
+        available_spare: log[3] as f64,
+        spare_threshold: log[4] as f64,
+        percentage_used: log[5] as f64,
+        data_written: u128::from_le_bytes(log[48..64].try_into().unwrap_or_default()) as f64 * DATA_UNIT_BYTES / 1e12,
+    }
+}
+
+pub fn parse_identify(identify: &[u8; 4096]) -> Controller {
+    Controller {
+        model: String::from_utf8_lossy(&identify[24..64]).trim().to_string(),
+        warning: celsius(u16_at(identify, 266)),
+        critical: celsius(u16_at(identify, 268)),
+    }
+}
+
+pub fn health_section(name: &str, controller: Option<&Controller>, log: &HealthLog) -> SensorSection {
+    let composite_limits = Limits {
+        max: controller.and_then(|controller| controller.warning),
+        crit: controller.and_then(|controller| controller.critical),
+        ..Limits::default()
+    };
+    let mut entries = vec![reading_entry("Composite", Some(log.composite), "°C", composite_limits)];
+    for &(sensor, temperature) in &log.sensors {
+        entries.push(reading_entry(&format!("Sensor {}", sensor), Some(temperature), "°C", Limits::default()));
+    }
+    entries.push(reading_entry(
+        "Life used",
+        Some(log.percentage_used),
+        "%",
+        Limits { max: Some(100.0), ..Limits::default() },
+    ));
+    entries.push(reading_entry(
+        "Available spare",
+        Some(log.available_spare),
+        "%",
+        Limits { crit_low: Some(log.spare_threshold), ..Limits::default() },
+    ));
+    entries.push(reading_entry("Data written", Some(log.data_written), "TB", Limits::default()));
+    SensorSection {
+        name: format!("nvme_log-{}", name),
+        adapter: controller.map_or("NVMe controller".to_string(), |controller| controller.model.clone()),
+        entries,
+        unparsed: Vec::new(),
+        label: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_the_health_log_and_identify_data() {
+        let mut log = [0u8; 512];
+        log[1..3].copy_from_slice(&314u16.to_le_bytes());
+        log[3] = 100;
+        log[4] = 10;
+        log[5] = 3;
+        log[48..64].copy_from_slice(&24_414_063u128.to_le_bytes());
+        log[200..202].copy_from_slice(&318u16.to_le_bytes());
+        log[202..204].copy_from_slice(&309u16.to_le_bytes());
+        let log = parse_health_log(&log);
+        assert!((log.composite - 40.85).abs() < 1e-9);
+        assert_eq!(log.sensors.len(), 2);
+        assert_eq!((log.percentage_used, log.available_spare, log.spare_threshold), (3.0, 100.0, 10.0));
+        assert!((log.data_written - 12.5).abs() < 1e-6);
+
+        let mut identify = [0u8; 4096];
+        let model = format!("{:<40}", "Samsung SSD 980 PRO 1TB");
+        identify[24..64].copy_from_slice(model.as_bytes());
+        identify[266..268].copy_from_slice(&355u16.to_le_bytes());
+        let controller = parse_identify(&identify);
+        assert_eq!(controller.model, "Samsung SSD 980 PRO 1TB");
+        assert_eq!(controller.critical, None);
+
+        let section = health_section("nvme0", Some(&controller), &log);
+        assert_eq!((section.name.as_str(), section.adapter.as_str()), ("nvme_log-nvme0", "Samsung SSD 980 PRO 1TB"));
+        let keys: Vec<&str> = section.entries.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, ["Composite", "Sensor 1", "Sensor 2", "Life used", "Available spare", "Data written"]);
+        assert!((section.entries[0].limits.max.unwrap() - 81.85).abs() < 1e-9);
+    }
+}
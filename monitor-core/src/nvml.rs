@@ -0,0 +1,174 @@
+// Just enough of NVML for NVIDIA fan control. libnvidia-ml.so.1 ships with the
+// proprietary driver, so it is loaded at runtime and machines without it simply
+// have no NVIDIA fans.
+use std::ffi::{c_char, c_int, c_uint, c_void, CStr};
+use std::sync::OnceLock;
+
+extern "C" {
+    fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void;
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+}
+
+const RTLD_NOW: c_int = 2;
+const NVML_SUCCESS: c_int = 0;
+const NVML_ERROR_NO_PERMISSION: c_int = 4;
+// nvmlFanControlPolicy_t: the driver's curve, or speeds set through NVML.
+const NVML_FAN_POLICY_MANUAL: c_uint = 1;
+
+type Device = *mut c_void;
+
+struct Library {
+    init: unsafe extern "C" fn() -> c_int,
+    device_count: unsafe extern "C" fn(*mut c_uint) -> c_int,
+    device_by_index: unsafe extern "C" fn(c_uint, *mut Device) -> c_int,
+    device_name: unsafe extern "C" fn(Device, *mut c_char, c_uint) -> c_int,
+    fan_count: unsafe extern "C" fn(Device, *mut c_uint) -> c_int,
+    fan_speed: unsafe extern "C" fn(Device, c_uint, *mut c_uint) -> c_int,
+    fan_policy: unsafe extern "C" fn(Device, c_uint, *mut c_uint) -> c_int,
+    set_fan_speed: unsafe extern "C" fn(Device, c_uint, c_uint) -> c_int,
+    set_default_fan_speed: unsafe extern "C" fn(Device, c_uint) -> c_int,
+}
+
+// An NVML call that failed, with its nvmlReturn_t.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NvmlError(pub i32);
+
+impl NvmlError {
+    pub fn needs_root(self) -> bool {
+        self.0 == NVML_ERROR_NO_PERMISSION
+    }
+}
+
+impl std::fmt::Display for NvmlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            -1 => write!(f, "NVML is not available"),
+            NVML_ERROR_NO_PERMISSION => write!(f, "NVML: permission denied"),
+            code => write!(f, "NVML error {}", code),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpuFan {
+    pub gpu: u32,
+    pub fan: u32,
+    pub name: String,
+    // Percent of the fan's maximum speed.
+    pub speed: Option<u32>,
+    pub manual: bool,
+}
+
+fn library() -> Option<&'static Library> {
+    static LIBRARY: OnceLock<Option<Library>> = OnceLock::new();
+    LIBRARY.get_or_init(load).as_ref()
+}
+
+fn load() -> Option<Library> {
+    // SAFETY: the symbols are looked up by their documented names and cast to the
+    // signatures of nvml.h; the library stays loaded for the life of the process.
+    unsafe {
+        let handle = dlopen(c"libnvidia-ml.so.1".as_ptr(), RTLD_NOW);
+        if handle.is_null() {
+            return None;
+        }
+        let symbol = |name: &CStr| {
+            let pointer = dlsym(handle, name.as_ptr());
+            (!pointer.is_null()).then_some(pointer)
+        };
+        let library = Library {
+            init: std::mem::transmute::<*mut c_void, unsafe extern "C" fn() -> c_int>(symbol(c"nvmlInit_v2")?),
+            device_count: std::mem::transmute::<*mut c_void, unsafe extern "C" fn(*mut c_uint) -> c_int>(symbol(
+                c"nvmlDeviceGetCount_v2",
+            )?),
+            device_by_index: std::mem::transmute::<*mut c_void, unsafe extern "C" fn(c_uint, *mut Device) -> c_int>(
+                symbol(c"nvmlDeviceGetHandleByIndex_v2")?,
+            ),
+            device_name: std::mem::transmute::<*mut c_void, unsafe extern "C" fn(Device, *mut c_char, c_uint) -> c_int>(
+                symbol(c"nvmlDeviceGetName")?,
+            ),
+            fan_count: std::mem::transmute::<*mut c_void, unsafe extern "C" fn(Device, *mut c_uint) -> c_int>(symbol(
+                c"nvmlDeviceGetNumFans",
+            )?),
+            fan_speed: std::mem::transmute::<*mut c_void, unsafe extern "C" fn(Device, c_uint, *mut c_uint) -> c_int>(
+                symbol(c"nvmlDeviceGetFanSpeed_v2")?,
+            ),
+            fan_policy: std::mem::transmute::<*mut c_void, unsafe extern "C" fn(Device, c_uint, *mut c_uint) -> c_int>(
+                symbol(c"nvmlDeviceGetFanControlPolicy_v2")?,
+            ),
+            set_fan_speed: std::mem::transmute::<*mut c_void, unsafe extern "C" fn(Device, c_uint, c_uint) -> c_int>(
+                symbol(c"nvmlDeviceSetFanSpeed_v2")?,
+            ),
+            set_default_fan_speed: std::mem::transmute::<*mut c_void, unsafe extern "C" fn(Device, c_uint) -> c_int>(
+                symbol(c"nvmlDeviceSetDefaultFanSpeed_v2")?,
+            ),
+        };
+        ((library.init)() == NVML_SUCCESS).then_some(library)
+    }
+}
+
+fn check(code: c_int) -> Result<(), NvmlError> {
+    if code == NVML_SUCCESS {
+        Ok(())
+    } else {
+        Err(NvmlError(code))
+    }
+}
+
+fn device(library: &Library, gpu: u32) -> Result<Device, NvmlError> {
+    let mut device: Device = std::ptr::null_mut();
+    // SAFETY: `device` is a valid out pointer for the handle.
+    check(unsafe { (library.device_by_index)(gpu, &mut device) })?;
+    Ok(device)
+}
+
+pub fn fans() -> Vec<GpuFan> {
+    let Some(library) = library() else {
+        return Vec::new();
+    };
+    let mut count = 0;
+    // SAFETY: out pointers to locals, and a name buffer of the length passed.
+    unsafe {
+        if (library.device_count)(&mut count) != NVML_SUCCESS {
+            return Vec::new();
+        }
+        let mut fans = Vec::new();
+        for gpu in 0..count {
+            let Ok(device) = device(library, gpu) else { continue };
+            let mut name = [0 as c_char; 96];
+            let name = match (library.device_name)(device, name.as_mut_ptr(), name.len() as c_uint) {
+                NVML_SUCCESS => CStr::from_ptr(name.as_ptr()).to_string_lossy().into_owned(),
+                _ => format!("GPU {}", gpu),
+            };
+            let mut fan_count = 0;
+            if (library.fan_count)(device, &mut fan_count) != NVML_SUCCESS {
+                continue;
+            }
+            for fan in 0..fan_count {
+                let mut speed = 0;
+                let mut policy = 0;
+                let speed = ((library.fan_speed)(device, fan, &mut speed) == NVML_SUCCESS).then_some(speed);
+                let manual = (library.fan_policy)(device, fan, &mut policy) == NVML_SUCCESS
+                    && policy == NVML_FAN_POLICY_MANUAL;
+                fans.push(GpuFan { gpu, fan, name: name.clone(), speed, manual });
+            }
+        }
+        fans
+    }
+}
+
+// Percent of maximum; NVML switches the fan to manual policy by itself.
+pub fn set_speed(gpu: u32, fan: u32, percent: u32) -> Result<(), NvmlError> {
+    let library = library().ok_or(NvmlError(-1))?;
+    let device = device(library, gpu)?;
+    // SAFETY: a device handle NVML just returned.
+    check(unsafe { (library.set_fan_speed)(device, fan, percent.min(100)) })
+}
+
+// Back to the driver's own curve.
+pub fn set_default(gpu: u32, fan: u32) -> Result<(), NvmlError> {
+    let library = library().ok_or(NvmlError(-1))?;
+    let device = device(library, gpu)?;
+    // SAFETY: a device handle NVML just returned.
+    check(unsafe { (library.set_default_fan_speed)(device, fan) })
+}
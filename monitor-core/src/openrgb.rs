@@ -0,0 +1,310 @@
+// A small OpenRGB SDK client (the TCP protocol the OpenRGB server speaks on port
+// 6742), enough to list RGB devices and set every LED of one to a single color.
+// It speaks protocol version 0, which every server accepts.
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+pub const DEFAULT_SERVER: &str = "127.0.0.1:6742";
+
+const MAGIC: &[u8; 4] = b"ORGB";
+const REQUEST_CONTROLLER_COUNT: u32 = 0;
+const REQUEST_CONTROLLER_DATA: u32 = 1;
+const SET_CLIENT_NAME: u32 = 50;
+const UPDATE_LEDS: u32 = 1050;
+const SET_CUSTOM_MODE: u32 = 1100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    // "#ff0000" or "ff0000".
+    pub fn parse(text: &str) -> Option<Rgb> {
+        let hex = text.trim().trim_start_matches('#');
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Rgb(byte(0)?, byte(2)?, byte(4)?))
+    }
+}
+
+impl std::fmt::Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+// Colors a device by one temperature: linear between the stops, and the first or
+// last stop's color beyond them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightingRule {
+    pub source: (String, String),
+    // (°C, color), sorted by temperature.
+    pub stops: Vec<(f64, Rgb)>,
+    pub enabled: bool,
+}
+
+impl Default for LightingRule {
+    fn default() -> Self {
+        LightingRule {
+            source: (String::new(), String::new()),
+            stops: vec![(40.0, Rgb(0x00, 0x80, 0xff)), (65.0, Rgb(0xff, 0xd0, 0x00)), (80.0, Rgb(0xff, 0x00, 0x00))],
+            enabled: false,
+        }
+    }
+}
+
+impl LightingRule {
+    pub fn color_at(&self, temperature: f64) -> Rgb {
+        let (Some(&(first_temp, first)), Some(&(last_temp, last))) = (self.stops.first(), self.stops.last()) else {
+            return Rgb(0, 0, 0);
+        };
+        if temperature <= first_temp {
+            return first;
+        }
+        if temperature >= last_temp {
+            return last;
+        }
+        for pair in self.stops.windows(2) {
+            let ((t0, c0), (t1, c1)) = (pair[0], pair[1]);
+            if temperature <= t1 {
+                let f = if t1 > t0 { (temperature - t0) / (t1 - t0) } else { 1.0 };
+                let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * f).round() as u8;
+                return Rgb(mix(c0.0, c1.0), mix(c0.1, c1.1), mix(c0.2, c1.2));
+            }
+        }
+        last
+    }
+
+    // "40:#0080ff, 80:#ff0000"
+    pub fn format_stops(&self) -> String {
+        self.stops
+            .iter()
+            .map(|(temperature, color)| format!("{}:{}", temperature, color))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn parse_stops(text: &str) -> Option<Vec<(f64, Rgb)>> {
+        let mut stops = text
+            .split(',')
+            .map(|stop| {
+                let (temperature, color) = stop.split_once(':')?;
+                Some((temperature.trim().parse().ok()?, Rgb::parse(color)?))
+            })
+            .collect::<Option<Vec<(f64, Rgb)>>>()?;
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        (!stops.is_empty()).then_some(stops)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Controller {
+    pub index: u32,
+    pub name: String,
+    pub leds: usize,
+}
+
+pub struct Client {
+    stream: TcpStream,
+}
+
+impl Client {
+    // `server` is "host:port".
+    pub fn connect(server: &str) -> Result<Client, String> {
+        let fail = |e: std::io::Error| format!("Failed to connect to OpenRGB at {}: {}", server, e);
+        let address = server
+            .to_socket_addrs()
+            .map_err(fail)?
+            .next()
+            .ok_or_else(|| format!("Failed to resolve {}", server))?;
+        let stream = TcpStream::connect_timeout(&address, Duration::from_secs(1)).map_err(fail)?;
+        stream.set_read_timeout(Some(Duration::from_secs(2))).map_err(fail)?;
+        let mut client = Client { stream };
+        client.send(0, SET_CLIENT_NAME, b"sensory\0")?;
+        Ok(client)
+    }
+
+    pub fn controllers(&mut self) -> Result<Vec<Controller>, String> {
+        self.send(0, REQUEST_CONTROLLER_COUNT, &[])?;
+        let reply = self.receive(REQUEST_CONTROLLER_COUNT)?;
+        let count = Reader::new(&reply).u32()?;
+        (0..count)
+            .map(|index| {
+                self.send(index, REQUEST_CONTROLLER_DATA, &[])?;
+                parse_controller(index, &self.receive(REQUEST_CONTROLLER_DATA)?)
+            })
+            .collect()
+    }
+
+    // Switches the device to its direct mode and sets every LED to `color`.
+    pub fn set_color(&mut self, controller: &Controller, color: Rgb) -> Result<(), String> {
+        self.send(controller.index, SET_CUSTOM_MODE, &[])?;
+        let size = 4 + 2 + 4 * controller.leds;
+        let mut data = Vec::with_capacity(size);
+        data.extend_from_slice(&(size as u32).to_le_bytes());
+        data.extend_from_slice(&(controller.leds as u16).to_le_bytes());
+        for _ in 0..controller.leds {
+            data.extend_from_slice(&[color.0, color.1, color.2, 0]);
+        }
+        self.send(controller.index, UPDATE_LEDS, &data)
+    }
+
+    fn send(&mut self, device: u32, id: u32, data: &[u8]) -> Result<(), String> {
+        let mut packet = Vec::with_capacity(16 + data.len());
+        packet.extend_from_slice(MAGIC);
+        packet.extend_from_slice(&device.to_le_bytes());
+        packet.extend_from_slice(&id.to_le_bytes());
+        packet.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        packet.extend_from_slice(data);
+        self.stream
+            .write_all(&packet)
+            .map_err(|e| format!("Failed to talk to OpenRGB: {}", e))
+    }
+
+    // The next reply to `id`; the server also sends unrequested notices (e.g. a
+    // changed device list), which are skipped.
+    fn receive(&mut self, id: u32) -> Result<Vec<u8>, String> {
+        let fail = |e: std::io::Error| format!("Failed to read from OpenRGB: {}", e);
+        loop {
+            let mut header = [0; 16];
+            self.stream.read_exact(&mut header).map_err(fail)?;
+            if &header[..4] != MAGIC {
+                return Err("OpenRGB sent a malformed packet".to_string());
+            }
+            let field = |i: usize| u32::from_le_bytes([header[i], header[i + 1], header[i + 2], header[i + 3]]);
+            let mut data = vec![0; field(12) as usize];
+            self.stream.read_exact(&mut data).map_err(fail)?;
+            if field(8) == id {
+                return Ok(data);
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data, at: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .data
+            .get(self.at..self.at + n)
+            .ok_or("OpenRGB sent a truncated device description")?;
+        self.at += n;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    // A u16 length, counting the trailing NUL, then the bytes.
+    fn string(&mut self) -> Result<String, String> {
+        let length = self.u16()? as usize;
+        let bytes = self.take(length)?;
+        Ok(String::from_utf8_lossy(bytes.strip_suffix(&[0]).unwrap_or(bytes)).into_owned())
+    }
+}
+
+// A protocol 0 device description; only the name and the LED count are kept.
+pub fn parse_controller(index: u32, data: &[u8]) -> Result<Controller, String> {
+    let mut reader = Reader::new(data);
+    reader.take(4 + 4)?; // data size, device type
+    let name = reader.string()?;
+    for _ in 0..4 {
+        reader.string()?; // description, version, serial, location
+    }
+    let modes = reader.u16()?;
+    reader.take(4)?; // active mode
+    for _ in 0..modes {
+        reader.string()?;
+        // value, flags, speed min/max, colors min/max, speed, direction, color mode
+        reader.take(9 * 4)?;
+        let colors = reader.u16()? as usize;
+        reader.take(colors * 4)?;
+    }
+    let zones = reader.u16()?;
+    for _ in 0..zones {
+        reader.string()?;
+        reader.take(4 * 4)?; // type, LEDs min/max/count
+        let matrix = reader.u16()? as usize;
+        reader.take(matrix)?;
+    }
+    let leds = reader.u16()?;
+    for _ in 0..leds {
+        reader.string()?;
+        reader.take(4)?;
+    }
+    let leds = reader.u16()? as usize;
+    Ok(Controller { index, name, leds })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_string(data: &mut Vec<u8>, text: &str) {
+        data.extend_from_slice(&(text.len() as u16 + 1).to_le_bytes());
+        data.extend_from_slice(text.as_bytes());
+        data.push(0);
+    }
+
+    #[test]
+    fn parses_a_device_description() {
+        let mut data = vec![0; 8];
+        for text in ["Corsair Lighting Node", "", "1.0", "", "HID: /dev/hidraw3"] {
+            push_string(&mut data, text);
+        }
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        push_string(&mut data, "Direct");
+        data.extend_from_slice(&[0; 36]);
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&1u16.to_le_bytes());
+        push_string(&mut data, "Fan 1");
+        data.extend_from_slice(&[0; 16]);
+        data.extend_from_slice(&8u16.to_le_bytes());
+        data.extend_from_slice(&[0; 8]);
+        data.extend_from_slice(&2u16.to_le_bytes());
+        for led in ["LED 1", "LED 2"] {
+            push_string(&mut data, led);
+            data.extend_from_slice(&[0; 4]);
+        }
+        data.extend_from_slice(&2u16.to_le_bytes());
+        data.extend_from_slice(&[0; 8]);
+
+        assert_eq!(
+            parse_controller(3, &data),
+            Ok(Controller { index: 3, name: "Corsair Lighting Node".to_string(), leds: 2 })
+        );
+        assert!(parse_controller(3, &data[..data.len() - 9]).is_err());
+    }
+
+    #[test]
+    fn blends_between_stops() {
+        let rule = LightingRule {
+            stops: LightingRule::parse_stops("80:#ff0000, 40:#0000ff").unwrap(),
+            ..LightingRule::default()
+        };
+        assert_eq!(rule.format_stops(), "40:#0000ff, 80:#ff0000");
+        assert_eq!(rule.color_at(20.0), Rgb(0, 0, 255));
+        assert_eq!(rule.color_at(60.0), Rgb(128, 0, 128));
+        assert_eq!(rule.color_at(95.0), Rgb(255, 0, 0));
+        assert_eq!(LightingRule::parse_stops("40:blue"), None);
+    }
+}
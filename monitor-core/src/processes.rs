@@ -0,0 +1,110 @@
+// The processes using the most CPU and memory, from /proc, for seeing what is behind
+// a temperature spike. CPU use is the growth of each process's CPU time between
+// refreshes, so the CPU list fills in from the second read.
+use crate::counters::Counters;
+use crate::hwmon::reading_entry;
+use crate::model::{Limits, SensorSection};
+use std::ffi::{c_int, c_long};
+use std::fs;
+use std::sync::Mutex;
+use std::time::Instant;
+
+extern "C" {
+    fn sysconf(name: c_int) -> c_long;
+}
+
+const SC_CLK_TCK: c_int = 2;
+const SC_PAGESIZE: c_int = 30;
+// Rows in each list.
+const TOP_COUNT: usize = 5;
+
+static SAMPLES: Mutex<Counters> = Mutex::new(Counters::new());
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessStat {
+    pub pid: u32,
+    pub name: String,
+    // utime + stime, in clock ticks.
+    pub cpu_ticks: u64,
+    // Resident set, in pages.
+    pub rss_pages: u64,
+}
+
+pub fn read_process_data() -> Result<Vec<SensorSection>, String> {
+    let processes: Vec<ProcessStat> = fs::read_dir("/proc")
+        .map_err(|e| format!("Failed to read /proc: {}", e))?
+        .flatten()
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            parse_stat(pid, &fs::read_to_string(entry.path().join("stat")).ok()?)
+        })
+        .collect();
+
+    // SAFETY: sysconf only reads system constants.
+    let (ticks_per_second, page_size) = unsafe { (sysconf(SC_CLK_TCK), sysconf(SC_PAGESIZE)) };
+    let now = Instant::now();
+    let mut samples = SAMPLES.lock().map_err(|_| "Process samples are unavailable".to_string())?;
+    let mut cpu: Vec<(&ProcessStat, f64)> = processes
+        .iter()
+        .filter_map(|process| {
+            let ticks = samples.rate(&process.pid.to_string(), process.cpu_ticks, now)?;
+            Some((process, ticks / ticks_per_second.max(1) as f64 * 100.0))
+        })
+        .collect();
+    samples.retain(|pid| processes.iter().any(|process| process.pid.to_string() == pid));
+    cpu.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut memory: Vec<(&ProcessStat, f64)> = processes
+        .iter()
+        .map(|process| (process, (process.rss_pages * page_size.max(0) as u64) as f64 / (1024.0 * 1024.0)))
+        .collect();
+    memory.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let section = |name: &str, top: &[(&ProcessStat, f64)], unit: &str| SensorSection {
+        name: name.to_string(),
+        adapter: "Processes".to_string(),
+        entries: top
+            .iter()
+            .take(TOP_COUNT)
+            .map(|(process, value)| {
+                reading_entry(&format!("{} ({})", process.name, process.pid), Some(*value), unit, Limits::default())
+            })
+            .collect(),
+        unparsed: Vec::new(),
+        label: None,
+    };
+    Ok(vec![section("top_cpu-virtual-0", &cpu, "%"), section("top_memory-virtual-0", &memory, "MiB")])
+}
+
+// "1234 (Web Content) S 1 ... utime stime ... rss ...": the name is in parentheses and
+// may itself contain spaces or ")", so fields are counted from the last ")".
+pub fn parse_stat(pid: u32, stat: &str) -> Option<ProcessStat> {
+    let open = stat.find('(')?;
+    let close = stat.rfind(')')?;
+    let name = stat.get(open + 1..close)?.to_string();
+    // Fields from state (field 3 in proc(5)) on.
+    let fields: Vec<&str> = stat[close + 1..].split_whitespace().collect();
+    let field = |n: usize| fields.get(n - 3)?.parse::<u64>().ok();
+    Some(ProcessStat {
+        pid,
+        name,
+        cpu_ticks: field(14)? + field(15)?,
+        rss_pages: field(24)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_proc_stat() {
+        let stat = "4242 (Web Content (2)) S 4100 4100 4100 0 -1 4194560 98234 0 12 0 1500 250 0 0 20 0 \
+                    31 0 123456 3120000000 51200 18446744073709551615 1 1 0 0 0 0 0 4096 0 0 0 0 17 3 0 0 0 0 0";
+        assert_eq!(
+            parse_stat(4242, stat),
+            Some(ProcessStat { pid: 4242, name: "Web Content (2)".to_string(), cpu_ticks: 1750, rss_pages: 51200 })
+        );
+        assert_eq!(parse_stat(1, "1 (init"), None);
+    }
+}
@@ -0,0 +1,229 @@
+// A headless machine's sensors, scraped from its node_exporter (or anything else
+// exporting the same node_hwmon_* metrics) over plain HTTP. Each hwmon chip becomes
+// a section, its channels keyed by their labels as on the machine itself. Scrapes
+// are spaced by the configured interval; polls in between repeat the last one.
+use crate::hwmon::reading_entry;
+use crate::model::{Limits, SensorSection};
+use crate::source::{self, BackgroundSource, ReadResult, SensorSource};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+
+// node_exporter's hwmon metrics: (name, unit, the limit it is, or None for the reading).
+const METRICS: [(&str, &str, Option<&str>); 10] = [
+    ("node_hwmon_temp_celsius", "°C", None),
+    ("node_hwmon_temp_max_celsius", "°C", Some("high")),
+    ("node_hwmon_temp_crit_celsius", "°C", Some("crit")),
+    ("node_hwmon_fan_rpm", "RPM", None),
+    ("node_hwmon_fan_min_rpm", "RPM", Some("min")),
+    ("node_hwmon_in_volts", "V", None),
+    ("node_hwmon_in_min_volts", "V", Some("min")),
+    ("node_hwmon_in_max_volts", "V", Some("max")),
+    ("node_hwmon_curr_amps", "A", None),
+    ("node_hwmon_power_average_watt", "W", None),
+];
+
+// Scrapes on a thread of its own, so a slow or unreachable exporter never holds up a
+// refresh; polls return the latest scrape.
+pub struct PrometheusSource(BackgroundSource);
+
+impl PrometheusSource {
+    pub fn new(url: &str, interval: Duration) -> Self {
+        let target = url.to_string();
+        PrometheusSource(source::background(url, interval, move || read_prometheus_data(&target)))
+    }
+}
+
+impl SensorSource for PrometheusSource {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn poll(&mut self) -> ReadResult {
+        self.0.poll()
+    }
+}
+
+pub fn read_prometheus_data(url: &str) -> ReadResult {
+    scrape(url).and_then(|metrics| parse_metrics(&host(url), &metrics))
+}
+
+// "http://host:9100/metrics" -> ("host:9100", "/metrics").
+fn split_url(url: &str) -> Result<(&str, &str), String> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| format!("Only http:// URLs can be scraped: {}", url))?;
+    Ok(match rest.find('/') {
+        Some(at) => (&rest[..at], &rest[at..]),
+        None => (rest, "/metrics"),
+    })
+}
+
+fn host(url: &str) -> String {
+    split_url(url).map_or(url.to_string(), |(host, _)| host.to_string())
+}
+
+fn scrape(url: &str) -> Result<String, String> {
+    let (host, path) = split_url(url)?;
+    let address = if host.contains(':') { host.to_string() } else { format!("{}:80", host) };
+    let fail = |e: std::io::Error| format!("Failed to scrape {}: {}", url, e);
+    let address = address
+        .to_socket_addrs()
+        .map_err(fail)?
+        .next()
+        .ok_or_else(|| format!("Failed to resolve {}", host))?;
+    let mut stream = TcpStream::connect_timeout(&address, Duration::from_secs(1)).map_err(fail)?;
+    stream.set_read_timeout(Some(Duration::from_secs(2))).map_err(fail)?;
+    // HTTP/1.0, so the body is neither chunked nor kept open.
+    write!(stream, "GET {} HTTP/1.0\r\nHost: {}\r\nAccept: text/plain\r\n\r\n", path, host).map_err(fail)?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).map_err(fail)?;
+    let (head, body) = response.split_once("\r\n\r\n").ok_or_else(|| format!("{} sent no body", url))?;
+    let status = head.lines().next().unwrap_or_default();
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(format!("{} answered {}", url, status));
+    }
+    Ok(body.to_string())
+}
+
+type Labels = Vec<(String, String)>;
+
+// One hwmon channel of the scraped machine.
+struct Channel<'a> {
+    sensor: String,
+    unit: &'a str,
+    value: Option<f64>,
+    limits: Limits,
+}
+
+// `name{label="value",...} 12.5`; None for comments and metrics without labels.
+fn parse_line(line: &str) -> Option<(&str, Labels, f64)> {
+    let (name, mut rest) = line.split_once('{')?;
+    let mut labels = Vec::new();
+    loop {
+        rest = rest.trim_start_matches(',');
+        if let Some(after) = rest.strip_prefix('}') {
+            rest = after;
+            break;
+        }
+        let (key, after) = rest.split_once("=\"")?;
+        let mut value = String::new();
+        let mut chars = after.char_indices();
+        let end = loop {
+            match chars.next()? {
+                (at, '"') => break at + 1,
+                (_, '\\') => match chars.next()?.1 {
+                    'n' => value.push('\n'),
+                    escaped => value.push(escaped),
+                },
+                (_, c) => value.push(c),
+            }
+        };
+        labels.push((key.to_string(), value));
+        rest = &after[end..];
+    }
+    let value = rest.split_whitespace().next()?.parse().ok()?;
+    Some((name, labels, value))
+}
+
+pub fn parse_metrics(host: &str, metrics: &str) -> ReadResult {
+    let lines: Vec<_> = metrics.lines().filter(|line| !line.starts_with('#')).filter_map(parse_line).collect();
+    let label = |labels: &[(String, String)], key: &str| {
+        labels.iter().find(|(name, _)| name == key).map(|(_, value)| value.clone())
+    };
+    let chip_name = |chip: &str| {
+        lines
+            .iter()
+            .find(|(name, labels, _)| {
+                *name == "node_hwmon_chip_names" && label(labels, "chip").as_deref() == Some(chip)
+            })
+            .and_then(|(_, labels, _)| label(labels, "chip_name"))
+    };
+    let sensor_label = |chip: &str, sensor: &str| {
+        lines
+            .iter()
+            .find(|(name, labels, _)| {
+                *name == "node_hwmon_sensor_label"
+                    && label(labels, "chip").as_deref() == Some(chip)
+                    && label(labels, "sensor").as_deref() == Some(sensor)
+            })
+            .and_then(|(_, labels, _)| label(labels, "label"))
+    };
+
+    // The chips and their channels, in exposition order.
+    let mut chips: Vec<(String, Vec<Channel>)> = Vec::new();
+    for (name, labels, value) in &lines {
+        let Some(&(_, unit, limit)) = METRICS.iter().find(|(metric, _, _)| metric == name) else { continue };
+        let (Some(chip), Some(sensor)) = (label(labels, "chip"), label(labels, "sensor")) else { continue };
+        let index = chips.iter().position(|(id, _)| *id == chip).unwrap_or_else(|| {
+            chips.push((chip.clone(), Vec::new()));
+            chips.len() - 1
+        });
+        let channels = &mut chips[index].1;
+        let index = channels.iter().position(|channel| channel.sensor == sensor).unwrap_or_else(|| {
+            channels.push(Channel { sensor: sensor.clone(), unit, value: None, limits: Limits::default() });
+            channels.len() - 1
+        });
+        let channel = &mut channels[index];
+        match limit {
+            None => channel.value = Some(*value),
+            Some("high") => channel.limits.high = Some(*value),
+            Some("crit") => channel.limits.crit = Some(*value),
+            Some("min") => channel.limits.min = Some(*value),
+            Some(_) => channel.limits.max = Some(*value),
+        }
+    }
+    if chips.is_empty() {
+        return Err(format!("{} exports no node_hwmon metrics", host));
+    }
+    Ok(chips
+        .into_iter()
+        .map(|(chip, channels)| SensorSection {
+            name: format!("{}-{}", chip_name(&chip).unwrap_or_else(|| chip.clone()), chip),
+            adapter: format!("Prometheus {}", host),
+            entries: channels
+                .iter()
+                .map(|channel| {
+                    let key = sensor_label(&chip, &channel.sensor).unwrap_or_else(|| channel.sensor.clone());
+                    reading_entry(&key, channel.value, channel.unit, channel.limits)
+                })
+                .collect(),
+            unparsed: Vec::new(),
+            label: None,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_node_exporter_hwmon_metrics() {
+        let metrics = r#"# HELP node_hwmon_temp_celsius Hardware monitor for temperature (input)
+# TYPE node_hwmon_temp_celsius gauge
+node_hwmon_chip_names{chip="platform_coretemp_0",chip_name="coretemp"} 1
+node_hwmon_sensor_label{chip="platform_coretemp_0",label="Package id 0",sensor="temp1"} 1
+node_hwmon_temp_celsius{chip="platform_coretemp_0",sensor="temp1"} 45
+node_hwmon_temp_crit_celsius{chip="platform_coretemp_0",sensor="temp1"} 100
+node_hwmon_temp_max_celsius{chip="platform_coretemp_0",sensor="temp1"} 84
+node_hwmon_fan_rpm{chip="platform_nct6775_656",sensor="fan2"} 1185
+node_load1 0.52
+"#;
+        let sections = parse_metrics("nas:9100", metrics).unwrap();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].name, "coretemp-platform_coretemp_0");
+        let package = &sections[0].entries[0];
+        assert_eq!((package.key.as_str(), package.number), ("Package id 0", Some(45.0)));
+        assert_eq!((package.limits.high, package.limits.crit), (Some(84.0), Some(100.0)));
+        assert_eq!(sections[1].entries[0].key, "fan2");
+        assert_eq!(sections[1].adapter, "Prometheus nas:9100");
+
+        let (_, labels, _) = parse_line(r#"x{a="q\"uote",b="c,d"} 1"#).unwrap();
+        assert_eq!(labels, [("a".to_string(), "q\"uote".to_string()), ("b".to_string(), "c,d".to_string())]);
+        assert_eq!(split_url("http://nas:9100"), Ok(("nas:9100", "/metrics")));
+        assert!(parse_metrics("nas:9100", "node_load1 0.5\n").is_err());
+    }
+}
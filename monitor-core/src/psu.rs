@@ -0,0 +1,158 @@
+// Power supplies with a hwmon driver, such as corsair-psu ("corsairpsu-hid-3-1"). The
+// driver labels its channels "v_out +12v", "curr +12v", "power +12v" and so on, listed
+// by kind; this regroups them by rail, input first, and adds the input power and the
+// efficiency it implies.
+use crate::hwmon::reading_entry;
+use crate::model::{Limits, SensorEntry, SensorSection};
+
+const PSU_CHIPS: [&str; 1] = ["corsairpsu"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Quantity {
+    Voltage,
+    Current,
+    Power,
+}
+
+impl Quantity {
+    fn name(self) -> &'static str {
+        match self {
+            Quantity::Voltage => "voltage",
+            Quantity::Current => "current",
+            Quantity::Power => "power",
+        }
+    }
+}
+
+pub fn annotate(sections: &mut [SensorSection]) {
+    for section in sections {
+        let chip = section.name.split('-').next().unwrap_or_default();
+        if PSU_CHIPS.contains(&chip) {
+            section.entries = group_rails(std::mem::take(&mut section.entries));
+        }
+    }
+}
+
+// The rail ("Input", "Output", "+12V") and quantity a driver label names.
+fn rail(label: &str) -> Option<(String, Quantity)> {
+    let (kind, rail) = label.split_once(' ').unwrap_or((label, ""));
+    let (rail, quantity) = match (kind, rail) {
+        ("v_in", "") => ("in", Quantity::Voltage),
+        ("v_out", rail) => (rail, Quantity::Voltage),
+        ("curr", rail) => (rail, Quantity::Current),
+        ("power", "total") => ("out", Quantity::Power),
+        ("power", rail) => (rail, Quantity::Power),
+        _ => return None,
+    };
+    let rail = match rail {
+        "in" => "Input".to_string(),
+        "out" => "Output".to_string(),
+        rail if rail.starts_with('+') || rail.starts_with('-') => rail.to_uppercase(),
+        _ => return None,
+    };
+    Some((rail, quantity))
+}
+
+pub fn group_rails(entries: Vec<SensorEntry>) -> Vec<SensorEntry> {
+    let mut rails: Vec<(String, Vec<(Quantity, SensorEntry)>)> = Vec::new();
+    let mut others = Vec::new();
+    for mut entry in entries {
+        let Some((name, quantity)) = rail(&entry.key) else {
+            others.push(entry);
+            continue;
+        };
+        entry.key = format!("{} {}", name, quantity.name());
+        match rails.iter_mut().find(|(rail, _)| *rail == name) {
+            Some((_, readings)) => readings.push((quantity, entry)),
+            None => rails.push((name, vec![(quantity, entry)])),
+        }
+    }
+    if rails.is_empty() {
+        return others;
+    }
+    let rank = |name: &str| match name {
+        "Input" => 0,
+        "Output" => 1,
+        _ => 2,
+    };
+    rails.sort_by_key(|(name, _)| rank(name));
+
+    let reading = |rails: &[(String, Vec<(Quantity, SensorEntry)>)], name: &str, quantity: Quantity| {
+        rails
+            .iter()
+            .find(|(rail, _)| rail == name)
+            .and_then(|(_, readings)| readings.iter().find(|(q, _)| *q == quantity))
+            .and_then(|(_, entry)| entry.number)
+    };
+    // Most PSUs only measure the input voltage and current.
+    let input = reading(&rails, "Input", Quantity::Power).or_else(|| {
+        let power = reading(&rails, "Input", Quantity::Voltage)? * reading(&rails, "Input", Quantity::Current)?;
+        if let Some((_, readings)) = rails.iter_mut().find(|(rail, _)| rail == "Input") {
+            readings.push((Quantity::Power, reading_entry("Input power", Some(power), "W", Limits::default())));
+        }
+        Some(power)
+    });
+    let output = reading(&rails, "Output", Quantity::Power);
+    // The input side is an estimate on some models; a ratio above 1 means it is off.
+    let efficiency = match (input, output) {
+        (Some(input), Some(output)) if input > 0.0 && output <= input => Some(output / input * 100.0),
+        _ => None,
+    };
+
+    let mut grouped = Vec::new();
+    for (_, mut readings) in rails {
+        readings.sort_by_key(|(quantity, _)| *quantity as u8);
+        grouped.extend(readings.into_iter().map(|(_, entry)| entry));
+    }
+    if efficiency.is_some() {
+        grouped.push(reading_entry("Efficiency", efficiency, "%", Limits::default()));
+    }
+    grouped.extend(others);
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_corsair_rails() {
+        let channels = [
+            ("v_in", 230.0, "V"),
+            ("v_out +12v", 12.1, "V"),
+            ("v_out +5v", 5.0, "V"),
+            ("curr in", 1.0, "A"),
+            ("curr +12v", 15.0, "A"),
+            ("curr +5v", 2.0, "A"),
+            ("power total", 207.0, "W"),
+            ("power +12v", 181.5, "W"),
+            ("power +5v", 10.0, "W"),
+            ("vrm temp", 41.0, "°C"),
+        ];
+        let entries = channels
+            .iter()
+            .map(|&(key, value, unit)| reading_entry(key, Some(value), unit, Limits::default()))
+            .collect();
+        let grouped = group_rails(entries);
+        let keys: Vec<&str> = grouped.iter().map(|entry| entry.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            [
+                "Input voltage",
+                "Input current",
+                "Input power",
+                "Output power",
+                "+12V voltage",
+                "+12V current",
+                "+12V power",
+                "+5V voltage",
+                "+5V current",
+                "+5V power",
+                "Efficiency",
+                "vrm temp",
+            ]
+        );
+        assert_eq!(grouped[2].number, Some(230.0));
+        assert_eq!(grouped[10].number, Some(90.0));
+    }
+}
@@ -0,0 +1,134 @@
+// Average power from the RAPL energy counters under /sys/class/powercap. Each zone
+// counts microjoules and wraps at max_energy_range_uj, so power is the difference
+// between two samples over the time between them. Since kernel 5.10 the counters are
+// root-only by default, in which case the readings show as N/A.
+use crate::counters::Counters;
+use crate::hwmon::{format_value, read_trimmed, reading_entry};
+use crate::model::{Limits, SensorEntry, SensorSection};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+const POWERCAP_ROOT: &str = "/sys/class/powercap";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Zone {
+    pub path: PathBuf,
+    // "package-0", "core", "uncore" (the integrated GPU), "dram", "psys".
+    pub name: String,
+}
+
+// The last sample of each zone, per reader.
+static SAMPLES: Mutex<Counters> = Mutex::new(Counters::new());
+
+// intel-rapl:N packages and their intel-rapl:N:M subzones; AMD CPUs register theirs
+// under the same names.
+pub fn zones() -> Vec<Zone> {
+    let mut zones: Vec<Zone> = fs::read_dir(POWERCAP_ROOT)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("intel-rapl:"))
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = read_trimmed(&path.join("name"))?;
+            Some(Zone { path, name })
+        })
+        .collect();
+    zones.sort_by(|a, b| a.path.cmp(&b.path));
+    zones
+}
+
+// Watts since `reader`'s previous call for this zone; `None` on the first call and
+// when the counter cannot be read. Each reader keeps its own samples, so the Intel GPU
+// section and the RAPL section don't shorten each other's interval.
+pub fn power(reader: &str, zone: &Path) -> Option<f64> {
+    let energy: u64 = read_trimmed(&zone.join("energy_uj"))?.parse().ok()?;
+    let range: u64 = read_trimmed(&zone.join("max_energy_range_uj"))
+        .and_then(|range| range.parse().ok())
+        .unwrap_or(u64::MAX);
+    let (before, seconds) = SAMPLES.lock().ok()?.advance(&format!("{}:{}", reader, zone.display()), energy, Instant::now())?;
+    watts(before, energy, range, seconds)
+}
+
+// A section per package (and psys, the whole platform on recent laptops) with the
+// package's power first, then its core, uncore and DRAM subzones. The long-term
+// limit (PL1) is the package's max.
+pub fn read_rapl_data() -> Result<Vec<SensorSection>, String> {
+    let zones = zones();
+    let sections: Vec<SensorSection> = zones
+        .iter()
+        .filter(|zone| is_package(&zone.path))
+        .map(|package| {
+            let prefix = format!("{}:", package.path.file_name().unwrap_or_default().to_string_lossy());
+            let subzones = zones
+                .iter()
+                .filter(|zone| zone.path.file_name().is_some_and(|name| name.to_string_lossy().starts_with(&prefix)));
+            let limit = read_trimmed(&package.path.join("constraint_0_power_limit_uw"))
+                .and_then(|limit| limit.parse::<f64>().ok())
+                .filter(|&limit| limit > 0.0)
+                .map(|limit| limit / 1e6);
+            let mut entries = vec![zone_entry(package, Limits { max: limit, ..Limits::default() })];
+            entries.extend(subzones.map(|zone| zone_entry(zone, Limits::default())));
+            SensorSection {
+                name: format!("rapl-{}", package.name),
+                adapter: "Powercap".to_string(),
+                entries,
+                unparsed: Vec::new(),
+                label: None,
+            }
+        })
+        .collect();
+    if sections.is_empty() {
+        Err("No RAPL zones found".to_string())
+    } else {
+        Ok(sections)
+    }
+}
+
+// A zone's average power, with the energy counter it comes from in the details.
+fn zone_entry(zone: &Zone, limits: Limits) -> SensorEntry {
+    let mut entry = reading_entry(&zone.name, power("rapl", &zone.path), "W", limits);
+    let energy = read_trimmed(&zone.path.join("energy_uj")).and_then(|energy| energy.parse::<f64>().ok());
+    if let Some(energy) = energy {
+        let counter = format!("energy = {}", format_value(energy / 1e6, "J"));
+        entry.additional_info = Some(match entry.additional_info.take() {
+            Some(info) => format!("{}, {}", info, counter),
+            None => counter,
+        });
+    }
+    entry
+}
+
+pub fn available() -> bool {
+    zones().iter().any(|zone| is_package(&zone.path))
+}
+
+// intel-rapl:0, not the intel-rapl:0:1 subzone.
+fn is_package(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name.to_string_lossy().matches(':').count() == 1)
+}
+
+// Microjoules counted between two samples `seconds` apart, as watts.
+pub fn watts(before: u64, after: u64, range: u64, seconds: f64) -> Option<f64> {
+    if seconds <= 0.0 {
+        return None;
+    }
+    let delta = if after >= before { after - before } else { range - before + after };
+    Some(delta as f64 / 1e6 / seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_energy_to_power() {
+        assert_eq!(watts(1_000_000, 6_000_000, u64::MAX, 1.0), Some(5.0));
+        assert_eq!(watts(2_000_000, 3_000_000, u64::MAX, 2.0), Some(0.5));
+        // Wrapped at a 262 J range.
+        assert_eq!(watts(261_000_000, 1_000_000, 262_000_000, 1.0), Some(2.0));
+        assert_eq!(watts(0, 1, u64::MAX, 0.0), None);
+    }
+}
@@ -0,0 +1,187 @@
+// Sensors the user defines in the config: a file to read or a shell command to run
+// each sample, and how to get a reading out of what it prints. For anything without
+// a backend of its own, like a vendor fan_boost_mode file.
+use crate::hwmon::reading_entry;
+use crate::model::{Limits, SensorEntry, SensorSection};
+use std::fs;
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// The sensors are read on a background thread this often, since commands can be slow.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+// A hung command still holds up the sensors after it, so it is killed after this.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Probe {
+    File(String),
+    // Run through `sh -c`, so pipes work.
+    Command(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptSensor {
+    pub label: String,
+    pub probe: Option<Probe>,
+    pub unit: String,
+    // Steps applied to the output in order: `line <n>`, `field <n>` (1-based,
+    // whitespace-separated), `after <word>`, `scale <factor>`, or `text` to show the
+    // result as words. Without one, the first number in the output is the reading.
+    pub parse: String,
+}
+
+impl ScriptSensor {
+    pub fn new(label: &str) -> Self {
+        ScriptSensor { label: label.to_string(), probe: None, unit: String::new(), parse: String::new() }
+    }
+}
+
+pub fn read_script_data(sensors: &[ScriptSensor]) -> Result<Vec<SensorSection>, String> {
+    if sensors.is_empty() {
+        return Err("No custom sensors configured".to_string());
+    }
+    let entries = sensors
+        .iter()
+        .map(|sensor| {
+            let output = match &sensor.probe {
+                Some(Probe::File(path)) => fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e)),
+                Some(Probe::Command(command)) => run(command),
+                None => Err("no file or command set".to_string()),
+            };
+            sensor_entry(sensor, output)
+        })
+        .collect();
+    Ok(vec![SensorSection {
+        name: "custom-virtual-0".to_string(),
+        adapter: "Custom sensors".to_string(),
+        entries,
+        unparsed: Vec::new(),
+        label: None,
+    }])
+}
+
+fn run(command: &str) -> Result<String, String> {
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", command, e))?;
+    // Read while it runs: a command that fills the pipe would otherwise block until killed.
+    let (sender, receiver) = mpsc::channel();
+    if let Some(mut stdout) = child.stdout.take() {
+        thread::spawn(move || {
+            let mut output = String::new();
+            let _ = sender.send(stdout.read_to_string(&mut output).map(|_| output));
+        });
+    }
+    let deadline = Instant::now() + COMMAND_TIMEOUT;
+    let output = receiver.recv_timeout(COMMAND_TIMEOUT);
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(10)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("{} took longer than {:?}", command, COMMAND_TIMEOUT));
+            }
+        }
+    }
+    match output {
+        Ok(output) => output.map_err(|e| format!("Failed to read from {}: {}", command, e)),
+        Err(_) => Err(format!("{} took longer than {:?}", command, COMMAND_TIMEOUT)),
+    }
+}
+
+// The entry for one sensor; N/A, with the reason beside it, when reading or parsing
+// failed.
+pub fn sensor_entry(sensor: &ScriptSensor, output: Result<String, String>) -> SensorEntry {
+    let reading = output.and_then(|output| evaluate(&output, &sensor.parse));
+    match reading {
+        Ok(Reading::Number(number)) => reading_entry(&sensor.label, Some(number), &sensor.unit, Limits::default()),
+        Ok(Reading::Text(text)) => SensorEntry::from_text(&sensor.label, &text),
+        Err(e) => {
+            let mut entry = reading_entry(&sensor.label, None, &sensor.unit, Limits::default());
+            entry.additional_info = Some(e);
+            entry
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reading {
+    Number(f64),
+    Text(String),
+}
+
+pub fn evaluate(output: &str, parse: &str) -> Result<Reading, String> {
+    let mut text = output.trim().to_string();
+    let mut scale = 1.0;
+    let mut words = parse.split_whitespace();
+    while let Some(step) = words.next() {
+        if step == "text" {
+            return Ok(Reading::Text(text));
+        }
+        let argument = words.next().ok_or_else(|| format!("\"{}\" needs an argument", step))?;
+        let index = || argument.parse::<usize>().ok().filter(|&n| n > 0).ok_or_else(|| format!("bad {} number", step));
+        text = match step {
+            "line" => text.lines().nth(index()? - 1).ok_or("no such line")?.trim().to_string(),
+            "field" => text.split_whitespace().nth(index()? - 1).ok_or("no such field")?.to_string(),
+            "after" => {
+                let (_, rest) = text.split_once(argument).ok_or_else(|| format!("no \"{}\" in output", argument))?;
+                rest.trim().to_string()
+            }
+            "scale" => {
+                scale = argument.parse().map_err(|_| "bad scale".to_string())?;
+                text
+            }
+            _ => return Err(format!("unknown step \"{}\"", step)),
+        };
+    }
+    first_number(&text)
+        .map(|number| Reading::Number(number * scale))
+        .ok_or_else(|| "no number in output".to_string())
+}
+
+// The first number in the text: "45000" in "temp: 45000 mC", "-3.5" in "-3.5dB".
+fn first_number(text: &str) -> Option<f64> {
+    let bytes = text.as_bytes();
+    let start = (0..bytes.len()).find(|&i| {
+        bytes[i].is_ascii_digit() || (bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit))
+    })?;
+    let end = (start + 1..bytes.len())
+        .find(|&i| !(bytes[i].is_ascii_digit() || bytes[i] == b'.'))
+        .unwrap_or(bytes.len());
+    text[start..end].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluates_parse_steps() {
+        assert_eq!(evaluate("2\n", ""), Ok(Reading::Number(2.0)));
+        assert_eq!(evaluate("temp: 45000 mC", "scale 0.001"), Ok(Reading::Number(45.0)));
+        let table = "name load temp\ngpu0 35% 61C\n";
+        assert_eq!(evaluate(table, "line 2 field 3"), Ok(Reading::Number(61.0)));
+        assert_eq!(evaluate("mode: quiet\n", "after mode: text"), Ok(Reading::Text("quiet".to_string())));
+        assert_eq!(evaluate("offset -3.5dB", "after offset"), Ok(Reading::Number(-3.5)));
+        assert!(evaluate("n/a", "").is_err());
+        assert!(evaluate("1 2", "field 3").is_err());
+        assert!(evaluate("1", "field").is_err());
+
+        let sensor = ScriptSensor { unit: "°C".to_string(), ..ScriptSensor::new("Ambient") };
+        let entry = sensor_entry(&sensor, Err("/tmp/ambient: not found".to_string()));
+        assert!(entry.unavailable);
+        assert_eq!(entry.additional_info.as_deref(), Some("/tmp/ambient: not found"));
+
+        // More than a pipe buffer's worth, read as it is printed.
+        assert_eq!(run("head -c 200000 /dev/zero | tr '\\0' x").map(|output| output.len()), Ok(200_000));
+        assert!(run("sleep 5").is_err());
+    }
+}
@@ -0,0 +1,276 @@
+// The label, ignore and compute statements of /etc/sensors3.conf and /etc/sensors.d, so the sysfs backend
+// names and scales channels the way `sensors` does.
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+// One `chip "pattern" ...` block and the statements that follow it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChipBlock {
+    pub patterns: Vec<String>,
+    pub labels: Vec<(String, String)>,
+    pub ignores: Vec<String>,
+    pub computes: Vec<(String, String)>,
+}
+
+// The statements that apply to one chip, later blocks overriding earlier ones as in libsensors.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChipConfig {
+    pub labels: Vec<(String, String)>,
+    pub ignores: Vec<String>,
+    pub computes: Vec<(String, String)>,
+}
+
+impl ChipConfig {
+    pub fn label(&self, feature: &str) -> Option<&str> {
+        self.labels.iter().rev().find(|(name, _)| name == feature).map(|(_, label)| label.as_str())
+    }
+
+    pub fn ignored(&self, feature: &str) -> bool {
+        self.ignores.iter().any(|name| name == feature)
+    }
+
+    // The displayed value for a raw reading; a feature without a usable compute keeps its reading.
+    pub fn compute(&self, feature: &str, value: f64) -> f64 {
+        self.computes
+            .iter()
+            .rev()
+            .find(|(name, _)| name == feature)
+            .and_then(|(_, expression)| evaluate(expression, value))
+            .unwrap_or(value)
+    }
+}
+
+// Blocks from sensors3.conf (or the older sensors.conf) and then sensors.d, read once.
+pub fn system_blocks() -> &'static [ChipBlock] {
+    static BLOCKS: OnceLock<Vec<ChipBlock>> = OnceLock::new();
+    BLOCKS.get_or_init(|| {
+        let main = ["/etc/sensors3.conf", "/etc/sensors.conf"].iter().find_map(|path| fs::read_to_string(path).ok());
+        let mut blocks = main.map(|text| parse_config(&text)).unwrap_or_default();
+        let mut extra: Vec<_> = fs::read_dir(Path::new("/etc/sensors.d"))
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| !path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')))
+            .collect();
+        extra.sort();
+        for path in extra {
+            if let Ok(text) = fs::read_to_string(&path) {
+                blocks.extend(parse_config(&text));
+            }
+        }
+        blocks
+    })
+}
+
+pub fn chip_config(blocks: &[ChipBlock], chip: &str) -> ChipConfig {
+    let mut config = ChipConfig::default();
+    for block in blocks.iter().filter(|block| block.patterns.iter().any(|pattern| matches(pattern, chip))) {
+        config.labels.extend(block.labels.iter().cloned());
+        config.ignores.extend(block.ignores.iter().cloned());
+        config.computes.extend(block.computes.iter().cloned());
+    }
+    config
+}
+
+// Statements before the first `chip` line, and `bus`/`set` statements, don't affect naming and are skipped.
+pub fn parse_config(text: &str) -> Vec<ChipBlock> {
+    let mut blocks: Vec<ChipBlock> = Vec::new();
+    for line in text.lines() {
+        let words = split_words(line);
+        let Some((statement, args)) = words.split_first() else { continue };
+        match (statement.as_str(), args) {
+            ("chip", patterns) if !patterns.is_empty() => {
+                blocks.push(ChipBlock { patterns: patterns.to_vec(), ..ChipBlock::default() })
+            }
+            ("label", [feature, label, ..]) => {
+                if let Some(block) = blocks.last_mut() {
+                    block.labels.push((feature.clone(), label.clone()));
+                }
+            }
+            ("ignore", [feature, ..]) => {
+                if let Some(block) = blocks.last_mut() {
+                    block.ignores.push(feature.clone());
+                }
+            }
+            ("compute", [feature, _, ..]) => {
+                if let (Some(block), Some(expression)) = (blocks.last_mut(), forward_expression(line)) {
+                    block.computes.push((feature.clone(), expression));
+                }
+            }
+            _ => {}
+        }
+    }
+    blocks
+}
+
+// The expression before the comma of `compute <feature> <forward>, <reverse>`, which may contain spaces.
+fn forward_expression(line: &str) -> Option<String> {
+    let line = line.split('#').next()?.trim_start().strip_prefix("compute")?.trim_start();
+    let (_, expressions) = line.split_once(char::is_whitespace)?;
+    let forward = expressions.split(',').next()?.trim();
+    (!forward.is_empty()).then(|| forward.to_string())
+}
+
+// Words of a statement line: bare words, "quoted strings" with \" escapes, commas dropped, # to the end ignored.
+fn split_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c == '#' {
+            break;
+        } else if c.is_whitespace() || c == ',' {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut word = String::new();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' => word.extend(chars.next()),
+                    _ => word.push(c),
+                }
+            }
+            words.push(word);
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == ',' || c == '"' || c == '#' {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            words.push(word);
+        }
+    }
+    words
+}
+
+// Chip patterns are names with `*` standing for any run of characters: "nct6775-*", "*-isa-0290".
+pub fn matches(pattern: &str, chip: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = chip.strip_prefix(first) else { return false };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else { return rest.is_empty() };
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+// Evaluates a compute expression with `@` as the raw reading: numbers, + - * /, parentheses, and the
+// libsensors unary operators ^ (e to the power) and ` (natural log). References to other features aren't
+// supported and give `None`.
+pub fn evaluate(expression: &str, at: f64) -> Option<f64> {
+    let tokens: Vec<char> = expression.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut position = 0;
+    let value = sum(&tokens, &mut position, at)?;
+    (position == tokens.len() && value.is_finite()).then_some(value)
+}
+
+fn sum(tokens: &[char], position: &mut usize, at: f64) -> Option<f64> {
+    let mut value = product(tokens, position, at)?;
+    while let Some(&op) = tokens.get(*position).filter(|&&c| c == '+' || c == '-') {
+        *position += 1;
+        let right = product(tokens, position, at)?;
+        value = if op == '+' { value + right } else { value - right };
+    }
+    Some(value)
+}
+
+fn product(tokens: &[char], position: &mut usize, at: f64) -> Option<f64> {
+    let mut value = unary(tokens, position, at)?;
+    while let Some(&op) = tokens.get(*position).filter(|&&c| c == '*' || c == '/') {
+        *position += 1;
+        let right = unary(tokens, position, at)?;
+        value = if op == '*' { value * right } else { value / right };
+    }
+    Some(value)
+}
+
+fn unary(tokens: &[char], position: &mut usize, at: f64) -> Option<f64> {
+    match tokens.get(*position)? {
+        '-' => {
+            *position += 1;
+            Some(-unary(tokens, position, at)?)
+        }
+        '^' => {
+            *position += 1;
+            Some(unary(tokens, position, at)?.exp())
+        }
+        '`' => {
+            *position += 1;
+            Some(unary(tokens, position, at)?.ln())
+        }
+        '@' => {
+            *position += 1;
+            Some(at)
+        }
+        '(' => {
+            *position += 1;
+            let value = sum(tokens, position, at)?;
+            if tokens.get(*position) != Some(&')') {
+                return None;
+            }
+            *position += 1;
+            Some(value)
+        }
+        _ => {
+            let start = *position;
+            while tokens.get(*position).is_some_and(|c| c.is_ascii_digit() || *c == '.') {
+                *position += 1;
+            }
+            tokens[start..*position].iter().collect::<String>().parse().ok()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_matching_chip_blocks() {
+        let blocks = parse_config(
+            "# stock configuration\n\
+             chip \"nct6775-*\" \"nct6776-*\"\n\
+             \x20   label in1 \"+12V\"\n\
+             \x20   compute in1 @*(1+56/10), @/(1+56/10)\n\
+             \x20   compute in2 @ * 2, @ / 2\n\
+             \x20   ignore fan3\n\
+             \x20   set in1_min 10.8\n\
+             chip \"*-isa-0290\"\n\
+             \x20   label in1 \"12V rail\"  # overrides the block above\n\
+             \x20   label temp1 \"CPU \\\"socket\\\"\"\n\
+             chip \"coretemp-isa-*\"\n\
+             \x20   ignore temp2\n",
+        );
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].patterns, ["nct6775-*", "nct6776-*"]);
+
+        let config = chip_config(&blocks, "nct6775-isa-0290");
+        assert_eq!(config.label("in1"), Some("12V rail"));
+        assert_eq!(config.label("temp1"), Some("CPU \"socket\""));
+        assert_eq!(config.label("in2"), None);
+        assert!(config.ignored("fan3"));
+        assert!(!config.ignored("temp2"));
+        assert!((config.compute("in1", 1.0) - 6.6).abs() < 1e-9);
+        assert_eq!(config.compute("in2", 1.5), 3.0);
+        assert_eq!(config.compute("in3", 1.0), 1.0);
+
+        assert_eq!(chip_config(&blocks, "it87-isa-0228"), ChipConfig::default());
+        assert!(matches("*", "acpitz-acpi-0"));
+        assert!(!matches("coretemp-isa-*", "coretemp-pci-0000"));
+
+        assert_eq!(evaluate("-(@-32)/1.8", 212.0), Some(-100.0));
+        assert_eq!(evaluate("`^@", 2.0), Some(2.0));
+        assert_eq!(evaluate("in2*2", 1.0), None);
+        assert_eq!(evaluate("@/0", 1.0), None);
+    }
+}
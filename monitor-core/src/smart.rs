@@ -0,0 +1,257 @@
+// Drive temperature, power-on time, wear and error counts through `smartctl -H -A -j`
+// (smartmontools 7.0 and later), for disks without a drivetemp or nvme hwmon chip,
+// with a health entry that raises an alert when any of them looks bad. smartctl needs
+// root to open the devices, so this is opt-in (`[sensors] extra`).
+use crate::hwmon::{read_trimmed, reading_entry};
+use crate::json::{self, Json};
+use crate::model::{Limits, SensorEntry, SensorSection, Status};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+// A run per drive is slow and wakes up the drive's controller, so drives are read on a
+// background thread this seldom.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+// What `-n standby,<status>` makes smartctl exit with instead of waking a sleeping
+// disk. The bits it stands for (self-test log errors) are never set by `-H -A`.
+const ASLEEP_STATUS: i32 = 128;
+
+// ATA attributes whose normalized value is the life left, in percent, by vendor.
+const LIFE_LEFT_ATTRIBUTES: [&str; 5] = [
+    "Wear_Leveling_Count",
+    "Media_Wearout_Indicator",
+    "Percent_Lifetime_Remain",
+    "SSD_Life_Left",
+    "Remaining_Lifetime_Perc",
+];
+
+// ATA attributes that count failing sectors or errors, by id; any raw count above
+// zero is a drive on its way out.
+const ATA_ERROR_ATTRIBUTES: [(u64, &str, &str); 4] = [
+    (5, "Reallocated sectors", "sectors"),
+    (187, "Reported uncorrectable", "errors"),
+    (197, "Pending sectors", "sectors"),
+    (198, "Offline uncorrectable", "sectors"),
+];
+
+const NVME_LOG: &str = "nvme_smart_health_information_log";
+
+// Whole disks in /sys/block, skipping virtual devices.
+pub fn drives() -> Vec<String> {
+    let mut drives: Vec<String> = fs::read_dir("/sys/block")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| {
+            !["loop", "ram", "zram", "dm-", "md", "sr", "nbd"].iter().any(|prefix| name.starts_with(prefix))
+                && read_trimmed(&Path::new("/sys/block").join(name).join("size")).is_some_and(|size| size != "0")
+        })
+        .collect();
+    drives.sort();
+    drives
+}
+
+pub fn read_smart_data() -> Result<Vec<SensorSection>, String> {
+    let mut sections = Vec::new();
+    let mut errors = Vec::new();
+    for drive in drives() {
+        match read_drive(&drive) {
+            Ok(section) => sections.push(section),
+            Err(e) => errors.push(e),
+        }
+    }
+    if sections.is_empty() {
+        Err(errors.first().cloned().unwrap_or_else(|| "No drives found".to_string()))
+    } else {
+        Ok(sections)
+    }
+}
+
+fn read_drive(drive: &str) -> Result<SensorSection, String> {
+    let device = format!("/dev/{}", drive);
+    let output = Command::new("smartctl")
+        .args(["-n", &format!("standby,{}", ASLEEP_STATUS), "-H", "-A", "-j", &device])
+        .output()
+        .map_err(|e| format!("Failed to execute smartctl: {}", e))?;
+    if output.status.code() == Some(ASLEEP_STATUS) {
+        return Ok(asleep_section(drive));
+    }
+    // The exit status is a bit mask; most bits report the drive's health, not a
+    // failed run, so the JSON is read whatever it is.
+    parse_smartctl_output(drive, &String::from_utf8_lossy(&output.stdout))
+}
+
+pub fn parse_smartctl_output(drive: &str, output: &str) -> Result<SensorSection, String> {
+    let report = json::parse(output).map_err(|e| format!("Failed to parse smartctl output: {}", e))?;
+    let number = |path: &[&str]| {
+        path.iter()
+            .try_fold(&report, |value, key| value.get(key))
+            .and_then(Json::as_f64)
+    };
+
+    let mut entries = Vec::new();
+    if let Some(temperature) = number(&["temperature", "current"]) {
+        entries.push(reading_entry("Temperature", Some(temperature), "°C", Limits::default()));
+    }
+    if let Some(hours) = number(&["power_on_time", "hours"]) {
+        entries.push(reading_entry("Power-on time", Some(hours), "h", Limits::default()));
+    }
+    if let Some(used) = number(&[NVME_LOG, "percentage_used"]) {
+        entries.push(reading_entry("Life used", Some(used), "%", Limits { max: Some(100.0), ..Limits::default() }));
+    }
+    if let Some(spare) = number(&[NVME_LOG, "available_spare"]) {
+        let threshold = number(&[NVME_LOG, "available_spare_threshold"]);
+        entries.push(reading_entry("Available spare", Some(spare), "%", Limits { crit_low: threshold, ..Limits::default() }));
+    }
+    if let Some(units) = number(&[NVME_LOG, "data_units_written"]) {
+        // Thousands of 512-byte blocks; shown in the TB endurance ratings use.
+        entries.push(reading_entry("Data written", Some(units * 512_000.0 / 1e12), "TB", Limits::default()));
+    }
+    if let Some((value, threshold)) = ata_life_left(&report) {
+        let limits = Limits { crit_low: threshold.filter(|&threshold| threshold > 0.0), ..Limits::default() };
+        entries.push(reading_entry("Life left", Some(value), "%", limits));
+    }
+    for (id, key, unit) in ATA_ERROR_ATTRIBUTES {
+        if let Some(count) = ata_raw_value(&report, id) {
+            entries.push(reading_entry(key, Some(count), unit, Limits::default()));
+        }
+    }
+    if let Some(errors) = number(&[NVME_LOG, "media_errors"]) {
+        entries.push(reading_entry("Media errors", Some(errors), "errors", Limits::default()));
+    }
+
+    if entries.is_empty() {
+        // smartctl explains failures (permission denied, unsupported USB bridge) in
+        // its messages.
+        let message = report
+            .get("smartctl")
+            .and_then(|smartctl| smartctl.get("messages"))
+            .and_then(|messages| match messages {
+                Json::Array(messages) => messages.first(),
+                _ => None,
+            })
+            .and_then(|message| message.get("string"))
+            .and_then(Json::as_str)
+            .unwrap_or("no readings");
+        return Err(format!("smartctl /dev/{}: {}", drive, message));
+    }
+
+    entries.insert(0, health_entry(&report, &entries));
+    Ok(SensorSection {
+        name: format!("smart-{}", drive),
+        adapter: report.get("model_name").and_then(Json::as_str).unwrap_or(drive).to_string(),
+        entries,
+        unparsed: Vec::new(),
+        label: None,
+    })
+}
+
+// A spun-down disk is left asleep: there is nothing to show until it wakes up by itself.
+fn asleep_section(drive: &str) -> SensorSection {
+    SensorSection {
+        name: format!("smart-{}", drive),
+        adapter: drive.to_string(),
+        entries: vec![SensorEntry::from_text("Power mode", "standby")],
+        unparsed: Vec::new(),
+        label: None,
+    }
+}
+
+// "Warning" with the reasons when the drive failed its self-assessment, counts bad
+// sectors or media errors, or is worn past its threshold.
+fn health_entry(report: &Json, entries: &[SensorEntry]) -> SensorEntry {
+    let mut problems = Vec::new();
+    if report.get("smart_status").and_then(|status| status.get("passed")) == Some(&Json::Bool(false)) {
+        problems.push("failed its SMART self-assessment".to_string());
+    }
+    let critical_warning = report.get(NVME_LOG).and_then(|log| log.get("critical_warning")).and_then(Json::as_f64);
+    if let Some(warning) = critical_warning.filter(|&warning| warning > 0.0) {
+        problems.push(format!("critical warning {:#04x}", warning as u8));
+    }
+    for entry in entries {
+        match entry.number {
+            Some(count) if count > 0.0 && ["sectors", "errors"].contains(&entry.unit.symbol()) => {
+                problems.push(format!("{} {}", count, entry.key.to_lowercase()));
+            }
+            _ if entry.key == "Life left" && entry.in_alarm() => problems.push("worn out".to_string()),
+            _ => {}
+        }
+    }
+    let mut health = SensorEntry::from_status("Health", if problems.is_empty() { Status::Ok } else { Status::Warning });
+    health.additional_info = (!problems.is_empty()).then(|| problems.join(", "));
+    health
+}
+
+fn ata_raw_value(report: &Json, id: u64) -> Option<f64> {
+    let Some(Json::Array(table)) = report.get("ata_smart_attributes").and_then(|attributes| attributes.get("table"))
+    else {
+        return None;
+    };
+    let attribute = table
+        .iter()
+        .find(|attribute| attribute.get("id").and_then(Json::as_f64) == Some(id as f64))?;
+    attribute.get("raw")?.get("value")?.as_f64()
+}
+
+// (normalized value, failure threshold) of the first life-left attribute.
+fn ata_life_left(report: &Json) -> Option<(f64, Option<f64>)> {
+    let Some(Json::Array(table)) = report.get("ata_smart_attributes").and_then(|attributes| attributes.get("table"))
+    else {
+        return None;
+    };
+    let attribute = table.iter().find(|attribute| {
+        attribute
+            .get("name")
+            .and_then(Json::as_str)
+            .is_some_and(|name| LIFE_LEFT_ATTRIBUTES.contains(&name))
+    })?;
+    Some((
+        attribute.get("value").and_then(Json::as_f64)?,
+        attribute.get("thresh").and_then(Json::as_f64),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_smartctl_reports() {
+        let reading = |section: &SensorSection, key: &str| {
+            section.entries.iter().find(|entry| entry.key == key).and_then(|entry| entry.number)
+        };
+
+        let ssd = parse_smartctl_output("sda", include_str!("testdata/smartctl-7.3-sata-ssd.json")).unwrap();
+        assert_eq!((ssd.name.as_str(), ssd.adapter.as_str()), ("smart-sda", "Samsung SSD 860 EVO 500GB"));
+        assert_eq!(reading(&ssd, "Temperature"), Some(34.0));
+        assert_eq!(reading(&ssd, "Power-on time"), Some(21874.0));
+        assert_eq!(reading(&ssd, "Life left"), Some(93.0));
+        assert_eq!(reading(&ssd, "Reallocated sectors"), Some(0.0));
+        assert_eq!(ssd.entries[0].status, Some(Status::Ok));
+
+        let nvme = parse_smartctl_output("nvme0n1", include_str!("testdata/smartctl-7.3-nvme.json")).unwrap();
+        assert_eq!(reading(&nvme, "Temperature"), Some(41.0));
+        assert_eq!(reading(&nvme, "Life used"), Some(2.0));
+        assert!((reading(&nvme, "Data written").unwrap() - 20.214).abs() < 1e-3);
+        let spare = nvme.entries.iter().find(|entry| entry.key == "Available spare").unwrap();
+        assert_eq!((spare.number, spare.limits.crit_low), (Some(100.0), Some(10.0)));
+
+        let failing = r#"{"smart_status": {"passed": true}, "ata_smart_attributes": {"table": [
+            {"id": 5, "name": "Reallocated_Sector_Ct", "value": 98, "thresh": 10, "raw": {"value": 8}},
+            {"id": 197, "name": "Current_Pending_Sector", "value": 100, "thresh": 0, "raw": {"value": 2}}]}}"#;
+        let health = &parse_smartctl_output("sdb", failing).unwrap().entries[0];
+        assert!(health.in_alarm());
+        assert_eq!(health.additional_info.as_deref(), Some("8 reallocated sectors, 2 pending sectors"));
+
+        let denied = r#"{"smartctl": {"messages": [{"string": "Smartctl open device: /dev/sda failed: Permission denied", "severity": "error"}], "exit_status": 2}}"#;
+        assert_eq!(
+            parse_smartctl_output("sda", denied).err(),
+            Some("smartctl /dev/sda: Smartctl open device: /dev/sda failed: Permission denied".to_string())
+        );
+        let asleep = asleep_section("sdc");
+        assert_eq!((asleep.name.as_str(), asleep.entries[0].value.as_str()), ("smart-sdc", "standby"));
+    }
+}
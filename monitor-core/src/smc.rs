@@ -0,0 +1,229 @@
+// macOS, where there is no lm-sensors: temperatures and fan speeds from the System
+// Management Controller, through the AppleSMC IOKit user client. Keys are four
+// characters ("TC0P") and values come typed ("sp78", "fpe2", "flt "); which keys exist
+// depends on the model, so missing ones are skipped.
+use crate::hwmon::reading_entry;
+use crate::model::{Limits, SensorEntry, SensorSection};
+
+// Temperature keys seen on Intel Macs and Apple silicon, with what they measure.
+const TEMPERATURES: [(&str, &str); 14] = [
+    ("TC0P", "CPU proximity"),
+    ("TC0D", "CPU die"),
+    ("TC0E", "CPU PECI"),
+    ("TCXC", "CPU PECI (cores)"),
+    ("Tp09", "CPU efficiency cores"),
+    ("Tp0T", "CPU efficiency cores 2"),
+    ("Tp01", "CPU performance cores"),
+    ("Tp05", "CPU performance cores 2"),
+    ("TG0P", "GPU proximity"),
+    ("TG0D", "GPU die"),
+    ("Tg05", "GPU"),
+    ("Tg0D", "GPU 2"),
+    ("Tm0P", "Memory proximity"),
+    ("TB0T", "Battery"),
+];
+
+// The value of a key, decoded by its type.
+pub fn decode(data_type: &str, bytes: &[u8]) -> Option<f64> {
+    let be = |n: usize| bytes.get(..n).map(|bytes| bytes.iter().fold(0u32, |value, &b| value << 8 | b as u32));
+    match data_type {
+        // Signed fixed point, 7 integer and 8 fraction bits.
+        "sp78" => be(2).map(|raw| raw as u16 as i16 as f64 / 256.0),
+        // Unsigned fixed point, 14 integer and 2 fraction bits.
+        "fpe2" => be(2).map(|raw| raw as f64 / 4.0),
+        // Apple silicon; little-endian, unlike the rest.
+        "flt " => Some(f32::from_le_bytes(bytes.get(..4)?.try_into().ok()?) as f64),
+        "ui8 " => be(1).map(f64::from),
+        "ui16" => be(2).map(f64::from),
+        "ui32" => be(4).map(f64::from),
+        _ => None,
+    }
+}
+
+// A section from a key reader; `read` returns a key's type and bytes.
+pub fn smc_section(mut read: impl FnMut(&str) -> Option<(String, Vec<u8>)>) -> Option<SensorSection> {
+    let mut value = |key: &str| read(key).and_then(|(data_type, bytes)| decode(&data_type, &bytes));
+    let mut entries: Vec<SensorEntry> = Vec::new();
+    for (key, label) in TEMPERATURES {
+        // Unpopulated sensors read as 0 °C, or below.
+        if let Some(celsius) = value(key).filter(|celsius| *celsius > 0.0) {
+            entries.push(reading_entry(label, Some(celsius), "°C", Limits::default()));
+        }
+    }
+    let fans = value("FNum").unwrap_or(0.0) as u32;
+    for fan in 0..fans {
+        let mut fan_value = |suffix: &str| value(&format!("F{}{}", fan, suffix));
+        let limits = Limits { min: fan_value("Mn"), max: fan_value("Mx"), ..Limits::default() };
+        entries.push(reading_entry(&format!("Fan {}", fan + 1), fan_value("Ac"), "RPM", limits));
+    }
+    (!entries.is_empty()).then(|| SensorSection {
+        name: "applesmc-virtual-0".to_string(),
+        adapter: "SMC".to_string(),
+        entries,
+        unparsed: Vec::new(),
+        label: None,
+    })
+}
+
+pub fn available() -> bool {
+    cfg!(target_os = "macos")
+}
+
+#[cfg(target_os = "macos")]
+pub fn read_smc_data() -> Result<Vec<SensorSection>, String> {
+    let smc = ffi::Smc::open()?;
+    smc_section(|key| smc.read(key))
+        .map(|section| vec![section])
+        .ok_or_else(|| "The SMC has no known sensors".to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn read_smc_data() -> Result<Vec<SensorSection>, String> {
+    Err("The SMC is only on Macs".to_string())
+}
+
+#[cfg(target_os = "macos")]
+mod ffi {
+    use std::ffi::{c_char, c_void};
+
+    type KernReturn = i32;
+    type MachPort = u32;
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOServiceMatching(name: *const c_char) -> *mut c_void;
+        fn IOServiceGetMatchingService(main_port: MachPort, matching: *mut c_void) -> MachPort;
+        fn IOServiceOpen(service: MachPort, owning_task: MachPort, kind: u32, connect: *mut MachPort) -> KernReturn;
+        fn IOServiceClose(connect: MachPort) -> KernReturn;
+        fn IOObjectRelease(object: MachPort) -> KernReturn;
+        fn IOConnectCallStructMethod(
+            connection: MachPort,
+            selector: u32,
+            input: *const c_void,
+            input_size: usize,
+            output: *mut c_void,
+            output_size: *mut usize,
+        ) -> KernReturn;
+    }
+
+    extern "C" {
+        // What mach_task_self() expands to.
+        static mach_task_self_: MachPort;
+    }
+
+    // The AppleSMC user client's one method, and the commands it takes.
+    const KERNEL_INDEX_SMC: u32 = 2;
+    const CMD_READ_BYTES: u8 = 5;
+    const CMD_READ_KEY_INFO: u8 = 9;
+
+    // SMCKeyData_t, as the SMC tools going back to smcFanControl declare it.
+    #[repr(C)]
+    #[derive(Default)]
+    struct KeyData {
+        key: u32,
+        version: [u8; 6],
+        p_limit_data: [u8; 16],
+        data_size: u32,
+        data_type: u32,
+        data_attributes: u8,
+        result: u8,
+        status: u8,
+        data8: u8,
+        data32: u32,
+        bytes: [u8; 32],
+    }
+
+    pub struct Smc {
+        connection: MachPort,
+    }
+
+    impl Smc {
+        pub fn open() -> Result<Smc, String> {
+            // SAFETY: plain IOKit calls; the matching dictionary is consumed by
+            // IOServiceGetMatchingService and the service released once opened.
+            unsafe {
+                let service = IOServiceGetMatchingService(0, IOServiceMatching(c"AppleSMC".as_ptr()));
+                if service == 0 {
+                    return Err("No AppleSMC service found".to_string());
+                }
+                let mut connection = 0;
+                let status = IOServiceOpen(service, mach_task_self_, 0, &mut connection);
+                IOObjectRelease(service);
+                if status != 0 {
+                    return Err(format!("Failed to open the SMC: kern_return {:#x}", status));
+                }
+                Ok(Smc { connection })
+            }
+        }
+
+        fn call(&self, input: &KeyData) -> Option<KeyData> {
+            let mut output = KeyData::default();
+            let mut size = std::mem::size_of::<KeyData>();
+            // SAFETY: both buffers are KeyData, the layout the user client expects.
+            let status = unsafe {
+                IOConnectCallStructMethod(
+                    self.connection,
+                    KERNEL_INDEX_SMC,
+                    input as *const KeyData as *const c_void,
+                    std::mem::size_of::<KeyData>(),
+                    &mut output as *mut KeyData as *mut c_void,
+                    &mut size,
+                )
+            };
+            (status == 0 && output.result == 0).then_some(output)
+        }
+
+        // The key's type and its bytes.
+        pub fn read(&self, key: &str) -> Option<(String, Vec<u8>)> {
+            let key = u32::from_be_bytes(key.as_bytes().try_into().ok()?);
+            let info = self.call(&KeyData { key, data8: CMD_READ_KEY_INFO, ..KeyData::default() })?;
+            let value = self.call(&KeyData {
+                key,
+                data_size: info.data_size,
+                data8: CMD_READ_BYTES,
+                ..KeyData::default()
+            })?;
+            let size = (info.data_size as usize).min(value.bytes.len());
+            let data_type = String::from_utf8_lossy(&info.data_type.to_be_bytes()).into_owned();
+            Some((data_type, value.bytes[..size].to_vec()))
+        }
+    }
+
+    impl Drop for Smc {
+        fn drop(&mut self) {
+            // SAFETY: the connection was opened by IOServiceOpen and is closed once.
+            unsafe {
+                IOServiceClose(self.connection);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_smc_values() {
+        assert_eq!(decode("sp78", &[0x2e, 0x80]), Some(46.5));
+        assert_eq!(decode("sp78", &[0xff, 0x00]), Some(-1.0));
+        assert_eq!(decode("fpe2", &[0x1f, 0x40]), Some(2000.0));
+        assert_eq!(decode("flt ", &41.25f32.to_le_bytes()), Some(41.25));
+        assert_eq!(decode("ui8 ", &[2]), Some(2.0));
+        assert_eq!(decode("sp78", &[0x2e]), None);
+        assert_eq!(decode("ch8*", b"abcd"), None);
+
+        let keys = [("TC0P", "sp78", vec![0x2e, 0x80]), ("TC0D", "sp78", vec![0, 0]), ("FNum", "ui8 ", vec![1])];
+        let mut keys: Vec<(&str, String, Vec<u8>)> =
+            keys.into_iter().map(|(key, data_type, bytes)| (key, data_type.to_string(), bytes)).collect();
+        keys.push(("F0Ac", "fpe2".to_string(), vec![0x1f, 0x40]));
+        let section = smc_section(|key| {
+            let (_, data_type, bytes) = keys.iter().find(|(name, _, _)| *name == key)?;
+            Some((data_type.clone(), bytes.clone()))
+        })
+        .unwrap();
+        let readings: Vec<(&str, Option<f64>)> =
+            section.entries.iter().map(|entry| (entry.key.as_str(), entry.number)).collect();
+        assert_eq!(readings, [("CPU proximity", Some(46.5)), ("Fan 1", Some(2000.0))]);
+    }
+}
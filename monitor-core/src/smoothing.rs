@@ -0,0 +1,85 @@
+// Exponential moving averages for readings that jitter from sample to sample, such as
+// voltages and fan speeds. Each smoothed entry's `number` becomes the average, while
+// `value` keeps the reading as it was read.
+use crate::model::SensorSection;
+use std::collections::HashMap;
+
+// Weight of the newest sample when smoothing is turned on without an alpha.
+pub const DEFAULT_ALPHA: f64 = 0.3;
+
+#[derive(Debug, Default)]
+pub struct Smoother {
+    averages: HashMap<(String, String), f64>,
+}
+
+impl Smoother {
+    pub fn new() -> Self {
+        Smoother::default()
+    }
+
+    // `alpha` gives the weight (0 to 1] of the newest sample for a (section, entry),
+    // or `None` to leave it as read. A reading that goes missing starts over.
+    pub fn apply(&mut self, sections: &mut [SensorSection], alpha: impl Fn(&str, &str) -> Option<f64>) {
+        let mut averages = HashMap::new();
+        for section in sections {
+            for entry in &mut section.entries {
+                let (Some(number), Some(alpha)) = (entry.number, alpha(&section.name, &entry.key)) else {
+                    continue;
+                };
+                let alpha = alpha.clamp(0.0, 1.0);
+                let id = (section.name.clone(), entry.key.clone());
+                let average = match self.averages.get(&id) {
+                    Some(previous) => alpha * number + (1.0 - alpha) * previous,
+                    None => number,
+                };
+                entry.number = Some(average);
+                averages.insert(id, average);
+            }
+        }
+        self.averages = averages;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{SensorEntry, Unit};
+
+    fn fan(rpm: f64) -> Vec<SensorSection> {
+        let mut entry = SensorEntry::from_text("fan1", &format!("{} RPM", rpm));
+        entry.number = Some(rpm);
+        entry.unit = Unit::Rpm;
+        let mut voltage = entry.clone();
+        voltage.key = "in0".to_string();
+        vec![SensorSection {
+            name: "nct6775-isa-0290".to_string(),
+            adapter: "ISA adapter".to_string(),
+            entries: vec![entry, voltage],
+            unparsed: Vec::new(),
+            label: None,
+        }]
+    }
+
+    #[test]
+    fn averages_chosen_readings() {
+        let mut smoother = Smoother::new();
+        let alpha = |_: &str, key: &str| (key == "fan1").then_some(0.25);
+        let numbers = |sections: &[SensorSection]| -> Vec<Option<f64>> {
+            sections[0].entries.iter().map(|entry| entry.number).collect()
+        };
+
+        let mut sections = fan(1000.0);
+        smoother.apply(&mut sections, alpha);
+        assert_eq!(numbers(&sections), [Some(1000.0), Some(1000.0)]);
+
+        let mut sections = fan(1400.0);
+        smoother.apply(&mut sections, alpha);
+        assert_eq!(numbers(&sections), [Some(1100.0), Some(1400.0)]);
+        assert_eq!(sections[0].entries[0].value, "1400 RPM");
+
+        smoother.apply(&mut [], alpha);
+        let mut sections = fan(1400.0);
+        smoother.apply(&mut sections, alpha);
+        assert_eq!(numbers(&sections), [Some(1400.0), Some(1400.0)]);
+    }
+}
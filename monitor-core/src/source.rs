@@ -0,0 +1,217 @@
+// Where sensor sections come from. Every backend is a `SensorSource`; the registry
+// holds the enabled ones, polls them in order each sample and merges what they read
+// into one list, so a source that fails does not hide the others.
+use crate::counters::{self, Counters};
+use crate::model::SensorSection;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub type ReadResult = Result<Vec<SensorSection>, String>;
+
+pub trait SensorSource: Send {
+    // For error messages and the startup list, e.g. "hwmon (sysfs)".
+    fn name(&self) -> &str;
+    // Sources that keep state between samples (rate counters, open devices) keep it
+    // in `self`.
+    fn poll(&mut self) -> ReadResult;
+}
+
+// A source that is just a read function.
+pub struct FnSource<F> {
+    name: String,
+    read: F,
+}
+
+pub fn from_fn<F: FnMut() -> ReadResult + Send>(name: &str, read: F) -> FnSource<F> {
+    FnSource { name: name.to_string(), read }
+}
+
+impl<F: FnMut() -> ReadResult + Send> SensorSource for FnSource<F> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn poll(&mut self) -> ReadResult {
+        (self.read)()
+    }
+}
+
+// A source whose reads are too slow for the UI thread (network scrapes, tools that
+// take hundreds of milliseconds to start): a thread reads every `interval` and polls
+// return the latest result, or nothing until the first read has finished.
+pub struct BackgroundSource {
+    name: String,
+    latest: Arc<Mutex<Option<ReadResult>>>,
+    stop: Arc<AtomicBool>,
+}
+
+pub fn background<F: FnMut() -> ReadResult + Send + 'static>(
+    name: &str,
+    interval: Duration,
+    mut read: F,
+) -> BackgroundSource {
+    let source = BackgroundSource { name: name.to_string(), latest: Arc::default(), stop: Arc::default() };
+    let (latest, stop) = (source.latest.clone(), source.stop.clone());
+    thread::spawn(move || {
+        while !stop.load(Ordering::Relaxed) {
+            let started = Instant::now();
+            let result = read();
+            *latest.lock().unwrap_or_else(|e| e.into_inner()) = Some(result);
+            // Short naps, so a dropped source's thread ends soon after.
+            while !stop.load(Ordering::Relaxed) && started.elapsed() < interval {
+                thread::sleep(interval.saturating_sub(started.elapsed()).min(Duration::from_millis(100)));
+            }
+        }
+    });
+    source
+}
+
+impl Drop for BackgroundSource {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl SensorSource for BackgroundSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn poll(&mut self) -> ReadResult {
+        self.latest.lock().unwrap_or_else(|e| e.into_inner()).clone().unwrap_or_else(|| Ok(Vec::new()))
+    }
+}
+
+#[derive(Default)]
+pub struct Registry {
+    sources: Vec<Box<dyn SensorSource>>,
+    // Previous samples of the energy and charge readings, for their averages.
+    counters: Counters,
+}
+
+impl Registry {
+    pub fn new(sources: Vec<Box<dyn SensorSource>>) -> Self {
+        Registry { sources, counters: Counters::new() }
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.sources.iter().map(|source| source.name()).collect()
+    }
+
+    pub fn poll(&mut self) -> ReadResult {
+        let mut result = merge(self.sources.iter_mut().map(|source| source.poll()).collect());
+        if let Ok(sections) = &mut result {
+            counters::add_averages(sections, &mut self.counters, Instant::now());
+        }
+        result
+    }
+}
+
+// The sections of every source that read, in order; an error only when none did.
+pub fn merge(results: Vec<ReadResult>) -> ReadResult {
+    let mut sections = Vec::new();
+    let mut errors = Vec::new();
+    for result in results {
+        match result {
+            Ok(mut data) => sections.append(&mut data),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    if sections.is_empty() && !errors.is_empty() {
+        Err(errors.join("; "))
+    } else {
+        disambiguate(&mut sections);
+        Ok(sections)
+    }
+}
+
+// Section names identify sections (collapsed state, pins, flashes), so chips that
+// still share a name after the bus address get "#2", "#3", … in read order. Entry
+// keys identify entries the same way, so a chip that repeats a key (several `temp1`
+// after a driver quirk) gets the suffix too, with the name it was read under kept
+// in the details.
+fn disambiguate(sections: &mut [SensorSection]) {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for section in sections {
+        let count = seen.entry(section.name.clone()).or_insert(0);
+        *count += 1;
+        if *count > 1 {
+            section.name = format!("{} #{}", section.name, count);
+        }
+
+        let mut keys: HashMap<String, usize> = HashMap::new();
+        for entry in &mut section.entries {
+            let count = keys.entry(entry.key.clone()).or_insert(0);
+            *count += 1;
+            if *count > 1 {
+                let channel = format!("reported as {}", entry.key);
+                entry.additional_info = Some(match entry.additional_info.take() {
+                    Some(info) => format!("{}, {}", channel, info),
+                    None => channel,
+                });
+                entry.key = format!("{} #{}", entry.key, count);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SensorEntry;
+
+    fn section(name: &str) -> SensorSection {
+        SensorSection {
+            name: name.to_string(),
+            adapter: "Virtual device".to_string(),
+            entries: vec![SensorEntry::from_text("temp1", "ok"), SensorEntry::from_text("temp1", "ok")],
+            unparsed: Vec::new(),
+            label: None,
+        }
+    }
+
+    #[test]
+    fn merges_sources_in_order() {
+        let mut polls = 0;
+        let mut registry = Registry::new(vec![
+            Box::new(from_fn("first", || Ok(vec![section("nvme-pci-0100")]))),
+            Box::new(from_fn("broken", || Err("no such file".to_string()))),
+            Box::new(from_fn("counting", move || {
+                polls += 1;
+                Ok(vec![section("nvme-pci-0100"), section(&format!("poll-{}", polls))])
+            })),
+        ]);
+        assert_eq!(registry.names(), ["first", "broken", "counting"]);
+        registry.poll().unwrap();
+        let names: Vec<String> = registry.poll().unwrap().into_iter().map(|section| section.name).collect();
+        assert_eq!(names, ["nvme-pci-0100", "nvme-pci-0100 #2", "poll-2"]);
+        let sections = registry.poll().unwrap();
+        let keys: Vec<&str> = sections[0].entries.iter().map(|entry| entry.key.as_str()).collect();
+        assert_eq!(keys, ["temp1", "temp1 #2"]);
+        assert_eq!(sections[0].entries[1].additional_info.as_deref(), Some("reported as temp1"));
+
+        let mut failing = Registry::new(vec![
+            Box::new(from_fn("a", || Err("a failed".to_string()))),
+            Box::new(from_fn("b", || Err("b failed".to_string()))),
+        ]);
+        assert_eq!(failing.poll().err(), Some("a failed; b failed".to_string()));
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut slow = background("slow", Duration::from_secs(60), move || {
+            let _ = receiver.recv_timeout(Duration::from_secs(5));
+            Ok(vec![section("slow-virtual-0")])
+        });
+        // Still reading: the poll returns at once, with nothing.
+        assert_eq!(slow.poll().map(|sections| sections.len()), Ok(0));
+        sender.send(()).unwrap();
+        let started = Instant::now();
+        while slow.poll().map(|sections| sections.len()) != Ok(1) {
+            assert!(started.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
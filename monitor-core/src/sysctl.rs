@@ -0,0 +1,87 @@
+// FreeBSD, where lm-sensors does not run: the temperatures coretemp(4), amdtemp(4)
+// and acpi_thermal(4) publish as sysctls, read through `sysctl -e` as
+// "dev.cpu.0.temperature=45.0C".
+use crate::hwmon::reading_entry;
+use crate::model::{Limits, SensorSection};
+use std::process::Command;
+
+const TREES: [&str; 3] = ["dev.cpu", "dev.amdtemp", "hw.acpi.thermal"];
+
+pub fn available() -> bool {
+    cfg!(target_os = "freebsd")
+}
+
+pub fn read_sysctl_data() -> Result<Vec<SensorSection>, String> {
+    // -i: a tree whose driver is not loaded is skipped instead of failing the lot.
+    let output = Command::new("sysctl")
+        .arg("-ie")
+        .args(TREES)
+        .output()
+        .map_err(|e| format!("Failed to execute sysctl: {}", e))?;
+    let sections = parse_sysctl_output(&String::from_utf8_lossy(&output.stdout));
+    if sections.is_empty() {
+        Err("No temperature sysctls found (is coretemp or amdtemp loaded?)".to_string())
+    } else {
+        Ok(sections)
+    }
+}
+
+// "45.0C"; sysctl prints temperatures in Celsius with a C suffix.
+fn celsius(value: &str) -> Option<f64> {
+    value.strip_suffix('C')?.parse().ok()
+}
+
+pub fn parse_sysctl_output(output: &str) -> Vec<SensorSection> {
+    let values: Vec<(&str, &str)> = output.lines().filter_map(|line| line.split_once('=')).collect();
+    let value = |name: &str| values.iter().find(|(key, _)| *key == name).and_then(|(_, value)| celsius(value));
+    let mut sections: Vec<SensorSection> = Vec::new();
+    let mut add = |name: String, entry| match sections.iter_mut().find(|section| section.name == name) {
+        Some(section) => section.entries.push(entry),
+        None => sections.push(SensorSection {
+            name,
+            adapter: "sysctl".to_string(),
+            entries: vec![entry],
+            unparsed: Vec::new(),
+            label: None,
+        }),
+    };
+    for (key, raw) in &values {
+        let Some(temperature) = celsius(raw) else { continue };
+        let parts: Vec<&str> = key.split('.').collect();
+        let entry = |key: &str| reading_entry(key, Some(temperature), "°C", Limits::default());
+        match parts[..] {
+            // coretemp, or amdtemp on older releases.
+            ["dev", "cpu", cpu, "temperature"] => add("cpu-sysctl-0".to_string(), entry(&format!("cpu{}", cpu))),
+            // dev.amdtemp.0.core0.sensor0
+            ["dev", "amdtemp", index, core, sensor] => {
+                add(format!("amdtemp-sysctl-{}", index), entry(&format!("{} {}", core, sensor)));
+            }
+            // The zone's passive cooling (_PSV) and critical shutdown (_CRT) trip points.
+            ["hw", "acpi", "thermal", zone, "temperature"] => {
+                let trip = |point: &str| value(&format!("hw.acpi.thermal.{}.{}", zone, point));
+                let limits = Limits { high: trip("_PSV"), crit: trip("_CRT"), ..Limits::default() };
+                add("acpi_thermal-sysctl-0".to_string(), reading_entry(zone, Some(temperature), "°C", limits));
+            }
+            _ => {}
+        }
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_temperature_sysctls() {
+        let output = "dev.cpu.0.temperature=45.0C\ndev.cpu.0.freq=3400\ndev.cpu.1.temperature=47.0C\n\
+            dev.amdtemp.0.core0.sensor0=51.5C\nhw.acpi.thermal.tz0.temperature=27.9C\n\
+            hw.acpi.thermal.tz0._PSV=-1\nhw.acpi.thermal.tz0._CRT=105.0C\n";
+        let sections = parse_sysctl_output(output);
+        let names: Vec<&str> = sections.iter().map(|section| section.name.as_str()).collect();
+        assert_eq!(names, ["cpu-sysctl-0", "amdtemp-sysctl-0", "acpi_thermal-sysctl-0"]);
+        assert_eq!(sections[0].entries.len(), 2);
+        assert_eq!((sections[1].entries[0].key.as_str(), sections[1].entries[0].number), ("core0 sensor0", Some(51.5)));
+        assert_eq!(sections[2].entries[0].limits, Limits { crit: Some(105.0), ..Limits::default() });
+    }
+}
@@ -0,0 +1,73 @@
+// What the machine is, for the header above the sections: host name, kernel, CPU
+// model and uptime. All but the uptime are read once.
+use crate::hwmon::read_trimmed;
+use std::fs;
+use std::path::Path;
+
+const HOSTNAME_PATH: &str = "/proc/sys/kernel/hostname";
+const KERNEL_PATH: &str = "/proc/sys/kernel/osrelease";
+const CPUINFO_PATH: &str = "/proc/cpuinfo";
+const UPTIME_PATH: &str = "/proc/uptime";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemInfo {
+    pub hostname: Option<String>,
+    pub kernel: Option<String>,
+    pub cpu_model: Option<String>,
+}
+
+impl SystemInfo {
+    pub fn read() -> Self {
+        SystemInfo {
+            hostname: read_trimmed(Path::new(HOSTNAME_PATH)),
+            kernel: read_trimmed(Path::new(KERNEL_PATH)),
+            cpu_model: fs::read_to_string(CPUINFO_PATH).ok().and_then(|cpuinfo| cpu_model(&cpuinfo)),
+        }
+    }
+}
+
+// x86 names the CPU in "model name"; ARM boards often only have "Model" (the board)
+// or "Hardware" (the SoC).
+pub fn cpu_model(cpuinfo: &str) -> Option<String> {
+    ["model name", "Model", "Hardware"].into_iter().find_map(|field| {
+        cpuinfo.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            (name.trim() == field && !value.is_empty()).then_some(value)
+        })
+    })
+}
+
+// Seconds since boot.
+pub fn read_uptime() -> Option<f64> {
+    read_trimmed(Path::new(UPTIME_PATH))?.split_whitespace().next()?.parse().ok()
+}
+
+// "3 days, 4:05" or "0:42".
+pub fn format_uptime(seconds: f64) -> String {
+    let minutes = (seconds.max(0.0) / 60.0) as u64;
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+    match days {
+        0 => format!("{}:{:02}", hours, minutes),
+        1 => format!("1 day, {}:{:02}", hours, minutes),
+        _ => format!("{} days, {}:{:02}", days, hours, minutes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_the_machine() {
+        let x86 = "processor\t: 0\nvendor_id\t: AuthenticAMD\nmodel name\t: AMD Ryzen 7 5800X  8-Core Processor\n";
+        assert_eq!(cpu_model(x86).as_deref(), Some("AMD Ryzen 7 5800X 8-Core Processor"));
+        let pi = "processor\t: 0\n\nRevision\t: d04170\nModel\t\t: Raspberry Pi 5 Model B Rev 1.0\n";
+        assert_eq!(cpu_model(pi).as_deref(), Some("Raspberry Pi 5 Model B Rev 1.0"));
+        assert_eq!(cpu_model("processor\t: 0\n"), None);
+
+        assert_eq!(format_uptime(2520.0), "0:42");
+        assert_eq!(format_uptime(86400.0 + 3.0 * 3600.0 + 300.0), "1 day, 3:05");
+        assert_eq!(format_uptime(3.0 * 86400.0 + 4.0 * 3600.0 + 330.0), "3 days, 4:05");
+    }
+}
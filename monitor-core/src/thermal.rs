@@ -0,0 +1,96 @@
+// Temperatures from /sys/class/thermal/thermal_zone*, labelled by each zone's type.
+// Many ARM boards have zones like these but no hwmon chip lm-sensors could list.
+use crate::hwmon::{read_trimmed, reading_entry};
+use crate::model::{Limits, SensorEntry, SensorSection};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const THERMAL_ROOT: &str = "/sys/class/thermal";
+
+pub fn read_thermal_zone_data() -> Result<Vec<SensorSection>, String> {
+    let mut zones: Vec<(u32, PathBuf)> = fs::read_dir(THERMAL_ROOT)
+        .map_err(|e| format!("Failed to read {}: {}", THERMAL_ROOT, e))?
+        .flatten()
+        .filter_map(|entry| {
+            let index = entry.file_name().to_str()?.strip_prefix("thermal_zone")?.parse().ok()?;
+            Some((index, entry.path()))
+        })
+        .collect();
+    zones.sort();
+
+    let entries: Vec<(u32, SensorEntry)> = zones
+        .iter()
+        .map(|(index, path)| {
+            let file = |name: &str| read_trimmed(&path.join(name));
+            let trips = trip_points(path);
+            (*index, zone_entry(*index, file("type").as_deref(), file("temp").as_deref(), &trips))
+        })
+        .collect();
+    if entries.is_empty() {
+        return Err("No sensor data found".to_string());
+    }
+    Ok(vec![SensorSection {
+        name: "thermal_zone-virtual-0".to_string(),
+        adapter: "Virtual device".to_string(),
+        entries: disambiguate_keys(entries),
+        unparsed: Vec::new(),
+        label: None,
+    }])
+}
+
+// (type, millidegrees) of each trip_point_N.
+fn trip_points(zone: &Path) -> Vec<(String, String)> {
+    (0..)
+        .map_while(|n| {
+            let trip = |attribute: &str| read_trimmed(&zone.join(format!("trip_point_{}_{}", n, attribute)));
+            Some((trip("type")?, trip("temp")?))
+        })
+        .collect()
+}
+
+// The zone's "critical" trip is its crit limit and the first "hot" or "passive" trip
+// (where the kernel starts throttling) its max.
+pub fn zone_entry(index: u32, kind: Option<&str>, millidegrees: Option<&str>, trips: &[(String, String)]) -> SensorEntry {
+    let celsius = |raw: &str| raw.parse::<f64>().ok().map(|millidegrees| millidegrees / 1000.0);
+    let trip = |kinds: &[&str]| {
+        trips.iter().find(|(kind, _)| kinds.contains(&kind.as_str())).and_then(|(_, temp)| celsius(temp))
+    };
+    let limits = Limits { max: trip(&["hot", "passive"]), crit: trip(&["critical"]), ..Limits::default() };
+    let key = kind.filter(|kind| !kind.is_empty()).map_or_else(|| format!("thermal_zone{}", index), str::to_string);
+    reading_entry(&key, millidegrees.and_then(celsius), "°C", limits)
+}
+
+// Zones often share a type (several "acpitz"); the later ones get their zone number.
+fn disambiguate_keys(entries: Vec<(u32, SensorEntry)>) -> Vec<SensorEntry> {
+    let mut seen: Vec<String> = Vec::new();
+    entries
+        .into_iter()
+        .map(|(index, mut entry)| {
+            if seen.contains(&entry.key) {
+                entry.key = format!("{} {}", entry.key, index);
+            }
+            seen.push(entry.key.clone());
+            entry
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_a_zone_with_its_trip_points() {
+        let trips = [
+            ("passive".to_string(), "85000".to_string()),
+            ("critical".to_string(), "90000".to_string()),
+        ];
+        let entry = zone_entry(0, Some("cpu-thermal"), Some("48312"), &trips);
+        assert_eq!((entry.key.as_str(), entry.number), ("cpu-thermal", Some(48.312)));
+        assert_eq!((entry.limits.max, entry.limits.crit), (Some(85.0), Some(90.0)));
+
+        let unnamed = zone_entry(3, None, Some("garbage"), &[]);
+        assert_eq!(unnamed.key, "thermal_zone3");
+        assert!(unnamed.unavailable);
+    }
+}
@@ -0,0 +1,135 @@
+// Thermal throttling on x86 CPUs, from the thermal_throttle counters the kernel keeps
+// per CPU: how often each core and each package hit its thermal limit, and whether
+// any of them did since the previous refresh, which is the only sign that a warm CPU
+// is actually being slowed down.
+use crate::counters::Counters;
+use crate::hwmon::{read_trimmed, reading_entry};
+use crate::model::{Limits, SensorEntry, SensorSection, Status};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+
+const CPU_ROOT: &str = "/sys/devices/system/cpu";
+
+static SAMPLES: Mutex<Counters> = Mutex::new(Counters::new());
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuThrottle {
+    pub cpu: u32,
+    pub package: u32,
+    pub core_count: u64,
+    pub core_time_ms: Option<u64>,
+    // The same on every CPU of the package.
+    pub package_count: Option<u64>,
+    pub package_time_ms: Option<u64>,
+}
+
+fn read_cpus() -> Vec<CpuThrottle> {
+    let mut cpus: Vec<CpuThrottle> = fs::read_dir(CPU_ROOT)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let cpu = entry.file_name().to_str()?.strip_prefix("cpu")?.parse().ok()?;
+            let path = entry.path();
+            let counter = |file: &str| read_trimmed(&path.join("thermal_throttle").join(file))?.parse::<u64>().ok();
+            let package = read_trimmed(&path.join("topology/physical_package_id"));
+            Some(CpuThrottle {
+                cpu,
+                package: package.and_then(|id| id.parse().ok()).unwrap_or(0),
+                core_count: counter("core_throttle_count")?,
+                core_time_ms: counter("core_throttle_total_time_ms"),
+                package_count: counter("package_throttle_count"),
+                package_time_ms: counter("package_throttle_total_time_ms"),
+            })
+        })
+        .collect();
+    cpus.sort_by_key(|cpu| cpu.cpu);
+    cpus
+}
+
+pub fn available() -> bool {
+    Path::new(CPU_ROOT).join("cpu0/thermal_throttle").exists()
+}
+
+pub fn read_throttle_data() -> Result<Vec<SensorSection>, String> {
+    let cpus = read_cpus();
+    if cpus.is_empty() {
+        return Err("No thermal_throttle counters found".to_string());
+    }
+    let mut samples = SAMPLES.lock().map_err(|_| "Throttle samples are unavailable".to_string())?;
+    Ok(vec![throttle_section(&mut samples, &cpus, Instant::now())])
+}
+
+fn count_entry(key: &str, count: u64, time_ms: Option<u64>) -> SensorEntry {
+    let mut entry = reading_entry(key, Some(count as f64), "events", Limits::default());
+    entry.additional_info = time_ms.map(|ms| format!("{:.1} s throttled", ms as f64 / 1000.0));
+    entry
+}
+
+// "Throttling" is Warning while any counter grew since the previous read, naming the
+// CPUs and packages that did; the first read can only say Ok.
+pub fn throttle_section(samples: &mut Counters, cpus: &[CpuThrottle], now: Instant) -> SensorSection {
+    let mut grew = |key: &str, count: u64| samples.advance(key, count, now).is_some_and(|(before, _)| count > before);
+    let mut throttling = Vec::new();
+    let mut packages = Vec::new();
+    let mut cores = Vec::new();
+    for cpu in cpus {
+        if let Some(count) = cpu.package_count {
+            if !cpus.iter().take_while(|other| other.cpu != cpu.cpu).any(|other| other.package == cpu.package) {
+                let key = format!("Package {}", cpu.package);
+                if grew(&key, count) {
+                    throttling.push(format!("package {}", cpu.package));
+                }
+                packages.push(count_entry(&key, count, cpu.package_time_ms));
+            }
+        }
+        let key = format!("cpu{}", cpu.cpu);
+        if grew(&key, cpu.core_count) {
+            throttling.push(key.clone());
+        }
+        cores.push(count_entry(&key, cpu.core_count, cpu.core_time_ms));
+    }
+
+    let mut indicator =
+        SensorEntry::from_status("Throttling", if throttling.is_empty() { Status::Ok } else { Status::Warning });
+    indicator.additional_info = (!throttling.is_empty()).then(|| throttling.join(", "));
+    SensorSection {
+        name: "throttle-virtual-0".to_string(),
+        adapter: "Virtual device".to_string(),
+        entries: [vec![indicator], packages, cores].concat(),
+        unparsed: Vec::new(),
+        label: Some("CPU throttling".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn flags_counters_that_grew_since_the_last_read() {
+        let cpu = |cpu: u32, core_count: u64, package_count: u64| CpuThrottle {
+            cpu,
+            core_count,
+            core_time_ms: Some(1500),
+            package_count: Some(package_count),
+            ..CpuThrottle::default()
+        };
+        let mut samples = Counters::new();
+        let start = Instant::now();
+        let first = throttle_section(&mut samples, &[cpu(0, 4, 9), cpu(1, 2, 9)], start);
+        let keys = |section: &SensorSection| section.entries.iter().map(|e| e.key.clone()).collect::<Vec<_>>();
+        assert_eq!(keys(&first), ["Throttling", "Package 0", "cpu0", "cpu1"]);
+        assert_eq!(first.entries[0].status, Some(Status::Ok));
+        assert_eq!(first.entries[2].additional_info.as_deref(), Some("1.5 s throttled"));
+
+        let later = start + Duration::from_secs(2);
+        let second = throttle_section(&mut samples, &[cpu(0, 4, 10), cpu(1, 3, 10)], later);
+        assert_eq!(second.entries[0].status, Some(Status::Warning));
+        assert_eq!(second.entries[0].additional_info.as_deref(), Some("package 0, cpu1"));
+        assert_eq!(second.entries[1].number, Some(10.0));
+    }
+}
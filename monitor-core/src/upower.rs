@@ -0,0 +1,368 @@
+// Batteries, AC adapters and wireless peripherals as the UPower daemon sees them,
+// through its `upower` client (which talks to it over D-Bus): `upower --dump` for the
+// devices, re-run whenever `upower --monitor` reports a change signal, so nothing is
+// polled. Compared with /sys/class/power_supply this adds the daemon's time-to-empty
+// estimate, its warning level, and the charging state with when it last changed.
+use crate::hwmon::{on_path, reading_entry};
+use crate::model::{Limits, SensorEntry, SensorSection};
+use crate::source::{ReadResult, SensorSource};
+use crate::sysinfo::format_uptime;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const RESTART_DELAY: Duration = Duration::from_secs(5);
+
+pub fn available() -> bool {
+    on_path("upower")
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Device {
+    // The last part of the object path: "battery_BAT0", "line_power_AC".
+    pub id: String,
+    // "battery", "line-power", "mouse", ...
+    pub kind: String,
+    pub properties: Vec<(String, String)>,
+}
+
+impl Device {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.properties.iter().find(|(name, _)| name == key).map(|(_, value)| value.as_str())
+    }
+
+    // "40.5 Wh", "80%"
+    fn number(&self, key: &str) -> Option<f64> {
+        self.get(key)?.split_whitespace().next()?.trim_end_matches('%').parse().ok()
+    }
+
+    // "4.9 hours", "37.2 minutes"
+    fn minutes(&self, key: &str) -> Option<f64> {
+        let (number, unit) = self.get(key)?.split_once(' ')?;
+        let number: f64 = number.parse().ok()?;
+        match unit.trim() {
+            "seconds" => Some(number / 60.0),
+            "minutes" => Some(number),
+            "hours" => Some(number * 60.0),
+            "days" => Some(number * 1440.0),
+            _ => None,
+        }
+    }
+}
+
+// The devices of `upower --dump`, without the DisplayDevice (UPower's aggregate of
+// every battery) and the history tables.
+pub fn parse_dump(text: &str) -> Vec<Device> {
+    let mut devices: Vec<Device> = Vec::new();
+    let mut in_history = false;
+    for line in text.lines() {
+        if let Some(path) = line.strip_prefix("Device:") {
+            let id = path.trim().rsplit('/').next().unwrap_or_default();
+            devices.push(Device { id: id.to_string(), ..Device::default() });
+            in_history = false;
+            continue;
+        }
+        // "Daemon:" and anything else at the left margin ends the device list.
+        if !line.starts_with(' ') && !line.trim().is_empty() {
+            break;
+        }
+        let Some(device) = devices.last_mut() else {
+            continue;
+        };
+        let trimmed = line.trim();
+        if trimmed.starts_with("History") {
+            in_history = true;
+        } else if in_history || trimmed.is_empty() {
+            continue;
+        } else if let Some((key, value)) = trimmed.split_once(':') {
+            device.properties.push((key.trim().to_string(), value.trim().trim_matches('\'').to_string()));
+        } else if device.kind.is_empty() {
+            device.kind = trimmed.to_string();
+        }
+    }
+    devices.retain(|device| device.id != "DisplayDevice");
+    devices
+}
+
+// "fully-charged" as "Fully charged".
+fn state_label(state: &str) -> String {
+    let state = state.replace('-', " ");
+    let mut chars = state.chars();
+    chars.next().map_or(String::new(), |first| first.to_uppercase().chain(chars).collect())
+}
+
+// The state a device was in before its current one, and when that changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    pub previous: String,
+    pub at: Instant,
+}
+
+pub fn device_section(device: &Device, transition: Option<&Transition>, now: Instant) -> SensorSection {
+    let mut entries = Vec::new();
+    let state = device.get("state").map(state_label);
+    if device.kind == "line-power" {
+        entries.push(SensorEntry::from_text("Online", device.get("online").unwrap_or("unknown")));
+    } else {
+        entries.push(reading_entry("Charge", device.number("percentage"), "%", Limits::default()));
+        if let Some(state) = &state {
+            let mut entry = SensorEntry::from_text("State", state);
+            entry.additional_info = transition.map(|transition| {
+                let ago = now.saturating_duration_since(transition.at).as_secs_f64();
+                format!("from {}, {} ago", state_label(&transition.previous), format_uptime(ago))
+            });
+            // "low", "critical" or "action" (the daemon is about to act on it).
+            entry.alarm = device.get("warning-level").is_some_and(|level| level != "none");
+            entries.push(entry);
+        }
+    }
+    let discharging = device.get("state") == Some("discharging");
+    let optional = [
+        ("Charge rate", device.number("energy-rate").map(|watts| if discharging { -watts } else { watts }), "W"),
+        ("Energy", device.number("energy"), "Wh"),
+        ("Voltage", device.number("voltage"), "V"),
+        ("Health", device.number("capacity"), "%"),
+        ("Time to empty", device.minutes("time to empty"), "min"),
+        ("Time to full", device.minutes("time to full"), "min"),
+        // Many firmwares leave the cycle count at 0, which UPower passes on.
+        ("Cycles", device.number("charge-cycles").filter(|&cycles| cycles > 0.0), "cycles"),
+    ];
+    for (key, value, unit) in optional {
+        if value.is_some() {
+            let limits = match key {
+                "Energy" => Limits { max: device.number("energy-full"), ..Limits::default() },
+                _ => Limits::default(),
+            };
+            entries.push(reading_entry(key, value, unit, limits));
+        }
+    }
+
+    let model = [device.get("vendor"), device.get("model")]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let description = match &state {
+        Some(state) if model.is_empty() => state.clone(),
+        Some(state) => format!("{}, {}", model, state),
+        None if model.is_empty() => "UPower device".to_string(),
+        None => model,
+    };
+    SensorSection {
+        name: format!("upower-{}", device.get("native-path").unwrap_or(&device.id)),
+        adapter: description,
+        entries,
+        unparsed: Vec::new(),
+        label: None,
+    }
+}
+
+fn dump() -> Result<Vec<Device>, String> {
+    let output = Command::new("upower").arg("--dump").output().map_err(|e| format!("Failed to run upower: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("upower failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let devices = parse_dump(&String::from_utf8_lossy(&output.stdout));
+    if devices.is_empty() {
+        return Err("UPower reports no devices".to_string());
+    }
+    Ok(devices)
+}
+
+pub fn read_upower_data() -> Result<Vec<SensorSection>, String> {
+    let now = Instant::now();
+    Ok(dump()?.iter().map(|device| device_section(device, None, now)).collect())
+}
+
+#[derive(Default)]
+struct Shared {
+    devices: Option<Result<Vec<Device>, String>>,
+    // Per device id: its last seen state, and the change that led to it.
+    states: BTreeMap<String, (String, Option<Transition>)>,
+    monitor: Option<Child>,
+    stopped: bool,
+}
+
+impl Shared {
+    fn update(&mut self, devices: Result<Vec<Device>, String>) {
+        let now = Instant::now();
+        for device in devices.iter().flatten() {
+            let Some(state) = device.get("state") else {
+                continue;
+            };
+            match self.states.get_mut(&device.id) {
+                Some((last, transition)) if last != state => {
+                    *transition = Some(Transition { previous: last.clone(), at: now });
+                    *last = state.to_string();
+                }
+                Some(_) => {}
+                None => {
+                    self.states.insert(device.id.clone(), (state.to_string(), None));
+                }
+            }
+        }
+        self.devices = Some(devices);
+    }
+}
+
+// Keeps the last dump and refreshes it on UPower's change signals.
+pub struct UpowerSource {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl UpowerSource {
+    pub fn start() -> Self {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let thread_shared = shared.clone();
+        thread::spawn(move || loop {
+            let lock = || thread_shared.lock().unwrap_or_else(|e| e.into_inner());
+            lock().update(dump());
+            let child = Command::new("upower").arg("--monitor").stdout(Stdio::piped()).stderr(Stdio::null()).spawn();
+            let stdout = match child {
+                Ok(mut child) => {
+                    let stdout = child.stdout.take();
+                    let mut shared = lock();
+                    if shared.stopped {
+                        let _ = child.kill();
+                        return;
+                    }
+                    shared.monitor = Some(child);
+                    stdout
+                }
+                Err(e) => {
+                    lock().devices = Some(Err(format!("Failed to run upower --monitor: {}", e)));
+                    None
+                }
+            };
+            // One line per signal: "[10:42:07.123]  device changed: /org/.../battery_BAT0".
+            for line in stdout.into_iter().flat_map(|stdout| BufReader::new(stdout).lines()) {
+                if line.is_err() {
+                    break;
+                }
+                let devices = dump();
+                lock().update(devices);
+            }
+            let mut shared = lock();
+            if let Some(mut child) = shared.monitor.take() {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+            if shared.stopped {
+                return;
+            }
+            drop(shared);
+            thread::sleep(RESTART_DELAY);
+        });
+        UpowerSource { shared }
+    }
+}
+
+impl Drop for UpowerSource {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+        shared.stopped = true;
+        // Ends the monitor's output, which lets the thread exit.
+        if let Some(child) = shared.monitor.as_mut() {
+            let _ = child.kill();
+        }
+    }
+}
+
+impl SensorSource for UpowerSource {
+    fn name(&self) -> &str {
+        "UPower"
+    }
+
+    fn poll(&mut self) -> ReadResult {
+        let shared = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        match &shared.devices {
+            // The first dump has not finished yet.
+            None => Ok(Vec::new()),
+            Some(Err(e)) => Err(e.clone()),
+            Some(Ok(devices)) => Ok(devices
+                .iter()
+                .map(|device| {
+                    let transition = shared.states.get(&device.id).and_then(|(_, transition)| transition.as_ref());
+                    device_section(device, transition, now)
+                })
+                .collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUMP: &str = "Device: /org/freedesktop/UPower/devices/line_power_AC
+  native-path:          AC
+  power supply:         yes
+  line-power
+    warning-level:       none
+    online:              no
+    icon-name:          'ac-adapter-symbolic'
+
+Device: /org/freedesktop/UPower/devices/battery_BAT0
+  native-path:          BAT0
+  vendor:               SMP
+  model:                5B10W13930
+  power supply:         yes
+  updated:              Tue 14 Oct 2026 10:42:07 (12 seconds ago)
+  battery
+    present:             yes
+    state:               discharging
+    warning-level:       low
+    energy:              40.5 Wh
+    energy-full:         50.2 Wh
+    energy-rate:         8.3 W
+    voltage:             11.9 V
+    charge-cycles:       0
+    time to empty:       4.9 hours
+    percentage:          80%
+    capacity:            88.07%
+  History (charge):
+    1791974527\t80.000\tdischarging
+
+Device: /org/freedesktop/UPower/devices/DisplayDevice
+  power supply:         yes
+  battery
+    percentage:          80%
+
+Daemon:
+  daemon-version:  1.90.2
+  on-battery:      yes
+";
+
+    #[test]
+    fn reads_devices_from_a_dump() {
+        let devices = parse_dump(DUMP);
+        assert_eq!(
+            devices.iter().map(|d| (d.id.as_str(), d.kind.as_str())).collect::<Vec<_>>(),
+            [("line_power_AC", "line-power"), ("battery_BAT0", "battery")]
+        );
+        assert_eq!(devices[1].get("icon-name"), None);
+        assert_eq!(devices[0].get("icon-name"), Some("ac-adapter-symbolic"));
+
+        let now = Instant::now();
+        let ac = device_section(&devices[0], None, now);
+        assert_eq!((ac.name.as_str(), ac.entries[0].value.as_str()), ("upower-AC", "no"));
+
+        let transition = Transition { previous: "fully-charged".to_string(), at: now - Duration::from_secs(720) };
+        let battery = device_section(&devices[1], Some(&transition), now);
+        let entry = |key: &str| battery.entries.iter().find(|e| e.key == key);
+        assert_eq!(battery.adapter, "SMP 5B10W13930, Discharging");
+        assert_eq!(entry("Charge").and_then(|e| e.number), Some(80.0));
+        assert_eq!(entry("Charge rate").and_then(|e| e.number), Some(-8.3));
+        assert_eq!(entry("Time to empty").and_then(|e| e.number), Some(294.0));
+        assert_eq!(entry("Health").and_then(|e| e.number), Some(88.07));
+        assert!(entry("Cycles").is_none());
+        let state = entry("State").unwrap();
+        assert_eq!(state.additional_info.as_deref(), Some("from Fully charged, 0:12 ago"));
+        assert!(state.alarm);
+    }
+}
@@ -0,0 +1,154 @@
+// UPS readings from a local NUT server (`upsc`) or apcupsd (`apcaccess`): battery
+// charge, input voltage, load and the runtime left, plus whether it is on battery.
+use crate::hwmon::{on_path, reading_entry};
+use crate::model::{Limits, SensorEntry, SensorSection, Status};
+use std::process::Command;
+use std::time::Duration;
+
+// apcaccess against an unreachable NIS host blocks until it times out, so UPSes are
+// read on a background thread at this interval.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+pub fn available() -> bool {
+    on_path("upsc") || on_path("apcaccess")
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to execute {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+pub fn read_ups_data() -> Result<Vec<SensorSection>, String> {
+    let mut sections = Vec::new();
+    let mut errors = Vec::new();
+    if on_path("upsc") {
+        // `upsc -l` lists the UPSes the local server knows.
+        match run("upsc", &["-l"]) {
+            Ok(list) => {
+                for name in list.split_whitespace() {
+                    match run("upsc", &[name]) {
+                        Ok(variables) => sections.push(parse_upsc(name, &variables)),
+                        Err(e) => errors.push(e),
+                    }
+                }
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+    if on_path("apcaccess") {
+        match run("apcaccess", &[]) {
+            Ok(status) => sections.push(parse_apcaccess(&status)),
+            Err(e) => errors.push(e),
+        }
+    }
+    if sections.is_empty() {
+        Err(errors.first().cloned().unwrap_or_else(|| "No UPS found".to_string()))
+    } else {
+        Ok(sections)
+    }
+}
+
+// "battery.charge: 100" lines.
+pub fn parse_upsc(name: &str, variables: &str) -> SensorSection {
+    let text = |key: &str| {
+        variables
+            .lines()
+            .find_map(|line| line.split_once(": ").filter(|(variable, _)| *variable == key))
+            .map(|(_, value)| value.trim().to_string())
+    };
+    let number = |key: &str| text(key)?.parse::<f64>().ok();
+    let model = [text("ups.mfr"), text("ups.model")].into_iter().flatten().collect::<Vec<_>>().join(" ");
+    // ups.status is a list of flags: "OL", "OB DISCHRG", "OB LB".
+    let on_battery = text("ups.status").map(|status| status.split_whitespace().any(|flag| flag == "OB"));
+    ups_section(
+        format!("ups-{}", name),
+        model,
+        [
+            ("Charge", number("battery.charge"), "%", Limits { low: number("battery.charge.low"), ..Limits::default() }),
+            ("Input voltage", number("input.voltage"), "V", Limits::default()),
+            ("Load", number("ups.load"), "%", Limits { max: Some(100.0), ..Limits::default() }),
+            ("Runtime", number("battery.runtime").map(|seconds| seconds / 60.0), "min", Limits::default()),
+        ],
+        on_battery,
+    )
+}
+
+// "BCHARGE  : 100.0 Percent" lines.
+pub fn parse_apcaccess(status: &str) -> SensorSection {
+    let text = |key: &str| {
+        status.lines().find_map(|line| {
+            let (field, value) = line.split_once(':')?;
+            (field.trim() == key).then(|| value.trim().to_string())
+        })
+    };
+    let number = |key: &str| text(key)?.split_whitespace().next()?.parse::<f64>().ok();
+    let name = text("UPSNAME").unwrap_or_else(|| "apcupsd".to_string());
+    let on_battery = text("STATUS").map(|status| status.contains("ONBATT"));
+    ups_section(
+        format!("ups-{}", name),
+        text("MODEL").unwrap_or_default(),
+        [
+            ("Charge", number("BCHARGE"), "%", Limits { low: number("MBATTCHG"), ..Limits::default() }),
+            ("Input voltage", number("LINEV"), "V", Limits::default()),
+            ("Load", number("LOADPCT"), "%", Limits { max: Some(100.0), ..Limits::default() }),
+            ("Runtime", number("TIMELEFT"), "min", Limits::default()),
+        ],
+        on_battery,
+    )
+}
+
+fn ups_section(
+    name: String,
+    model: String,
+    readings: [(&str, Option<f64>, &str, Limits); 4],
+    on_battery: Option<bool>,
+) -> SensorSection {
+    let mut entries: Vec<SensorEntry> = readings
+        .into_iter()
+        .map(|(key, value, unit, limits)| reading_entry(key, value, unit, limits))
+        .collect();
+    if let Some(on_battery) = on_battery {
+        entries.push(SensorEntry::from_status("On battery", if on_battery { Status::Alarm } else { Status::Ok }));
+    }
+    SensorSection {
+        name,
+        adapter: if model.is_empty() { "UPS".to_string() } else { model },
+        entries,
+        unparsed: Vec::new(),
+        label: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nut_and_apcupsd_status() {
+        let nut = parse_upsc(
+            "eaton",
+            "battery.charge: 87\nbattery.charge.low: 20\nbattery.runtime: 1530\ninput.voltage: 229.5\n\
+             ups.load: 31\nups.mfr: EATON\nups.model: Ellipse PRO 650\nups.status: OB DISCHRG\n",
+        );
+        let reading = |section: &SensorSection, key: &str| section.entries.iter().find(|e| e.key == key).cloned();
+        assert_eq!((nut.name.as_str(), nut.adapter.as_str()), ("ups-eaton", "EATON Ellipse PRO 650"));
+        assert_eq!(reading(&nut, "Charge").unwrap().limits.low, Some(20.0));
+        assert_eq!(reading(&nut, "Runtime").unwrap().number, Some(25.5));
+        assert_eq!(reading(&nut, "On battery").unwrap().status, Some(Status::Alarm));
+
+        let apc = parse_apcaccess(
+            "UPSNAME  : office\nMODEL    : Back-UPS XS 700U\nSTATUS   : ONLINE\nLINEV    : 231.0 Volts\n\
+             LOADPCT  : 12.0 Percent\nBCHARGE  : 100.0 Percent\nTIMELEFT : 48.3 Minutes\n",
+        );
+        assert_eq!(apc.name, "ups-office");
+        assert_eq!(reading(&apc, "Input voltage").unwrap().number, Some(231.0));
+        assert_eq!(reading(&apc, "Runtime").unwrap().number, Some(48.3));
+        assert_eq!(reading(&apc, "On battery").unwrap().status, Some(Status::Ok));
+    }
+}
@@ -0,0 +1,126 @@
+// Raspberry Pi firmware readings through `vcgencmd`: SoC temperature, core voltage,
+// clocks, and the throttling flags the firmware raises on under-voltage or heat.
+use crate::hwmon::reading_entry;
+use crate::model::{Limits, SensorEntry, SensorSection, Status};
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+// Every vcgencmd call `pi_section` makes. They are run by one shell, one output line
+// each, on a background thread at `POLL_INTERVAL`: spawning five processes twice a
+// second is heavy on a Pi.
+const COMMANDS: [&str; 5] =
+    ["measure_temp", "measure_volts core", "measure_clock arm", "measure_clock core", "get_throttled"];
+
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// get_throttled bits: the low ones hold now, the same bit + 16 has happened since boot.
+const THROTTLE_FLAGS: [(u32, &str); 4] = [
+    (0, "Under-voltage"),
+    (1, "Frequency capped"),
+    (2, "Throttled"),
+    (3, "Soft temperature limit"),
+];
+
+// The VideoCore mailbox vcgencmd talks through.
+pub fn available() -> bool {
+    Path::new("/dev/vchiq").exists()
+}
+
+pub fn read_vcgencmd_data() -> Result<Vec<SensorSection>, String> {
+    // A failed command prints an empty line, so the outputs stay in step.
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(r#"for c in "$@"; do vcgencmd $c 2>/dev/null || echo; done"#)
+        .arg("sh")
+        .args(COMMANDS)
+        .output()
+        .map_err(|e| format!("Failed to execute vcgencmd: {}", e))?;
+    let output = String::from_utf8_lossy(&output.stdout);
+    let outputs: Vec<&str> = output.lines().map(str::trim).collect();
+    let section = pi_section(|args| {
+        let index = COMMANDS.iter().position(|command| *command == args.join(" "))?;
+        outputs.get(index).filter(|output| !output.is_empty()).map(|output| output.to_string())
+    });
+    if section.entries.iter().all(|entry| entry.unavailable) {
+        return Err("vcgencmd returned no readings (is it installed, and in the video group?)".to_string());
+    }
+    Ok(vec![section])
+}
+
+// `run` returns one vcgencmd command's output.
+pub fn pi_section(run: impl Fn(&[&str]) -> Option<String>) -> SensorSection {
+    // "temp=48.3'C", "volt=0.8600V", "frequency(48)=1500398464", "throttled=0x50005"
+    let value = |args: &[&str]| {
+        let output = run(args)?;
+        let (_, value) = output.split_once('=')?;
+        Some(value.trim_end_matches(|c: char| c.is_ascii_alphabetic() || c == '\'').to_string())
+    };
+    let number = |args: &[&str]| value(args)?.parse::<f64>().ok();
+    let megahertz = |clock: &str| number(&["measure_clock", clock]).map(|hz| hz / 1e6);
+
+    let mut entries = vec![
+        // The firmware starts throttling at 80°C and caps hard at 85°C.
+        reading_entry(
+            "SoC",
+            number(&["measure_temp"]),
+            "°C",
+            Limits { max: Some(80.0), crit: Some(85.0), ..Limits::default() },
+        ),
+        reading_entry("Core voltage", number(&["measure_volts", "core"]), "V", Limits::default()),
+        reading_entry("ARM clock", megahertz("arm"), "MHz", Limits::default()),
+        reading_entry("Core clock", megahertz("core"), "MHz", Limits::default()),
+    ];
+    let throttled = value(&["get_throttled"])
+        .and_then(|flags| u32::from_str_radix(flags.trim_start_matches("0x"), 16).ok());
+    if let Some(throttled) = throttled {
+        entries.extend(THROTTLE_FLAGS.iter().map(|&(bit, key)| throttle_entry(key, throttled, bit)));
+    }
+    SensorSection {
+        name: "vcgencmd-virtual-0".to_string(),
+        adapter: "Raspberry Pi firmware".to_string(),
+        entries,
+        unparsed: Vec::new(),
+        label: None,
+    }
+}
+
+fn throttle_entry(key: &str, throttled: u32, bit: u32) -> SensorEntry {
+    let now = throttled & (1 << bit) != 0;
+    let mut entry = SensorEntry::from_status(key, if now { Status::Alarm } else { Status::Ok });
+    if throttled & (1 << (bit + 16)) != 0 {
+        entry.additional_info = Some("happened since boot".to_string());
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_vcgencmd_output() {
+        let section = pi_section(|args| {
+            Some(
+                match args {
+                    ["measure_temp"] => "temp=61.8'C",
+                    ["measure_volts", "core"] => "volt=0.8563V",
+                    ["measure_clock", "arm"] => "frequency(48)=1800404352",
+                    ["measure_clock", "core"] => "frequency(1)=500000992",
+                    ["get_throttled"] => "throttled=0x50005",
+                    _ => return None,
+                }
+                .to_string(),
+            )
+        });
+        let entry = |key: &str| section.entries.iter().find(|e| e.key == key).unwrap();
+        assert_eq!(entry("SoC").number, Some(61.8));
+        assert_eq!(entry("Core voltage").number, Some(0.8563));
+        assert_eq!(entry("ARM clock").number, Some(1800.404352));
+        // 0x50005: under-voltage and throttled now; both also since boot.
+        assert_eq!(entry("Under-voltage").status, Some(Status::Alarm));
+        assert_eq!(entry("Throttled").additional_info.as_deref(), Some("happened since boot"));
+        assert_eq!(entry("Frequency capped").status, Some(Status::Ok));
+        assert_eq!(entry("Frequency capped").additional_info, None);
+    }
+}
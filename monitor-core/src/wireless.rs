@@ -0,0 +1,153 @@
+// Wi-Fi link readings: signal level and link quality per wireless interface from
+// /proc/net/wireless, plus the bitrate, channel frequency and network name from
+// `iw dev <interface> link` where iw is installed.
+use crate::hwmon::{on_path, reading_entry};
+use crate::model::{Limits, SensorSection};
+use std::fs;
+use std::process::Command;
+
+const WIRELESS_PATH: &str = "/proc/net/wireless";
+// cfg80211 drivers report link quality out of 70.
+const MAX_QUALITY: f64 = 70.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    pub interface: String,
+    pub quality: Option<f64>,
+    pub level: Option<f64>,
+}
+
+// After two header lines, one line per interface:
+//   wlan0: 0000   54.  -56.  -256        0      0      0      0     13        0
+pub fn parse_wireless(text: &str) -> Vec<Link> {
+    text.lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (interface, rest) = line.split_once(':')?;
+            let number = |field: Option<&str>| field?.trim_end_matches('.').parse::<f64>().ok();
+            // status, link, level, noise, ...
+            let mut fields = rest.split_whitespace().skip(1);
+            let quality = number(fields.next());
+            // Older drivers print the dBm as an unsigned byte.
+            let level = number(fields.next()).map(|level| if level > 63.0 { level - 256.0 } else { level });
+            Some(Link { interface: interface.trim().to_string(), quality, level })
+        })
+        .collect()
+}
+
+fn links() -> Vec<Link> {
+    fs::read_to_string(WIRELESS_PATH).map(|text| parse_wireless(&text)).unwrap_or_default()
+}
+
+pub fn available() -> bool {
+    !links().is_empty()
+}
+
+// What `iw dev <interface> link` adds: "Not connected." or
+//   Connected to aa:bb:cc:dd:ee:ff (on wlan0)
+//   	SSID: home
+//   	freq: 5180
+//   	tx bitrate: 866.7 MBit/s VHT-MCS 9 80MHz short GI VHT-NSS 2
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LinkInfo {
+    pub ssid: Option<String>,
+    pub frequency: Option<f64>,
+    pub tx_bitrate: Option<f64>,
+    pub rx_bitrate: Option<f64>,
+}
+
+pub fn parse_iw_link(text: &str) -> LinkInfo {
+    let mut info = LinkInfo::default();
+    for line in text.lines() {
+        let Some((key, value)) = line.trim().split_once(':') else { continue };
+        let number = || value.split_whitespace().next().and_then(|number| number.parse::<f64>().ok());
+        match key {
+            "SSID" => info.ssid = Some(value.trim().to_string()),
+            "freq" => info.frequency = number(),
+            "tx bitrate" => info.tx_bitrate = number(),
+            "rx bitrate" => info.rx_bitrate = number(),
+            _ => {}
+        }
+    }
+    info
+}
+
+fn iw_link(interface: &str) -> LinkInfo {
+    if !on_path("iw") {
+        return LinkInfo::default();
+    }
+    Command::new("iw")
+        .args(["dev", interface, "link"])
+        .output()
+        .map(|output| parse_iw_link(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+pub fn read_wireless_data() -> Result<Vec<SensorSection>, String> {
+    let sections: Vec<SensorSection> =
+        links().iter().map(|link| wireless_section(link, &iw_link(&link.interface))).collect();
+    if sections.is_empty() {
+        Err("No wireless interfaces found".to_string())
+    } else {
+        Ok(sections)
+    }
+}
+
+pub fn wireless_section(link: &Link, info: &LinkInfo) -> SensorSection {
+    let mut entries = vec![
+        reading_entry("Signal", link.level, "dBm", Limits::default()),
+        reading_entry(
+            "Link quality",
+            link.quality.map(|quality| quality / MAX_QUALITY * 100.0),
+            "%",
+            Limits::default(),
+        ),
+    ];
+    for (key, value, unit) in [
+        ("Transmit bitrate", info.tx_bitrate, "Mbit/s"),
+        ("Receive bitrate", info.rx_bitrate, "Mbit/s"),
+        ("Frequency", info.frequency, "MHz"),
+    ] {
+        if value.is_some() {
+            entries.push(reading_entry(key, value, unit, Limits::default()));
+        }
+    }
+    SensorSection {
+        name: format!("wireless-{}", link.interface),
+        adapter: match &info.ssid {
+            Some(ssid) => format!("Wireless interface ({})", ssid),
+            None => "Wireless interface".to_string(),
+        },
+        entries,
+        unparsed: Vec::new(),
+        label: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_signal_and_bitrate() {
+        let links = parse_wireless(
+            "Inter-| sta-|   Quality        |   Discarded packets               | Missed | WE\n \
+             face | tus | link level noise |  nwid  crypt   frag  retry   misc | beacon | 22\n \
+             wlan0: 0000   56.  -54.  -256        0      0      0      0     13        0\n \
+             wlan1: 0000   35.  196.  0           0      0      0      0      0        0\n",
+        );
+        assert_eq!(links.iter().map(|link| link.level).collect::<Vec<_>>(), [Some(-54.0), Some(-60.0)]);
+
+        let info = parse_iw_link(
+            "Connected to aa:bb:cc:dd:ee:ff (on wlan0)\n\tSSID: home: 5G\n\tfreq: 5180\n\
+             \ttx bitrate: 866.7 MBit/s VHT-MCS 9 80MHz short GI VHT-NSS 2\n",
+        );
+        let section = wireless_section(&links[0], &info);
+        let reading = |key: &str| section.entries.iter().find(|e| e.key == key).and_then(|e| e.number);
+        assert_eq!(section.adapter, "Wireless interface (home: 5G)");
+        assert_eq!(reading("Link quality"), Some(80.0));
+        assert_eq!(reading("Transmit bitrate"), Some(866.7));
+        assert_eq!(reading("Frequency"), Some(5180.0));
+        assert_eq!(reading("Receive bitrate"), None);
+    }
+}
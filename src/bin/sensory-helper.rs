@@ -0,0 +1,111 @@
+// The privileged half of fan control, CPU power settings and driver setup, started
+// by the GUI through pkexec (see monitor-core/src/helper.rs). Every request is checked by
+// `helper::parse_request` and logged to stderr, which pkexec leaves attached to the
+// journal or terminal. Once the GUI goes away, for whatever reason, each fan it took
+// over is handed back to its driver. Modules are only loaded when their file lives
+// under one of MODULE_DIRS.
+use monitor_core::helper::{self, Request};
+#[cfg(unix)]
+use monitor_core::nvml;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::process::Command;
+
+// Where the module's file has to live for `modprobe` to be allowed: sensor drivers
+// and the buses they sit on, nothing else.
+const MODULE_DIRS: [&str; 4] = ["/drivers/hwmon/", "/drivers/i2c/", "/drivers/platform/", "/drivers/gpu/"];
+
+fn main() {
+    // pwmN_enable attributes with the value they had, and NVIDIA fans set manually.
+    let mut originals: Vec<(PathBuf, String)> = Vec::new();
+    #[cfg(unix)]
+    let mut nvml_fans: Vec<(u32, u32)> = Vec::new();
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    for line in stdin.lock().lines() {
+        let Ok(line) = line else { break };
+        let reply = helper::parse_request(&line).and_then(|request| {
+            match &request {
+                Request::Set(path, value) => {
+                    if path.to_string_lossy().ends_with("_enable") && !originals.iter().any(|(known, _)| known == path) {
+                        if let Ok(original) = fs::read_to_string(path) {
+                            originals.push((path.clone(), original.trim().to_string()));
+                        }
+                    }
+                    fs::write(path, value).map_err(|e| e.to_string())?;
+                }
+                #[cfg(unix)]
+                Request::Nvml { gpu, fan, speed } => {
+                    match speed {
+                        Some(speed) => nvml::set_speed(*gpu, *fan, *speed),
+                        None => nvml::set_default(*gpu, *fan),
+                    }
+                    .map_err(|e| e.to_string())?;
+                    if !nvml_fans.contains(&(*gpu, *fan)) {
+                        nvml_fans.push((*gpu, *fan));
+                    }
+                }
+                #[cfg(not(unix))]
+                Request::Nvml { .. } => return Err("NVML is not available here".to_string()),
+                Request::LoadModule(module) => {
+                    check_module_location(module)?;
+                    run(Command::new("modprobe").arg(module))?;
+                }
+                Request::BootModules(modules) => {
+                    modules.iter().try_for_each(|module| check_module_location(module))?;
+                    let contents = format!("# Sensor drivers, written by sensory\n{}\n", modules.join("\n"));
+                    fs::write(helper::BOOT_MODULES_PATH, contents).map_err(|e| e.to_string())?;
+                }
+            }
+            eprintln!("{}: {}", helper::HELPER_NAME, line.trim());
+            Ok(())
+        });
+        let written = match reply {
+            Ok(()) => writeln!(stdout, "ok"),
+            Err(e) => {
+                eprintln!("{}: refused {:?}: {}", helper::HELPER_NAME, line, e);
+                writeln!(stdout, "error: {}", e)
+            }
+        };
+        if written.and_then(|_| stdout.flush()).is_err() {
+            break;
+        }
+    }
+
+    for (path, original) in originals {
+        match fs::write(&path, &original) {
+            Ok(()) => eprintln!("{}: restored {} to {}", helper::HELPER_NAME, path.display(), original),
+            Err(e) => eprintln!("{}: failed to restore {}: {}", helper::HELPER_NAME, path.display(), e),
+        }
+    }
+    #[cfg(unix)]
+    for (gpu, fan) in nvml_fans {
+        if let Err(e) = nvml::set_default(gpu, fan) {
+            eprintln!("{}: failed to restore fan {} of GPU {}: {}", helper::HELPER_NAME, fan, gpu, e);
+        }
+    }
+}
+
+fn check_module_location(module: &str) -> Result<(), String> {
+    let output = Command::new("modinfo")
+        .args(["-F", "filename", module])
+        .output()
+        .map_err(|e| format!("modinfo: {}", e))?;
+    let filename = String::from_utf8_lossy(&output.stdout);
+    if output.status.success() && MODULE_DIRS.iter().any(|dir| filename.contains(dir)) {
+        Ok(())
+    } else {
+        Err(format!("{} is not a loadable sensor driver", module))
+    }
+}
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let output = command.output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
@@ -0,0 +1,76 @@
+// Status colors for readings. The alternatives are based on the Okabe–Ito set, so
+// normal and alarm values stay distinguishable with common color-vision deficiencies;
+// alarm rows also carry an icon so color is never the only signal.
+use iced::Color;
+
+pub const ALARM_ICON: &str = "⚠";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatusPalette {
+    #[default]
+    Standard,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+impl StatusPalette {
+    pub const ALL: [StatusPalette; 4] = [
+        StatusPalette::Standard,
+        StatusPalette::Deuteranopia,
+        StatusPalette::Protanopia,
+        StatusPalette::Tritanopia,
+    ];
+
+    pub fn config_name(self) -> &'static str {
+        match self {
+            StatusPalette::Standard => "standard",
+            StatusPalette::Deuteranopia => "deuteranopia",
+            StatusPalette::Protanopia => "protanopia",
+            StatusPalette::Tritanopia => "tritanopia",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<StatusPalette> {
+        StatusPalette::ALL.into_iter().find(|p| p.config_name() == name.trim())
+    }
+
+    // A reading within its limits.
+    pub fn normal(self) -> Color {
+        match self {
+            StatusPalette::Standard => Color::from_rgb(0.4, 0.8, 0.4),
+            StatusPalette::Deuteranopia | StatusPalette::Protanopia => Color::from_rgb(0.34, 0.71, 0.91),
+            StatusPalette::Tritanopia => Color::from_rgb(0.0, 0.75, 0.6),
+        }
+    }
+
+    // Limits and other secondary details.
+    pub fn info(self) -> Color {
+        match self {
+            StatusPalette::Standard => Color::from_rgb(0.8, 0.8, 0.4),
+            StatusPalette::Deuteranopia | StatusPalette::Protanopia => Color::from_rgb(0.94, 0.89, 0.26),
+            StatusPalette::Tritanopia => Color::from_rgb(0.8, 0.8, 0.8),
+        }
+    }
+
+    // A reading past one of its limits.
+    pub fn alarm(self) -> Color {
+        match self {
+            StatusPalette::Standard => Color::from_rgb(0.8, 0.2, 0.2),
+            StatusPalette::Deuteranopia | StatusPalette::Protanopia => Color::from_rgb(0.9, 0.6, 0.0),
+            StatusPalette::Tritanopia => Color::from_rgb(0.84, 0.37, 0.0),
+        }
+    }
+}
+
+impl std::fmt::Display for StatusPalette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            StatusPalette::Standard => "Standard",
+            StatusPalette::Deuteranopia => "Deuteranopia-safe",
+            StatusPalette::Protanopia => "Protanopia-safe",
+            StatusPalette::Tritanopia => "Tritanopia-safe",
+        };
+        write!(f, "{}", label)
+    }
+}
@@ -0,0 +1,434 @@
+// User configuration, read from `$XDG_CONFIG_HOME/sensory/sensory.conf`.
+//
+// The format is deliberately simple: `key = value` lines, optional `[section]`
+// headers, and `#` comments. Unknown keys are ignored so older builds can read
+// newer files.
+use crate::colors::StatusPalette;
+use crate::mini::Corner;
+use crate::{Backend, LimitColumn};
+use monitor_core::{ec, mqtt, prometheus};
+use monitor_core::fan::{FanCurve, FanProfile, BUILTIN_PROFILES};
+use monitor_core::openrgb::{self, LightingRule};
+use monitor_core::script::{Probe, ScriptSensor};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    // Minimum change in a reading that makes its row flash, and for how long.
+    pub flash_delta: f64,
+    pub flash_duration_ms: u64,
+    // `[sensors] backend`: where readings come from; the last one picked is kept.
+    // `[sensors] extra = nvidia-smi, ...` (or `none`) replaces the default of every
+    // extra source whose hardware is present.
+    pub backend: Backend,
+    pub extra_backends: Option<Vec<Backend>>,
+    // Start with unconnected channels (0 RPM fans, 0 V inputs) hidden.
+    pub hide_disconnected: bool,
+    // Point out every sensor output line that did not parse with a banner, rather
+    // than only counting them in the toolbar.
+    pub strict_parsing: bool,
+    // Minimize instead of quitting when the window's close button is pressed.
+    pub minimize_on_close: bool,
+    // Raw `[keymap]` entries (action name, binding); see `keymap::Keymap::from_config`.
+    pub keymap: Vec<(String, String)>,
+    // `[display] palette`: status colors, including color-blind safe sets.
+    pub palette: StatusPalette,
+    // `[display] limit_columns = min, max, crit` (or `none`): the limits given columns
+    // of their own; the rest stay in the details text.
+    pub limit_columns: Vec<LimitColumn>,
+    // `[display] sparklines`: a chart of the last samples next to each reading.
+    pub sparklines: bool,
+    // `[display] headline = <section>/<entry>`: reading appended to the window title.
+    pub headline: Option<(String, String)>,
+    // `[pinned] sensor = <section>/<entry>` lines: the readings shown in mini mode.
+    pub pinned: Vec<(String, String)>,
+    // `[smoothing] <section>/<entry> = <alpha>` lines: readings shown as a moving
+    // average, weighting the newest sample by alpha (0 to 1).
+    pub smoothing: Vec<((String, String), f64)>,
+    // `[mini] corner` and `[mini] screen = <width>x<height>`; the screen size is
+    // detected when unset, but scaled desktops need the logical size here.
+    pub mini_corner: Corner,
+    pub mini_screen: Option<iced::Size>,
+    // One `[fan <chip>/pwmN]` section per fan with `source = <section>/<entry>`,
+    // `points = <°C>:<duty %>, ...`, an optional `zero_rpm = <°C>` and `enabled`.
+    pub fan_curves: Vec<(String, FanCurve)>,
+    // °C at which a manual fan override hands the fan back; the linked sensor's own
+    // high limit wins when it is lower.
+    pub fan_safety_limit: f64,
+    // `[profile <name>]` sections: `<chip>/pwmN = <points>` per fan, and optionally
+    // `trigger = <section>/<entry>` with `trigger_above = <°C>`. The built-in names
+    // always exist; `[fans] profile` is the one chosen last.
+    pub fan_profiles: Vec<FanProfile>,
+    pub fan_profile: Option<String>,
+    // `[openrgb] server = <host>:<port>`, and one `[rgb <device name>]` section per
+    // OpenRGB device with `source = <section>/<entry>`, `colors = <°C>:#rrggbb, ...`
+    // and `enabled`.
+    pub openrgb_server: String,
+    pub lighting: Vec<(String, LightingRule)>,
+    // `[ec]` lines `<name> = <offset> <u8|s8|u16|u16be> [<unit>] [<scale>]`: embedded
+    // controller registers to show, e.g. `CPU = 0x58 u8 °C`.
+    pub ec_registers: Vec<ec::Register>,
+    // One `[sensor <label>]` section per custom sensor, with `file = <path>` or
+    // `command = <shell command>`, and optionally `unit` and `parse` (see
+    // `ScriptSensor::parse`).
+    pub script_sensors: Vec<ScriptSensor>,
+    // `[mqtt] server = <host>:<port>`, optional `username` and `password`, and
+    // `topic = <filter> [<unit>]` lines for the remote sensors to subscribe to.
+    pub mqtt: mqtt::Settings,
+    // `[prometheus] url = http://<host>:9100/metrics`: a node_exporter to scrape, every
+    // `interval` seconds.
+    pub prometheus_url: Option<String>,
+    pub prometheus_interval: std::time::Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            flash_delta: 1.0,
+            flash_duration_ms: 1500,
+            backend: Backend::native(),
+            extra_backends: None,
+            hide_disconnected: false,
+            strict_parsing: false,
+            minimize_on_close: false,
+            keymap: Vec::new(),
+            palette: StatusPalette::default(),
+            limit_columns: LimitColumn::ALL.to_vec(),
+            sparklines: true,
+            headline: None,
+            pinned: Vec::new(),
+            smoothing: Vec::new(),
+            mini_corner: Corner::default(),
+            mini_screen: None,
+            fan_curves: Vec::new(),
+            fan_safety_limit: 85.0,
+            fan_profiles: BUILTIN_PROFILES.iter().map(|name| FanProfile::new(name)).collect(),
+            fan_profile: None,
+            openrgb_server: openrgb::DEFAULT_SERVER.to_string(),
+            lighting: Vec::new(),
+            ec_registers: Vec::new(),
+            script_sensors: Vec::new(),
+            mqtt: mqtt::Settings::default(),
+            prometheus_url: None,
+            prometheus_interval: prometheus::DEFAULT_INTERVAL,
+        }
+    }
+}
+
+impl Config {
+    pub fn flash_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.flash_duration_ms)
+    }
+
+    pub fn load() -> Self {
+        let mut config = Config::default();
+        let Some(contents) = config_path().and_then(|path| fs::read_to_string(path).ok()) else {
+            return config;
+        };
+
+        for (section, key, value) in parse(&contents) {
+            config.set(&section, &key, &value);
+        }
+        config
+    }
+
+    fn set(&mut self, section: &str, key: &str, value: &str) {
+        match (section, key) {
+            ("", "flash_delta") => set_parsed(&mut self.flash_delta, value),
+            ("", "flash_duration_ms") => set_parsed(&mut self.flash_duration_ms, value),
+            ("", "hide_disconnected") => set_parsed(&mut self.hide_disconnected, value),
+            ("", "strict_parsing") => set_parsed(&mut self.strict_parsing, value),
+            ("", "minimize_on_close") => set_parsed(&mut self.minimize_on_close, value),
+            ("", "fan_safety_limit") => set_parsed(&mut self.fan_safety_limit, value),
+            ("sensors", "backend") => {
+                if let Some(backend) = Backend::parse(value) {
+                    self.backend = backend;
+                }
+            }
+            ("sensors", "extra") => {
+                self.extra_backends = Some(value.split(',').filter_map(Backend::parse).collect());
+            }
+            ("display", "palette") => {
+                if let Some(palette) = StatusPalette::parse(value) {
+                    self.palette = palette;
+                }
+            }
+            ("display", "limit_columns") => {
+                self.limit_columns = value.split(',').filter_map(LimitColumn::parse).collect();
+            }
+            ("display", "sparklines") => set_parsed(&mut self.sparklines, value),
+            ("display", "headline") => self.headline = sensor_path(value),
+            ("pinned", "sensor") => self.pinned.extend(sensor_path(value)),
+            ("smoothing", sensor) => {
+                if let (Some(sensor), Ok(alpha)) = (sensor_path(sensor), value.parse::<f64>()) {
+                    if alpha > 0.0 && alpha <= 1.0 {
+                        self.smoothing.push((sensor, alpha));
+                    }
+                }
+            }
+            ("mini", "corner") => {
+                if let Some(corner) = Corner::parse(value) {
+                    self.mini_corner = corner;
+                }
+            }
+            ("mini", "screen") => self.mini_screen = crate::mini::parse_size(value),
+            (section, key) if section.starts_with("fan ") => {
+                let curve = self.fan_curve_mut(section["fan ".len()..].trim());
+                match key {
+                    "source" => {
+                        if let Some(source) = sensor_path(value) {
+                            curve.source = source;
+                        }
+                    }
+                    "points" => {
+                        if let Some(points) = FanCurve::parse_points(value) {
+                            curve.points = points;
+                        }
+                    }
+                    "zero_rpm" => curve.zero_rpm = value.parse().ok(),
+                    "enabled" => set_parsed(&mut curve.enabled, value),
+                    _ => {}
+                }
+            }
+            (section, key) if section.starts_with("profile ") => {
+                let profile = self.fan_profile_mut(section["profile ".len()..].trim());
+                match key {
+                    "trigger" => {
+                        if let Some(source) = sensor_path(value) {
+                            let limit = profile.trigger.as_ref().map_or(f64::INFINITY, |(_, limit)| *limit);
+                            profile.trigger = Some((source, limit));
+                        }
+                    }
+                    "trigger_above" => {
+                        if let (Some((_, limit)), Ok(value)) = (&mut profile.trigger, value.parse()) {
+                            *limit = value;
+                        }
+                    }
+                    fan => {
+                        if let Some(points) = FanCurve::parse_points(value) {
+                            profile.curves.push((fan.to_string(), points));
+                        }
+                    }
+                }
+            }
+            ("fans", "profile") => self.fan_profile = Some(value.to_string()),
+            ("openrgb", "server") => self.openrgb_server = value.to_string(),
+            (section, key) if section.starts_with("rgb ") => {
+                let rule = self.lighting_rule_mut(section["rgb ".len()..].trim());
+                match key {
+                    "source" => {
+                        if let Some(source) = sensor_path(value) {
+                            rule.source = source;
+                        }
+                    }
+                    "colors" => {
+                        if let Some(stops) = LightingRule::parse_stops(value) {
+                            rule.stops = stops;
+                        }
+                    }
+                    "enabled" => set_parsed(&mut rule.enabled, value),
+                    _ => {}
+                }
+            }
+            (section, key) if section.starts_with("sensor ") => {
+                let sensor = self.script_sensor_mut(section["sensor ".len()..].trim());
+                match key {
+                    "file" => sensor.probe = Some(Probe::File(value.to_string())),
+                    "command" => sensor.probe = Some(Probe::Command(value.to_string())),
+                    "unit" => sensor.unit = value.to_string(),
+                    "parse" => sensor.parse = value.to_string(),
+                    _ => {}
+                }
+            }
+            ("mqtt", "server") => self.mqtt.server = value.to_string(),
+            ("mqtt", "username") => self.mqtt.username = Some(value.to_string()),
+            ("mqtt", "password") => self.mqtt.password = Some(value.to_string()),
+            ("mqtt", "topic") => self.mqtt.add_topic(value),
+            ("prometheus", "url") => self.prometheus_url = Some(value.to_string()),
+            ("prometheus", "interval") => {
+                if let Ok(seconds) = value.parse::<f64>() {
+                    self.prometheus_interval = std::time::Duration::from_secs_f64(seconds.max(0.5));
+                }
+            }
+            ("ec", name) => self.ec_registers.extend(ec::Register::parse(name, value)),
+            ("keymap", action) => self.keymap.push((action.to_string(), value.to_string())),
+            _ => {}
+        }
+    }
+
+    pub fn smoothing_alpha(&self, section: &str, key: &str) -> Option<f64> {
+        self.smoothing.iter().find(|((s, k), _)| s == section && k == key).map(|(_, alpha)| *alpha)
+    }
+
+    pub fn fan_curve(&self, fan: &str) -> Option<&FanCurve> {
+        self.fan_curves.iter().find(|(id, _)| id == fan).map(|(_, curve)| curve)
+    }
+
+    pub fn fan_curve_mut(&mut self, fan: &str) -> &mut FanCurve {
+        let i = match self.fan_curves.iter().position(|(id, _)| id == fan) {
+            Some(i) => i,
+            None => {
+                self.fan_curves.push((fan.to_string(), FanCurve::default()));
+                self.fan_curves.len() - 1
+            }
+        };
+        &mut self.fan_curves[i].1
+    }
+
+    pub fn lighting_rule_mut(&mut self, device: &str) -> &mut LightingRule {
+        let i = match self.lighting.iter().position(|(name, _)| name == device) {
+            Some(i) => i,
+            None => {
+                self.lighting.push((device.to_string(), LightingRule::default()));
+                self.lighting.len() - 1
+            }
+        };
+        &mut self.lighting[i].1
+    }
+
+    pub fn script_sensor_mut(&mut self, label: &str) -> &mut ScriptSensor {
+        let i = match self.script_sensors.iter().position(|sensor| sensor.label == label) {
+            Some(i) => i,
+            None => {
+                self.script_sensors.push(ScriptSensor::new(label));
+                self.script_sensors.len() - 1
+            }
+        };
+        &mut self.script_sensors[i]
+    }
+
+    pub fn fan_profile_mut(&mut self, name: &str) -> &mut FanProfile {
+        let i = match self.fan_profiles.iter().position(|profile| profile.name == name) {
+            Some(i) => i,
+            None => {
+                self.fan_profiles.push(FanProfile::new(name));
+                self.fan_profiles.len() - 1
+            }
+        };
+        &mut self.fan_profiles[i]
+    }
+}
+
+pub fn save_fan_profile(profile: &FanProfile) -> Result<(), String> {
+    let mut pairs: Vec<(String, String)> = profile
+        .curves
+        .iter()
+        .map(|(fan, points)| {
+            let points = FanCurve { points: points.clone(), ..FanCurve::default() }.format_points();
+            (fan.clone(), points)
+        })
+        .collect();
+    if let Some(((section, key), limit)) = &profile.trigger {
+        pairs.push(("trigger".to_string(), format!("{}/{}", section, key)));
+        pairs.push(("trigger_above".to_string(), limit.to_string()));
+    }
+    save_section(&format!("profile {}", profile.name), &pairs)
+}
+
+// The `[fan <id>]` section a curve is saved as.
+pub fn save_fan_curve(fan: &str, curve: &FanCurve) -> Result<(), String> {
+    let mut pairs = vec![
+        ("source".to_string(), format!("{}/{}", curve.source.0, curve.source.1)),
+        ("points".to_string(), curve.format_points()),
+    ];
+    if let Some(threshold) = curve.zero_rpm {
+        pairs.push(("zero_rpm".to_string(), threshold.to_string()));
+    }
+    pairs.push(("enabled".to_string(), curve.enabled.to_string()));
+    save_section(&format!("fan {}", fan), &pairs)
+}
+
+// The `[rgb <device>]` section a lighting rule is saved as.
+pub fn save_lighting_rule(device: &str, rule: &LightingRule) -> Result<(), String> {
+    let pairs = [
+        ("source".to_string(), format!("{}/{}", rule.source.0, rule.source.1)),
+        ("colors".to_string(), rule.format_stops()),
+        ("enabled".to_string(), rule.enabled.to_string()),
+    ];
+    save_section(&format!("rgb {}", device), &pairs)
+}
+
+// Writes an exported file (e.g. a fancontrol config) next to the config file.
+pub fn save_export(name: &str, contents: &str) -> Result<PathBuf, String> {
+    let path = config_path().ok_or("No config directory available")?.with_file_name(name);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+// "<section>/<entry>" -> (section, entry).
+pub fn sensor_path(value: &str) -> Option<(String, String)> {
+    value
+        .split_once('/')
+        .map(|(section, key)| (section.to_string(), key.to_string()))
+}
+
+// Leaves the default in place when the value does not parse.
+fn set_parsed<T: std::str::FromStr>(field: &mut T, value: &str) {
+    if let Ok(parsed) = value.parse() {
+        *field = parsed;
+    }
+}
+
+// Rewrites one `[section]` of the config file with `pairs`, leaving every other line
+// (including comments) as the user wrote it.
+pub fn save_section(section: &str, pairs: &[(String, String)]) -> Result<(), String> {
+    let path = config_path().ok_or("No config directory available")?;
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+
+    let mut contents = String::new();
+    let mut in_section = false;
+    for line in existing.lines() {
+        let trimmed = line.trim();
+        if let Some(name) = trimmed.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            in_section = name.trim() == section;
+        }
+        if !in_section {
+            contents.push_str(line);
+            contents.push('\n');
+        }
+    }
+
+    if !contents.is_empty() && !contents.ends_with("\n\n") {
+        contents.push('\n');
+    }
+    contents.push_str(&format!("[{}]\n", section));
+    for (key, value) in pairs {
+        contents.push_str(&format!("{} = {}\n", key, value));
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("sensory").join("sensory.conf"))
+}
+
+// Yields (section, key, value) triples; keys before any header have an empty section.
+pub fn parse(contents: &str) -> Vec<(String, String, String)> {
+    let mut section = String::new();
+    let mut pairs = Vec::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            section = name.trim().to_string();
+        } else if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim().trim_matches('"');
+            pairs.push((section.clone(), key.trim().to_string(), value.to_string()));
+        }
+    }
+    pairs
+}
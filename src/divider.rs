@@ -0,0 +1,157 @@
+// A thin vertical handle that reports horizontal drags, used to resize columns.
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer;
+use iced::advanced::widget::{tree, Tree, Widget};
+use iced::advanced::{Clipboard, Shell};
+use iced::event::{self, Event};
+use iced::mouse;
+use iced::{Border, Color, Element, Length, Rectangle, Size};
+
+const HANDLE_WIDTH: f32 = 7.0;
+
+pub struct Divider<Message> {
+    on_drag: Box<dyn Fn(f32) -> Message>,
+    on_release: Message,
+    height: f32,
+}
+
+impl<Message> Divider<Message> {
+    // `on_drag` receives the horizontal movement since the previous event.
+    pub fn new(height: f32, on_drag: impl Fn(f32) -> Message + 'static, on_release: Message) -> Self {
+        Divider {
+            on_drag: Box::new(on_drag),
+            on_release,
+            height,
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    // Cursor x at the last emitted drag, while the handle is held.
+    drag_origin: Option<f32>,
+}
+
+impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer> for Divider<Message>
+where
+    Message: Clone,
+    Renderer: iced::advanced::Renderer,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size::new(Length::Fixed(HANDLE_WIDTH), Length::Fixed(self.height))
+    }
+
+    fn layout(&self, _tree: &mut Tree, _renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        layout::Node::new(limits.resolve(
+            Length::Fixed(HANDLE_WIDTH),
+            Length::Fixed(self.height),
+            Size::new(HANDLE_WIDTH, self.height),
+        ))
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        _viewport: &Rectangle,
+    ) -> event::Status {
+        let state = tree.state.downcast_mut::<State>();
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                if let Some(position) = cursor.position_over(layout.bounds()) {
+                    state.drag_origin = Some(position.x);
+                    return event::Status::Captured;
+                }
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                if let Some(origin) = state.drag_origin {
+                    shell.publish((self.on_drag)(position.x - origin));
+                    state.drag_origin = Some(position.x);
+                    return event::Status::Captured;
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+                if state.drag_origin.is_some() =>
+            {
+                state.drag_origin = None;
+                shell.publish(self.on_release.clone());
+                return event::Status::Captured;
+            }
+            _ => {}
+        }
+        event::Status::Ignored
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let dragging = tree.state.downcast_ref::<State>().drag_origin.is_some();
+        if dragging || cursor.is_over(layout.bounds()) {
+            mouse::Interaction::ResizingHorizontally
+        } else {
+            mouse::Interaction::Idle
+        }
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        _theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        let active = tree.state.downcast_ref::<State>().drag_origin.is_some() || cursor.is_over(bounds);
+        let line = Rectangle {
+            x: bounds.center_x() - 0.5,
+            width: 1.0,
+            ..bounds
+        };
+        let color = if active {
+            Color::from_rgb(0.53, 0.81, 0.92)
+        } else {
+            Color::from_rgb(0.3, 0.3, 0.3)
+        };
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: if active { bounds } else { line },
+                border: Border::with_radius(2.0),
+                ..renderer::Quad::default()
+            },
+            if active { Color { a: 0.5, ..color } } else { color },
+        );
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<Divider<Message>> for Element<'a, Message, Theme, Renderer>
+where
+    Message: Clone + 'a,
+    Renderer: iced::advanced::Renderer + 'a,
+{
+    fn from(divider: Divider<Message>) -> Self {
+        Element::new(divider)
+    }
+}
@@ -0,0 +1,42 @@
+// Short explanations for sensor names that are cryptic to non-experts.
+
+// (lowercase name prefix, explanation). Checked in order, so specific names come first.
+const EXPLANATIONS: &[(&str, &str)] = &[
+    ("tctl", "AMD control temperature. On some Ryzen CPUs it is offset above the real die temperature; fan curves use it."),
+    ("tdie", "AMD die temperature: the real silicon temperature, without the Tctl offset."),
+    ("tccd", "AMD core complex die temperature; one reading per chiplet (CCD)."),
+    ("control (tctl)", "AMD control temperature. On some Ryzen CPUs it is offset above the real die temperature; fan curves use it."),
+    ("die (tdie)", "AMD die temperature: the real silicon temperature, without the Tctl offset."),
+    ("ccd ", "AMD core complex die temperature; one reading per chiplet (CCD)."),
+    ("hottest ccd", "The hottest of the AMD chiplets (CCDs); the one that throttles first."),
+    ("package id", "Intel CPU package temperature: the hottest point across the whole processor."),
+    ("core ", "Temperature of one physical CPU core."),
+    ("systin", "Nuvoton/ITE Super I/O: motherboard (system) temperature sensor."),
+    ("cputin", "Nuvoton/ITE Super I/O: CPU socket temperature as seen by the motherboard."),
+    ("auxtin", "Nuvoton/ITE Super I/O: auxiliary temperature input; often unconnected and reading nonsense."),
+    ("peci agent", "CPU temperature reported to the motherboard over PECI (Platform Environment Control Interface)."),
+    ("pch", "Platform Controller Hub (chipset) temperature."),
+    ("vddgfx", "GPU core voltage (AMD)."),
+    ("vddnb", "SoC / north bridge voltage on AMD APUs and GPUs."),
+    ("vcore", "CPU core supply voltage."),
+    ("ppt", "AMD Package Power Tracking: total power drawn by the CPU or GPU package."),
+    ("edge", "AMD GPU edge temperature, measured at the edge of the die."),
+    ("junction", "AMD GPU junction (hotspot) temperature: the hottest point on the die."),
+    ("mem", "GPU memory (VRAM) temperature."),
+    ("composite", "NVMe composite temperature: the drive's overall temperature, used for throttling."),
+    ("sensor 1", "NVMe auxiliary temperature sensor; which component it measures is vendor specific."),
+    ("sensor 2", "NVMe auxiliary temperature sensor; which component it measures is vendor specific."),
+    ("intrusion", "Chassis intrusion switch: ALARM means the case was opened."),
+    ("cpu fan", "Fan connected to the CPU fan header."),
+    ("fan", "Fan speed on a motherboard fan header. 0 RPM usually means nothing is connected."),
+    ("in", "Voltage input on the monitoring chip; its meaning depends on the motherboard wiring."),
+    ("temp", "Generic temperature channel; its location depends on the chip and motherboard."),
+];
+
+pub fn explain(key: &str) -> Option<&'static str> {
+    let key = key.trim().to_lowercase();
+    EXPLANATIONS
+        .iter()
+        .find(|(prefix, _)| key.starts_with(prefix))
+        .map(|(_, explanation)| *explanation)
+}
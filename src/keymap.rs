@@ -0,0 +1,229 @@
+// Rebindable keyboard shortcuts, configured in the `[keymap]` section of the config:
+//
+//     [keymap]
+//     collapse_all = ctrl+-
+//     quit = none
+use iced::keyboard::{Key, Modifiers};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    CommandPalette,
+    CollapseAll,
+    ExpandAll,
+    TogglePause,
+    ToggleSidebar,
+    ToggleMiniMode,
+    JumpToAlert,
+    CycleFanProfile,
+    Refresh,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 10] = [
+        Action::CommandPalette,
+        Action::CollapseAll,
+        Action::ExpandAll,
+        Action::TogglePause,
+        Action::ToggleSidebar,
+        Action::ToggleMiniMode,
+        Action::JumpToAlert,
+        Action::CycleFanProfile,
+        Action::Refresh,
+        Action::Quit,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::CommandPalette => "Command palette",
+            Action::CollapseAll => "Collapse all sections",
+            Action::ExpandAll => "Expand all sections",
+            Action::TogglePause => "Pause / resume sampling",
+            Action::ToggleSidebar => "Toggle section sidebar",
+            Action::ToggleMiniMode => "Toggle mini mode",
+            Action::JumpToAlert => "Jump to next alert",
+            Action::CycleFanProfile => "Next fan profile",
+            Action::Refresh => "Refresh now",
+            Action::Quit => "Quit",
+        }
+    }
+
+    // The key used for this action in the config file.
+    pub fn config_name(self) -> &'static str {
+        match self {
+            Action::CommandPalette => "command_palette",
+            Action::CollapseAll => "collapse_all",
+            Action::ExpandAll => "expand_all",
+            Action::TogglePause => "toggle_pause",
+            Action::ToggleSidebar => "toggle_sidebar",
+            Action::ToggleMiniMode => "toggle_mini_mode",
+            Action::JumpToAlert => "jump_to_alert",
+            Action::CycleFanProfile => "cycle_fan_profile",
+            Action::Refresh => "refresh",
+            Action::Quit => "quit",
+        }
+    }
+
+    fn default_binding(self) -> Option<KeyCombo> {
+        let binding = match self {
+            Action::CommandPalette => "ctrl+shift+p",
+            Action::CollapseAll => "ctrl+-",
+            Action::ExpandAll => "ctrl+=",
+            Action::TogglePause => "ctrl+space",
+            Action::ToggleSidebar => "ctrl+b",
+            Action::ToggleMiniMode => "ctrl+m",
+            Action::JumpToAlert => "ctrl+j",
+            Action::CycleFanProfile => "ctrl+shift+f",
+            Action::Refresh => "f5",
+            Action::Quit => "ctrl+q",
+        };
+        KeyCombo::parse(binding)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyCombo {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    // Lowercase character ("p", "-") or named key ("f5", "space", "escape").
+    pub key: String,
+}
+
+impl KeyCombo {
+    // Parses "ctrl+shift+p"; the last `+`-separated part is the key, so "ctrl++" works.
+    pub fn parse(text: &str) -> Option<KeyCombo> {
+        let text = text.trim().to_lowercase();
+        let (modifiers, key) = match text.strip_suffix("++") {
+            Some(rest) => (rest, "+"),
+            None => match text.rsplit_once('+') {
+                Some((modifiers, key)) => (modifiers, key),
+                None => ("", text.as_str()),
+            },
+        };
+        if key.is_empty() {
+            return None;
+        }
+
+        let mut combo = KeyCombo {
+            ctrl: false,
+            shift: false,
+            alt: false,
+            key: key.to_string(),
+        };
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            match modifier {
+                "ctrl" | "control" | "cmd" => combo.ctrl = true,
+                "shift" => combo.shift = true,
+                "alt" => combo.alt = true,
+                _ => return None,
+            }
+        }
+        Some(combo)
+    }
+
+    // `None` for bare modifier presses, which cannot be bound on their own.
+    pub fn from_event(key: &Key, modifiers: Modifiers) -> Option<KeyCombo> {
+        use iced::keyboard::key::Named;
+
+        let key = match key.as_ref() {
+            Key::Character(c) => c.to_lowercase(),
+            Key::Named(Named::Shift | Named::Control | Named::Alt | Named::Super) => return None,
+            Key::Named(Named::Space) => "space".to_string(),
+            Key::Named(named) => format!("{:?}", named).to_lowercase(),
+            Key::Unidentified => return None,
+        };
+        Some(KeyCombo {
+            ctrl: modifiers.command(),
+            shift: modifiers.shift(),
+            alt: modifiers.alt(),
+            key,
+        })
+    }
+}
+
+impl std::fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.ctrl {
+            write!(f, "ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "shift+")?;
+        }
+        if self.alt {
+            write!(f, "alt+")?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Action, Option<KeyCombo>)>,
+}
+
+impl Keymap {
+    // Defaults with the config's overrides applied; "none" disables an action.
+    pub fn from_config(overrides: &[(String, String)]) -> Self {
+        let mut keymap = Keymap {
+            bindings: Action::ALL.iter().map(|&a| (a, a.default_binding())).collect(),
+        };
+        for (name, value) in overrides {
+            let Some(action) = Action::ALL.iter().find(|a| a.config_name() == name) else {
+                continue;
+            };
+            if value == "none" {
+                keymap.bind(*action, None);
+            } else if let Some(combo) = KeyCombo::parse(value) {
+                keymap.bind(*action, Some(combo));
+            }
+        }
+        keymap
+    }
+
+    pub fn binding(&self, action: Action) -> Option<&KeyCombo> {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .and_then(|(_, combo)| combo.as_ref())
+    }
+
+    pub fn action_for(&self, combo: &KeyCombo) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, bound)| bound.as_ref() == Some(combo))
+            .map(|(action, _)| *action)
+    }
+
+    // Binds `action`, unbinding any other action that used the same combo; returns it.
+    pub fn bind(&mut self, action: Action, combo: Option<KeyCombo>) -> Option<Action> {
+        let conflict = combo
+            .as_ref()
+            .and_then(|combo| self.action_for(combo))
+            .filter(|other| *other != action);
+        for (bound_action, bound) in &mut self.bindings {
+            if Some(*bound_action) == conflict {
+                *bound = None;
+            }
+            if *bound_action == action {
+                *bound = combo.clone();
+            }
+        }
+        conflict
+    }
+
+    pub fn reset(&mut self) {
+        *self = Keymap::from_config(&[]);
+    }
+
+    // (config name, binding) pairs for writing back to the config file.
+    pub fn to_config(&self) -> Vec<(String, String)> {
+        self.bindings
+            .iter()
+            .map(|(action, combo)| {
+                let value = combo.as_ref().map_or("none".to_string(), |c| c.to_string());
+                (action.config_name().to_string(), value)
+            })
+            .collect()
+    }
+}
@@ -1,173 +1,3547 @@
+mod colors;
+mod config;
+mod divider;
+mod glossary;
+mod keymap;
+mod mini;
+mod palette;
+mod setup;
+mod sparkline;
+mod stack;
+mod state;
+
 use iced::{
-    widget::{column, container, horizontal_rule, row, scrollable, text, Column, Space},
+    widget::{
+        button, checkbox, column, container, mouse_area, pick_list, progress_bar, row, scrollable, slider, text,
+        text_input, tooltip, vertical_slider, Column, Space,
+    },
     Alignment, Application, Color, Command, Element, Length, Settings, Subscription, Theme
 };
-use once_cell::sync::Lazy;
-use regex::Regex;
-use std::process::Command as StdCommand;
-use std::time::Duration;
+use colors::StatusPalette;
+use config::Config;
+use divider::Divider;
+use keymap::{Action, KeyCombo, Keymap};
+use monitor_core::cpufreq::{self, CpuPolicy, Turbo};
+use monitor_core::detect::{self, Detection, MissingDriver};
+use monitor_core::fan::{self, FanCurve, PwmFan};
+use monitor_core::helper;
+use monitor_core::model::{self, Limits, SensorEntry, SensorSection, SensorValue, Status};
+use monitor_core::mqtt::MqttSource;
+use monitor_core::openrgb::{self, Controller, Rgb};
+use monitor_core::prometheus::PrometheusSource;
+use monitor_core::smoothing::{self, Smoother};
+use monitor_core::source::{self, ReadResult, Registry, SensorSource};
+use monitor_core::sysinfo::{self, SystemInfo};
+use monitor_core::upower::UpowerSource;
+use monitor_core::{
+    amdgpu, battery, diskstats, ec, hwmon, intel_gpu, lhm, liquidctl, lm_sensors, loadavg, meminfo, net, nvidia_smi,
+    prometheus, rapl, script, smart, smc, sysctl, thermal, throttle, upower, ups, vcgencmd, wireless,
+};
+#[cfg(target_os = "linux")]
+use monitor_core::{nvme, processes};
+use setup::SetupInfo;
+use sparkline::Sparkline;
+use stack::Stack;
+use state::{ColumnWidths, UiState, MIN_COLUMN_WIDTH};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+const HEADER_COLOR: Color = Color::from_rgb(0.53, 0.81, 0.92);
+const TEXT_COLOR: Color = Color::from_rgb(0.9, 0.9, 0.9);
+const BACKGROUND_COLOR: Color = Color::from_rgb(0.1, 0.1, 0.1);
+const ROW_ALT_COLOR: Color = Color::from_rgb(0.15, 0.15, 0.15);
+const ERROR_COLOR: Color = Color::from_rgb(0.8, 0.2, 0.2);
+const FLASH_COLOR: Color = Color::from_rgb(0.35, 0.3, 0.1);
+const FOCUS_COLOR: Color = Color::from_rgb(0.15, 0.25, 0.4);
+const UNAVAILABLE_COLOR: Color = Color::from_rgb(0.5, 0.5, 0.5);
+
+// Font sizes (converted to u16)-(Also remember to add Body)
+const HEADER_FONT_SIZE: u16 = 18;
+const USAGE_BAR_WIDTH: f32 = 80.0;
+const LIMIT_COLUMN_WIDTH: f32 = 70.0;
+const SPARKLINE_WIDTH: f32 = 60.0;
+// Samples a sparkline covers, 30 s at the 500 ms refresh.
+const SPARKLINE_SAMPLES: usize = 60;
+
+// Layout metrics. Rows and headers have fixed heights so section positions can be
+// computed from the scroll offset (used by the pinned header).
+const CONTENT_PADDING: f32 = 20.0;
+const SECTION_SPACING: f32 = 20.0;
+const SECTION_PADDING: f32 = 20.0;
+const HEADER_HEIGHT: f32 = 24.0;
+const HEADER_ROWS_SPACING: f32 = 10.0;
+const ROW_HEIGHT: f32 = 42.0;
+const ROW_SPACING: f32 = 5.0;
+const ROW_PADDING: f32 = 10.0;
+const COLUMN_SPACING: f32 = 20.0;
+
+fn main() -> iced::Result {
+    let ui_state = UiState::load();
+    let window = ui_state.window;
+    SensorViewer::run(Settings {
+        window: iced::window::Settings {
+            size: iced::Size::new(window.width, window.height), // Use iced::Size::new for the window size
+            position: match window.position {
+                Some((x, y)) => iced::window::Position::Specific(iced::Point::new(x as f32, y as f32)),
+                None => iced::window::Position::Default,
+            },
+            resizable: true,  // You can toggle whether the window should be resizable
+            exit_on_close_request: false, // Close requests go through `shutdown` so state gets saved
+            ..Default::default()
+        },
+        flags: ui_state,
+        ..Default::default()
+    })
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Refresh,
+    Scrolled(scrollable::Viewport),
+    Retry,
+    DismissError,
+    BackendLoaded(Backend, ReadResult),
+    SwitchBackend(Backend),
+    ToggleExtra(Backend),
+    ToggleTroubleshooting,
+    ToggleSection(String),
+    CollapseAll,
+    ExpandAll,
+    CollapseOthers(String),
+    HideDisconnected(bool),
+    ResizeColumn(TableColumn, f32),
+    SaveUiState,
+    ToggleSidebar,
+    JumpToSection(String),
+    Window(iced::window::Event),
+    Quit,
+    TogglePause,
+    OpenPalette,
+    ClosePalette,
+    PaletteInput(String),
+    PaletteMove(i32),
+    PaletteRun,
+    KeyPressed(KeyCombo),
+    ToggleSettings,
+    CaptureShortcut(Action),
+    ClearShortcut(Action),
+    ResetShortcuts,
+    SelectPalette(StatusPalette),
+    ShowLimitColumn(LimitColumn, bool),
+    ShowSparklines(bool),
+    SetHeadline(Option<(String, String)>),
+    TogglePin(String, String),
+    ToggleSmoothing(String, String),
+    ToggleMiniMode,
+    CycleMiniCorner,
+    DragWindow,
+    JumpToAlert,
+    ToggleRaw(String),
+    ToggleDiagnostics,
+    CopyDiagnostics,
+    ToggleFans,
+    EditCurve(String, CurveEdit),
+    ApplyCurve(String),
+    RevertCurve(String),
+    ReleaseFan(String),
+    OverrideFan(String, f64),
+    EndOverride(String),
+    ExportFancontrol,
+    SelectFanProfile(String),
+    CycleFanProfile,
+    SaveFanProfile,
+    OpenDetect,
+    CloseDetect,
+    RunDetect,
+    DetectFinished(Result<String, String>),
+    LoadModules(Vec<String>),
+    SaveBootModules(Vec<String>),
+    ModulesChanged(Result<String, String>),
+    DismissMissingDrivers,
+    FindRgbDevices,
+    EditLighting(String, LightingEdit),
+    SaveLighting(String),
+    ToggleCpuPower,
+    // `None` sets every policy.
+    SetGovernor(Option<u32>, String),
+    SetEpp(Option<u32>, String),
+    SetTurbo(bool),
+}
+
+#[derive(Debug, Clone)]
+enum LightingEdit {
+    // A "<section>/<entry>" label.
+    Source(String),
+    Colors(String),
+    Enabled(bool),
+}
+
+// After OpenRGB could not be reached, how long before lighting tries again.
+const RGB_RETRY: Duration = Duration::from_secs(30);
+
+// The steps of the sensors-detect setup flow.
+#[derive(Debug, Clone)]
+enum DetectStep {
+    // Explains what will run, and how, before anything does.
+    Consent,
+    Running,
+    Done(Detection),
+    Failed(String),
+}
+
+// Changes to the fan curve being edited; applied to a draft until ApplyCurve.
+#[derive(Debug, Clone)]
+enum CurveEdit {
+    // A "<section>/<entry>" temperature.
+    Source(String),
+    Duty(usize, f64),
+    Shift(usize, f64),
+    AddPoint,
+    RemovePoint(usize),
+    ZeroRpm(Option<f64>),
+}
+
+// The resizable columns of a sensor row; the details column takes the remaining width.
+#[derive(Debug, Clone, Copy)]
+enum TableColumn {
+    Key,
+    Value,
+    Unit,
+}
+
+// Limits shown as columns of their own, between the unit and the details. Temperatures
+// use low/high where other readings use min/max, so each column takes either.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LimitColumn {
+    Min,
+    Max,
+    Crit,
+}
+
+impl LimitColumn {
+    const ALL: [LimitColumn; 3] = [LimitColumn::Min, LimitColumn::Max, LimitColumn::Crit];
+
+    // As written to `[display] limit_columns`.
+    fn config_name(self) -> &'static str {
+        match self {
+            LimitColumn::Min => "min",
+            LimitColumn::Max => "max",
+            LimitColumn::Crit => "crit",
+        }
+    }
+
+    fn parse(name: &str) -> Option<LimitColumn> {
+        LimitColumn::ALL.into_iter().find(|column| column.config_name() == name.trim())
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            LimitColumn::Min => "Min",
+            LimitColumn::Max => "Max",
+            LimitColumn::Crit => "Crit",
+        }
+    }
+
+    // The names the column takes over from the details text ("high = +80.0°C").
+    fn limit_names(self) -> &'static [&'static str] {
+        match self {
+            LimitColumn::Min => &["min", "low"],
+            LimitColumn::Max => &["max", "high"],
+            LimitColumn::Crit => &["crit", "crit max"],
+        }
+    }
+
+    fn value(self, limits: &Limits) -> Option<f64> {
+        match self {
+            LimitColumn::Min => limits.min.or(limits.low),
+            LimitColumn::Max => limits.max.or(limits.high),
+            LimitColumn::Crit => limits.crit,
+        }
+    }
+}
+
+
+// Sources of sensor sections, read in this order and merged. hwmon is the default on
+// Linux: it needs no lm-sensors install and reads the kernel's values unrounded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Backend {
+    LmSensors,
+    // `sensors -u`: raw subfeature values at full precision.
+    LmSensorsRaw,
+    // `sensors -j`: the same values as JSON, for lm-sensors 3.5 and later.
+    LmSensorsJson,
+    Hwmon,
+    // /sys/class/thermal, also the fallback when the chosen backend finds nothing.
+    ThermalZone,
+    // The defaults on Windows, macOS and FreeBSD.
+    Lhm,
+    Smc,
+    Sysctl,
+    // Extras, read next to the chosen backend (see `Backend::EXTRAS`).
+    NvidiaSmi,
+    Amdgpu,
+    IntelGpu,
+    Battery,
+    CpuFrequency,
+    Throttle,
+    Memory,
+    DiskIo,
+    Network,
+    Wireless,
+    Rapl,
+    Vcgencmd,
+    NvmeLog,
+    Ups,
+    // Needs a register map in `[ec]`.
+    Ec,
+    Liquidctl,
+    // `[sensor <label>]` sections in the config.
+    Script,
+    Mqtt,
+    Prometheus,
+    // Listed first, above every other section.
+    LoadAverage,
+    // Opt-in: only read when `[sensors] extra` lists them.
+    Smartctl,
+    Processes,
+    // Batteries again, as UPower reports them; the battery extra already lists them.
+    Upower,
+}
+
+impl Backend {
+    const ALL: [Backend; 31] = [
+        Backend::Hwmon,
+        Backend::ThermalZone,
+        Backend::LmSensors,
+        Backend::LmSensorsRaw,
+        Backend::LmSensorsJson,
+        Backend::Lhm,
+        Backend::Smc,
+        Backend::Sysctl,
+        Backend::NvidiaSmi,
+        Backend::Amdgpu,
+        Backend::IntelGpu,
+        Backend::Battery,
+        Backend::CpuFrequency,
+        Backend::Throttle,
+        Backend::Memory,
+        Backend::DiskIo,
+        Backend::Network,
+        Backend::Wireless,
+        Backend::Rapl,
+        Backend::Vcgencmd,
+        Backend::NvmeLog,
+        Backend::Ups,
+        Backend::Ec,
+        Backend::Liquidctl,
+        Backend::Script,
+        Backend::Mqtt,
+        Backend::Prometheus,
+        Backend::LoadAverage,
+        Backend::Smartctl,
+        Backend::Processes,
+        Backend::Upower,
+    ];
+    // Sources for hardware the main backends cannot see. Unless `[sensors] extra`
+    // lists them, each is read whenever its hardware is present. smartctl (root, one
+    // run per drive) and the process list (not hardware) are not among them.
+    const EXTRAS: [Backend; 20] = [
+        Backend::NvidiaSmi,
+        Backend::Amdgpu,
+        Backend::IntelGpu,
+        Backend::Battery,
+        Backend::CpuFrequency,
+        Backend::Throttle,
+        Backend::Memory,
+        Backend::DiskIo,
+        Backend::Network,
+        Backend::Wireless,
+        Backend::Rapl,
+        Backend::Vcgencmd,
+        Backend::NvmeLog,
+        Backend::Ups,
+        Backend::Ec,
+        Backend::Liquidctl,
+        Backend::Script,
+        Backend::Mqtt,
+        Backend::Prometheus,
+        Backend::LoadAverage,
+    ];
+
+    // The backend a fresh config starts with: the one for this platform.
+    fn native() -> Backend {
+        if cfg!(windows) {
+            Backend::Lhm
+        } else if cfg!(target_os = "macos") {
+            Backend::Smc
+        } else if cfg!(target_os = "freebsd") {
+            Backend::Sysctl
+        } else {
+            Backend::Hwmon
+        }
+    }
+
+    // As written to `[sensors] backend`.
+    fn config_name(self) -> &'static str {
+        match self {
+            Backend::LmSensors => "lm-sensors",
+            Backend::LmSensorsRaw => "lm-sensors-raw",
+            Backend::LmSensorsJson => "lm-sensors-json",
+            Backend::Hwmon => "hwmon",
+            Backend::ThermalZone => "thermal-zone",
+            Backend::Lhm => "librehardwaremonitor",
+            Backend::Smc => "smc",
+            Backend::Sysctl => "sysctl",
+            Backend::NvidiaSmi => "nvidia-smi",
+            Backend::Amdgpu => "amdgpu",
+            Backend::IntelGpu => "intel-gpu",
+            Backend::Battery => "battery",
+            Backend::CpuFrequency => "cpufreq",
+            Backend::Throttle => "throttle",
+            Backend::Memory => "memory",
+            Backend::DiskIo => "disk-io",
+            Backend::Network => "network",
+            Backend::Wireless => "wireless",
+            Backend::Rapl => "rapl",
+            Backend::Vcgencmd => "vcgencmd",
+            Backend::NvmeLog => "nvme-log",
+            Backend::Ups => "ups",
+            Backend::Ec => "ec",
+            Backend::Liquidctl => "liquidctl",
+            Backend::Script => "custom",
+            Backend::Mqtt => "mqtt",
+            Backend::Prometheus => "prometheus",
+            Backend::LoadAverage => "loadavg",
+            Backend::Smartctl => "smartctl",
+            Backend::Processes => "processes",
+            Backend::Upower => "upower",
+        }
+    }
+
+    fn available(self, config: &Config) -> bool {
+        match self {
+            Backend::Ec => !config.ec_registers.is_empty(),
+            Backend::Script => !config.script_sensors.is_empty(),
+            Backend::Mqtt => !config.mqtt.server.is_empty() && !config.mqtt.topics.is_empty(),
+            Backend::Prometheus => config.prometheus_url.is_some(),
+            Backend::Lhm => lhm::available(),
+            Backend::Smc => smc::available(),
+            Backend::Sysctl => sysctl::available(),
+            Backend::NvidiaSmi => nvidia_smi::available(),
+            Backend::Amdgpu => amdgpu::available(),
+            Backend::IntelGpu => intel_gpu::available(),
+            Backend::Battery => battery::available(),
+            Backend::Wireless => wireless::available(),
+            Backend::Rapl => rapl::available(),
+            Backend::Vcgencmd => vcgencmd::available(),
+            #[cfg(target_os = "linux")]
+            Backend::NvmeLog => nvme::available(),
+            #[cfg(not(target_os = "linux"))]
+            Backend::NvmeLog | Backend::Processes => false,
+            Backend::Ups => ups::available(),
+            Backend::Liquidctl => liquidctl::available(),
+            Backend::CpuFrequency => cpufreq::available(),
+            Backend::Throttle => throttle::available(),
+            Backend::Upower => upower::available(),
+            _ => true,
+        }
+    }
+
+    fn parse(name: &str) -> Option<Backend> {
+        Backend::ALL.into_iter().find(|backend| backend.config_name() == name.trim())
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Backend::LmSensors => "lm-sensors",
+            Backend::LmSensorsRaw => "lm-sensors (raw)",
+            Backend::LmSensorsJson => "lm-sensors (JSON)",
+            Backend::Hwmon => "hwmon (sysfs)",
+            Backend::ThermalZone => "Thermal zones (sysfs)",
+            Backend::Lhm => "LibreHardwareMonitor (WMI)",
+            Backend::Smc => "Apple SMC",
+            Backend::Sysctl => "FreeBSD (sysctl)",
+            Backend::NvidiaSmi => "NVIDIA (nvidia-smi)",
+            Backend::Amdgpu => "AMD GPU (amdgpu)",
+            Backend::IntelGpu => "Intel GPU (i915/xe)",
+            Backend::Battery => "Battery",
+            Backend::CpuFrequency => "CPU frequency (cpufreq)",
+            Backend::Throttle => "CPU throttling (thermal_throttle)",
+            Backend::Memory => "Memory (/proc/meminfo)",
+            Backend::DiskIo => "Disk I/O (/proc/diskstats)",
+            Backend::Network => "Network (/sys/class/net)",
+            Backend::Wireless => "Wi-Fi (/proc/net/wireless)",
+            Backend::Rapl => "RAPL power (powercap)",
+            Backend::Vcgencmd => "Raspberry Pi (vcgencmd)",
+            Backend::NvmeLog => "NVMe health log (ioctl)",
+            Backend::Ups => "UPS (NUT/apcupsd)",
+            Backend::Ec => "Embedded controller (ec_sys)",
+            Backend::Liquidctl => "Coolers (liquidctl)",
+            Backend::Script => "Custom sensors",
+            Backend::Mqtt => "Remote sensors (MQTT)",
+            Backend::Prometheus => "Remote machine (Prometheus)",
+            Backend::LoadAverage => "Load average",
+            Backend::Smartctl => "Drives (smartctl)",
+            Backend::Processes => "Top processes",
+            Backend::Upower => "Power devices (UPower)",
+        }
+    }
+
+    // The backend as a registry entry, reading with the config as it is now.
+    fn source(self, config: &Config) -> Box<dyn SensorSource> {
+        match (self, &config.prometheus_url) {
+            (Backend::Mqtt, _) => return Box::new(MqttSource::start(config.mqtt.clone())),
+            // Updated on UPower's change signals rather than on every poll.
+            (Backend::Upower, _) => return Box::new(UpowerSource::start()),
+            // Scrapes on its own thread and keeps the last scrape for the polls in between.
+            (Backend::Prometheus, Some(url)) => {
+                return Box::new(PrometheusSource::new(url, config.prometheus_interval));
+            }
+            _ => {}
+        }
+        let config = config.clone();
+        match self.background_interval() {
+            Some(interval) => Box::new(source::background(self.label(), interval, move || self.read(&config))),
+            None => Box::new(source::from_fn(self.label(), move || self.read(&config))),
+        }
+    }
+
+    // Backends that run a tool too slow for the UI thread read on a thread of their
+    // own, this often.
+    fn background_interval(self) -> Option<Duration> {
+        match self {
+            Backend::Lhm => Some(lhm::POLL_INTERVAL),
+            Backend::Liquidctl => Some(liquidctl::POLL_INTERVAL),
+            Backend::NvidiaSmi => Some(nvidia_smi::POLL_INTERVAL),
+            Backend::Ups => Some(ups::POLL_INTERVAL),
+            Backend::Vcgencmd => Some(vcgencmd::POLL_INTERVAL),
+            Backend::Script => Some(script::POLL_INTERVAL),
+            Backend::Smartctl => Some(smart::POLL_INTERVAL),
+            _ => None,
+        }
+    }
+
+    fn read(self, config: &Config) -> ReadResult {
+        let main = matches!(
+            self,
+            Backend::LmSensors | Backend::LmSensorsRaw | Backend::LmSensorsJson | Backend::Hwmon
+        );
+        match self.read_once(config) {
+            // ARM boards often have thermal zones but no chip lm-sensors lists; keep the
+            // backend's own error if there are none either.
+            Err(e) if main => thermal::read_thermal_zone_data().map_err(|_| e),
+            result => result,
+        }
+    }
+
+    fn read_once(self, config: &Config) -> ReadResult {
+        match self {
+            Backend::LmSensors => lm_sensors::read_sensor_data(),
+            Backend::LmSensorsRaw => lm_sensors::read_raw_sensor_data(),
+            Backend::LmSensorsJson => lm_sensors::read_json_sensor_data(),
+            Backend::Hwmon => hwmon::read_hwmon_data(),
+            Backend::Lhm => lhm::read_lhm_data(),
+            Backend::Smc => smc::read_smc_data(),
+            Backend::Sysctl => sysctl::read_sysctl_data(),
+            Backend::ThermalZone => thermal::read_thermal_zone_data(),
+            Backend::NvidiaSmi => nvidia_smi::read_nvidia_data(),
+            Backend::Amdgpu => amdgpu::read_amdgpu_data(),
+            Backend::IntelGpu => intel_gpu::read_intel_gpu_data(),
+            Backend::Battery => battery::read_battery_data(),
+            Backend::CpuFrequency => cpufreq::read_frequency_data(),
+            Backend::Throttle => throttle::read_throttle_data(),
+            Backend::Memory => meminfo::read_memory_data(),
+            Backend::DiskIo => diskstats::read_disk_io_data(),
+            Backend::Network => net::read_network_data(),
+            Backend::Wireless => wireless::read_wireless_data(),
+            Backend::Rapl => rapl::read_rapl_data(),
+            Backend::Vcgencmd => vcgencmd::read_vcgencmd_data(),
+            #[cfg(target_os = "linux")]
+            Backend::NvmeLog => nvme::read_nvme_data(),
+            Backend::Ups => ups::read_ups_data(),
+            Backend::Ec => ec::read_ec_data(&config.ec_registers),
+            Backend::Liquidctl => liquidctl::read_liquidctl_data(),
+            Backend::Script => script::read_script_data(&config.script_sensors),
+            // Read through its source, which holds the subscription; nothing has
+            // arrived before that exists.
+            Backend::Mqtt => Ok(Vec::new()),
+            Backend::Prometheus => match &config.prometheus_url {
+                Some(url) => prometheus::read_prometheus_data(url),
+                None => Err("No Prometheus URL configured".to_string()),
+            },
+            Backend::LoadAverage => loadavg::read_load_data(),
+            Backend::Smartctl => smart::read_smart_data(),
+            #[cfg(target_os = "linux")]
+            Backend::Processes => processes::read_process_data(),
+            #[cfg(not(target_os = "linux"))]
+            Backend::NvmeLog | Backend::Processes => Err(format!("{} needs Linux", self.label())),
+            Backend::Upower => upower::read_upower_data(),
+        }
+    }
+}
+
+struct SensorViewer {
+    // Last successfully read data; kept on screen while refreshes fail.
+    sensor_data: Vec<SensorSection>,
+    error: Option<String>,
+    error_dismissed: bool,
+    scroll_offset: f32,
+    // Per-backend results of the initial read; empty once startup has finished.
+    startup: Vec<(Backend, Option<ReadResult>)>,
+    backends: Vec<Backend>,
+    // The enabled backends as sources, polled every sample.
+    registry: Registry,
+    // Moving averages of the readings the config smooths.
+    smoother: Smoother,
+    // The last samples of every numeric reading, oldest first, for the sparklines.
+    history: HashMap<(String, String), VecDeque<f64>>,
+    // Populated while there is nothing to show; drives the empty-state hints.
+    setup_info: Option<SetupInfo>,
+    show_troubleshooting: bool,
+    config: Config,
+    // When each (section, entry) last changed by more than the flash delta.
+    flashes: HashMap<(String, String), Instant>,
+    // Names of sections whose rows are hidden.
+    collapsed: HashSet<String>,
+    hide_disconnected: bool,
+    ui_state: UiState,
+    show_sidebar: bool,
+    // Set once shutdown has started; stops the sampler.
+    quitting: bool,
+    paused: bool,
+    palette: Option<PaletteState>,
+    keymap: Keymap,
+    show_settings: bool,
+    // The action waiting for its new shortcut in the editor.
+    capturing: Option<Action>,
+    settings_notice: Option<String>,
+    // Showing only the pinned readings in a small undecorated window.
+    mini: bool,
+    // The row last jumped to from the alert banner, and when, for its highlight.
+    focused: Option<((String, String), Instant)>,
+    // Sections whose unparsed raw lines are expanded.
+    raw_expanded: HashSet<String>,
+    show_diagnostics: bool,
+    // Fans with a PWM output, rescanned every sample.
+    fans: Vec<PwmFan>,
+    show_fans: bool,
+    // Curves being edited, by fan id; the config holds the applied ones.
+    fan_drafts: HashMap<String, FanCurve>,
+    // The last error writing each fan, e.g. permission denied.
+    fan_notices: HashMap<String, String>,
+    // The mode each fan was in before the app took it over, and the watchdog that
+    // restores it if the app dies first.
+    fan_takeover: fan::Takeover,
+    // Manual duty cycles being tried out; they win over curves until ended.
+    fan_overrides: HashMap<String, f64>,
+    // Result of the last fancontrol export or profile change.
+    fans_notice: Option<String>,
+    // The profile a trigger temperature switched to, over the chosen one.
+    triggered_profile: Option<String>,
+    detect: Option<DetectStep>,
+    // Outcome of loading or saving the recommended modules.
+    detect_notice: Option<String>,
+    // Drivers the hardware suggests but that are not loaded.
+    missing_drivers: Vec<MissingDriver>,
+    missing_drivers_dismissed: bool,
+    // The OpenRGB connection, its devices and the color each was last set to.
+    rgb: Option<openrgb::Client>,
+    rgb_devices: Vec<Controller>,
+    rgb_sent: HashMap<String, Rgb>,
+    rgb_retry: Option<Instant>,
+    rgb_notice: Option<String>,
+    // Color stops being typed, by device; applied once they parse.
+    rgb_drafts: HashMap<String, String>,
+    show_cpu_power: bool,
+    cpu_policies: Vec<CpuPolicy>,
+    turbo: Option<Turbo>,
+    cpu_notice: Option<String>,
+    system: SystemInfo,
+    // Seconds since boot, as of the last sample.
+    uptime: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+struct PaletteState {
+    query: String,
+    // Index into the filtered results.
+    selected: usize,
+}
+
+impl Application for SensorViewer {
+    type Executor = iced::executor::Default;
+    type Message = Message;
+    type Theme = Theme;
+    type Flags = UiState;
+
+    fn new(ui_state: UiState) -> (Self, Command<Self::Message>) {
+        let config = Config::load();
+        let backends = active_backends(&config);
+        let viewer = SensorViewer {
+            sensor_data: Vec::new(),
+            error: None,
+            error_dismissed: false,
+            scroll_offset: 0.0,
+            startup: backends.iter().map(|&backend| (backend, None)).collect(),
+            backends: backends.clone(),
+            registry: Registry::new(backends.iter().map(|backend| backend.source(&config)).collect()),
+            smoother: Smoother::new(),
+            history: HashMap::new(),
+            setup_info: None,
+            show_troubleshooting: false,
+            flashes: HashMap::new(),
+            collapsed: HashSet::new(),
+            hide_disconnected: config.hide_disconnected,
+            keymap: Keymap::from_config(&config.keymap),
+            config,
+            ui_state,
+            show_sidebar: false,
+            quitting: false,
+            paused: false,
+            palette: None,
+            show_settings: false,
+            capturing: None,
+            settings_notice: None,
+            mini: false,
+            focused: None,
+            raw_expanded: HashSet::new(),
+            show_diagnostics: false,
+            fans: fan::find_fans(),
+            show_fans: false,
+            fan_drafts: HashMap::new(),
+            fan_notices: HashMap::new(),
+            fan_takeover: fan::Takeover::default(),
+            fan_overrides: HashMap::new(),
+            fans_notice: None,
+            triggered_profile: None,
+            detect: None,
+            detect_notice: None,
+            missing_drivers: detect::find_missing_drivers(),
+            missing_drivers_dismissed: false,
+            rgb: None,
+            rgb_devices: Vec::new(),
+            rgb_sent: HashMap::new(),
+            rgb_retry: None,
+            rgb_notice: None,
+            rgb_drafts: HashMap::new(),
+            show_cpu_power: false,
+            cpu_policies: cpufreq::read_policies(),
+            turbo: cpufreq::read_turbo(),
+            cpu_notice: None,
+            system: SystemInfo::read(),
+            uptime: sysinfo::read_uptime(),
+        };
+        let probes: Vec<_> = backends
+            .into_iter()
+            .map(|backend| {
+                let config = viewer.config.clone();
+                Command::perform(async move { backend.read(&config) }, move |result| {
+                    Message::BackendLoaded(backend, result)
+                })
+            })
+            .collect();
+        (viewer, Command::batch(probes))
+    }
+
+    fn title(&self) -> String {
+        match self.headline_entry() {
+            Some(entry) => format!("Sensory — {} {}", entry.key, entry.display_reading()),
+            None => String::from("Sensory"),
+        }
+    }
+
+    fn theme(&self) -> Theme {
+        Theme::Dark
+    }
+
+    fn update(&mut self, message: Message) -> Command<Self::Message> {
+        match message {
+            Message::Refresh | Message::Retry => {
+                self.refresh();
+            }
+            Message::DismissError => {
+                self.error_dismissed = true;
+            }
+            Message::Scrolled(viewport) => {
+                self.scroll_offset = viewport.absolute_offset().y;
+            }
+            Message::BackendLoaded(backend, result) => {
+                if let Some((_, slot)) = self.startup.iter_mut().find(|(b, _)| *b == backend) {
+                    *slot = Some(result);
+                }
+                if self.startup.iter().all(|(_, slot)| slot.is_some()) {
+                    let results = self.startup.drain(..).filter_map(|(_, slot)| slot).collect();
+                    self.apply(source::merge(results));
+                }
+            }
+            Message::SwitchBackend(backend) => {
+                self.config.backend = backend;
+                self.save_backends();
+            }
+            Message::ToggleExtra(extra) => {
+                let extras = self.config.extra_backends.get_or_insert_with(|| Backend::EXTRAS.to_vec());
+                match extras.iter().position(|&listed| listed == extra) {
+                    Some(index) => {
+                        extras.remove(index);
+                    }
+                    None => extras.push(extra),
+                }
+                self.save_backends();
+            }
+            Message::ToggleTroubleshooting => {
+                self.show_troubleshooting = !self.show_troubleshooting;
+            }
+            Message::ToggleSection(name) => {
+                if !self.collapsed.remove(&name) {
+                    self.collapsed.insert(name);
+                }
+            }
+            Message::CollapseAll => {
+                self.collapsed = self.sensor_data.iter().map(|s| s.name.clone()).collect();
+            }
+            Message::ExpandAll => {
+                self.collapsed.clear();
+            }
+            Message::CollapseOthers(name) => {
+                self.collapsed = self
+                    .sensor_data
+                    .iter()
+                    .map(|s| s.name.clone())
+                    .filter(|other| *other != name)
+                    .collect();
+            }
+            Message::HideDisconnected(hide) => {
+                self.hide_disconnected = hide;
+            }
+            Message::ResizeColumn(column, delta) => {
+                let columns = &mut self.ui_state.columns;
+                let width = match column {
+                    TableColumn::Key => &mut columns.key,
+                    TableColumn::Value => &mut columns.value,
+                    TableColumn::Unit => &mut columns.unit,
+                };
+                *width = (*width + delta).max(MIN_COLUMN_WIDTH);
+            }
+            Message::ToggleSidebar => {
+                self.show_sidebar = !self.show_sidebar;
+            }
+            Message::JumpToSection(name) => {
+                let offsets = section_offsets(&self.sensor_data, &self.collapsed, self.hide_disconnected, &self.raw_expanded);
+                if let Some(position) = self.sensor_data.iter().position(|s| s.name == name) {
+                    let (start, _) = offsets[position];
+                    return scrollable::scroll_to(
+                        sections_scroll_id(),
+                        scrollable::AbsoluteOffset { x: 0.0, y: start },
+                    );
+                }
+            }
+            Message::Window(event) => match event {
+                // The mini window's geometry is not the one to restore next session.
+                iced::window::Event::Resized { .. } | iced::window::Event::Moved { .. } if self.mini => {}
+                iced::window::Event::Resized { width, height } => {
+                    self.ui_state.window.width = width as f32;
+                    self.ui_state.window.height = height as f32;
+                }
+                iced::window::Event::Moved { x, y } => {
+                    self.ui_state.window.position = Some((x, y));
+                }
+                iced::window::Event::CloseRequested if self.config.minimize_on_close => {
+                    return iced::window::minimize(iced::window::Id::MAIN, true);
+                }
+                iced::window::Event::CloseRequested => return self.shutdown(),
+                _ => {}
+            },
+            Message::Quit => return self.shutdown(),
+            Message::TogglePause => {
+                self.paused = !self.paused;
+            }
+            Message::OpenPalette => {
+                self.palette = Some(PaletteState::default());
+                return text_input::focus(palette_input_id());
+            }
+            Message::ClosePalette => {
+                self.palette = None;
+            }
+            Message::PaletteInput(query) => {
+                if let Some(palette) = &mut self.palette {
+                    palette.query = query;
+                    palette.selected = 0;
+                }
+            }
+            Message::PaletteMove(step) => {
+                let matches = self.palette.as_ref().map(|p| self.palette_matches(&p.query).len());
+                if let (Some(palette), Some(matches)) = (&mut self.palette, matches) {
+                    let last = matches.saturating_sub(1) as i32;
+                    palette.selected = (palette.selected as i32 + step).clamp(0, last) as usize;
+                }
+            }
+            Message::KeyPressed(combo) => {
+                if let Some(action) = self.capturing.take() {
+                    let plain_escape = combo.key == "escape" && !(combo.ctrl || combo.shift || combo.alt);
+                    if !plain_escape {
+                        let conflict = self.keymap.bind(action, Some(combo.clone()));
+                        self.settings_notice = conflict.map(|other| {
+                            format!("{} was bound to {} and is now disabled", combo, other.label())
+                        });
+                        self.save_keymap();
+                    }
+                } else if let Some(action) = self.keymap.action_for(&combo) {
+                    return self.update(action_message(action));
+                }
+            }
+            Message::ToggleSettings => {
+                self.show_settings = !self.show_settings;
+                self.show_diagnostics = false;
+                self.show_fans = false;
+                self.show_cpu_power = false;
+                self.capturing = None;
+            }
+            Message::CaptureShortcut(action) => {
+                self.capturing = Some(action);
+                self.settings_notice = None;
+            }
+            Message::ClearShortcut(action) => {
+                self.keymap.bind(action, None);
+                self.save_keymap();
+            }
+            Message::ResetShortcuts => {
+                self.keymap.reset();
+                self.settings_notice = None;
+                self.save_keymap();
+            }
+            Message::SelectPalette(palette) => {
+                self.config.palette = palette;
+                self.save_display();
+            }
+            Message::ShowLimitColumn(column, show) => {
+                self.config.limit_columns.retain(|shown| *shown != column);
+                if show {
+                    self.config.limit_columns.push(column);
+                    // Keep the columns in their fixed order whatever order they were turned on in.
+                    self.config.limit_columns =
+                        LimitColumn::ALL.into_iter().filter(|c| self.config.limit_columns.contains(c)).collect();
+                }
+                self.save_display();
+            }
+            Message::ShowSparklines(show) => {
+                self.config.sparklines = show;
+                self.save_display();
+            }
+            Message::SetHeadline(headline) => {
+                self.config.headline = headline;
+                self.save_display();
+            }
+            Message::ToggleSmoothing(section, key) => {
+                let sensor = (section, key);
+                match self.config.smoothing.iter().position(|(s, _)| *s == sensor) {
+                    Some(i) => {
+                        self.config.smoothing.remove(i);
+                    }
+                    None => self.config.smoothing.push((sensor, smoothing::DEFAULT_ALPHA)),
+                }
+                let pairs: Vec<_> = self
+                    .config
+                    .smoothing
+                    .iter()
+                    .map(|((section, key), alpha)| (format!("{}/{}", section, key), alpha.to_string()))
+                    .collect();
+                if let Err(e) = config::save_section("smoothing", &pairs) {
+                    self.settings_notice = Some(e);
+                }
+            }
+            Message::TogglePin(section, key) => {
+                let pin = (section, key);
+                match self.config.pinned.iter().position(|p| *p == pin) {
+                    Some(i) => {
+                        self.config.pinned.remove(i);
+                    }
+                    None => self.config.pinned.push(pin),
+                }
+                let pinned: Vec<_> = self
+                    .config
+                    .pinned
+                    .iter()
+                    .map(|(section, key)| ("sensor".to_string(), format!("{}/{}", section, key)))
+                    .collect();
+                if let Err(e) = config::save_section("pinned", &pinned) {
+                    self.settings_notice = Some(e);
+                }
+                if self.mini {
+                    return self.place_mini_window();
+                }
+            }
+            Message::ToggleMiniMode => return self.set_mini(!self.mini),
+            Message::CycleMiniCorner => {
+                self.config.mini_corner = self.config.mini_corner.next();
+                let mut pairs = vec![("corner".to_string(), self.config.mini_corner.config_name().to_string())];
+                if let Some(screen) = self.config.mini_screen {
+                    pairs.push(("screen".to_string(), format!("{}x{}", screen.width, screen.height)));
+                }
+                if let Err(e) = config::save_section("mini", &pairs) {
+                    self.settings_notice = Some(e);
+                }
+                return self.place_mini_window();
+            }
+            Message::DragWindow => return iced::window::drag(iced::window::Id::MAIN),
+            Message::JumpToAlert => return self.jump_to_alert(),
+            Message::ToggleDiagnostics => {
+                self.show_diagnostics = !self.show_diagnostics;
+                self.show_settings = false;
+                self.show_fans = false;
+                self.show_cpu_power = false;
+            }
+            Message::ToggleFans => {
+                self.show_fans = !self.show_fans;
+                self.show_settings = false;
+                self.show_diagnostics = false;
+                self.show_cpu_power = false;
+            }
+            Message::EditCurve(id, edit) => {
+                let sources = temperature_sources(&self.sensor_data);
+                let applied = self.config.fan_curve(&id).cloned();
+                let curve = self.fan_drafts.entry(id).or_insert_with(|| {
+                    applied.unwrap_or_else(|| FanCurve {
+                        source: sources.first().cloned().unwrap_or_default(),
+                        ..FanCurve::default()
+                    })
+                });
+                edit_curve(curve, edit);
+            }
+            Message::ApplyCurve(id) => {
+                if let Some(draft) = self.fan_drafts.remove(&id) {
+                    self.take_over_fan(&id);
+                    let curve = self.config.fan_curve_mut(&id);
+                    *curve = FanCurve { enabled: true, ..draft };
+                    if let Err(e) = config::save_fan_curve(&id, curve) {
+                        self.fan_notices.insert(id.clone(), e);
+                    }
+                    self.drive_fans();
+                }
+            }
+            Message::RevertCurve(id) => {
+                self.fan_drafts.remove(&id);
+            }
+            Message::ReleaseFan(id) => {
+                let curve = self.config.fan_curve_mut(&id);
+                curve.enabled = false;
+                let saved = config::save_fan_curve(&id, curve);
+                self.fan_overrides.remove(&id);
+                match saved.and(self.release_fan(&id)) {
+                    Ok(()) => self.fan_notices.remove(&id),
+                    Err(e) => self.fan_notices.insert(id, e),
+                };
+            }
+            Message::OverrideFan(id, duty) => {
+                self.take_over_fan(&id);
+                self.fan_overrides.insert(id, duty);
+                self.drive_fans();
+            }
+            Message::ExportFancontrol => {
+                let saved = fan::export_fancontrol(&self.config.fan_curves)
+                    .and_then(|contents| config::save_export("fancontrol", &contents));
+                self.fans_notice = Some(match saved {
+                    Ok(path) => format!(
+                        "Saved {}. Install it with `sudo install -m 644 {} /etc/fancontrol`, then enable fancontrol.service.",
+                        path.display(),
+                        path.display()
+                    ),
+                    Err(e) => e,
+                });
+            }
+            Message::SelectFanProfile(name) => {
+                self.config.fan_profile = Some(name.clone());
+                let mut saved = config::save_section("fans", &[("profile".to_string(), name.clone())]);
+                // A triggered profile stays in charge; the choice applies once it lets go.
+                if self.triggered_profile.is_none() {
+                    for id in self.apply_fan_profile(&name) {
+                        if let Some(curve) = self.config.fan_curve(&id) {
+                            saved = saved.and(config::save_fan_curve(&id, curve));
+                        }
+                    }
+                    self.drive_fans();
+                }
+                self.fans_notice = saved.err();
+            }
+            Message::CycleFanProfile => {
+                let profiles = &self.config.fan_profiles;
+                let current = profiles.iter().position(|p| Some(&p.name) == self.config.fan_profile.as_ref());
+                let next = current.map_or(0, |i| (i + 1) % profiles.len().max(1));
+                if let Some(profile) = profiles.get(next) {
+                    return self.update(Message::SelectFanProfile(profile.name.clone()));
+                }
+            }
+            Message::SaveFanProfile => {
+                if let Some(name) = self.active_fan_profile().map(str::to_string) {
+                    let curves: Vec<_> = self
+                        .config
+                        .fan_curves
+                        .iter()
+                        .map(|(id, curve)| (id.clone(), curve.points.clone()))
+                        .collect();
+                    let profile = self.config.fan_profile_mut(&name);
+                    profile.curves = curves;
+                    self.fans_notice = Some(match config::save_fan_profile(profile) {
+                        Ok(()) => format!("Saved the current curves as the {} profile", name),
+                        Err(e) => e,
+                    });
+                }
+            }
+            Message::OpenDetect => {
+                self.detect = Some(DetectStep::Consent);
+                self.detect_notice = None;
+            }
+            Message::CloseDetect => {
+                self.detect = None;
+            }
+            Message::RunDetect => {
+                self.detect = Some(DetectStep::Running);
+                return Command::perform(async { detect::run_sensors_detect() }, Message::DetectFinished);
+            }
+            Message::DetectFinished(result) => {
+                self.detect = Some(match result {
+                    Ok(output) => DetectStep::Done(detect::parse_sensors_detect(&output)),
+                    Err(e) => DetectStep::Failed(e),
+                });
+            }
+            Message::LoadModules(modules) => {
+                self.detect_notice = Some(format!("Loading {}…", modules.join(", ")));
+                return Command::perform(
+                    async move {
+                        modules.iter().try_for_each(|module| helper::load_module(module))?;
+                        Ok(format!("Loaded {}", modules.join(", ")))
+                    },
+                    Message::ModulesChanged,
+                );
+            }
+            Message::SaveBootModules(modules) => {
+                return Command::perform(
+                    async move {
+                        helper::save_boot_modules(&modules)?;
+                        Ok(format!("{} will load at every boot", modules.join(", ")))
+                    },
+                    Message::ModulesChanged,
+                );
+            }
+            Message::ModulesChanged(result) => {
+                let loaded = result.is_ok();
+                self.detect_notice = Some(result.unwrap_or_else(|e| e));
+                if loaded {
+                    self.missing_drivers = detect::find_missing_drivers();
+                    self.refresh();
+                }
+            }
+            Message::DismissMissingDrivers => {
+                self.missing_drivers_dismissed = true;
+            }
+            Message::ToggleCpuPower => {
+                self.show_cpu_power = !self.show_cpu_power;
+                self.show_settings = false;
+                self.show_diagnostics = false;
+                self.show_fans = false;
+                self.cpu_notice = None;
+                self.read_cpu_power();
+            }
+            Message::SetGovernor(id, governor) => {
+                let result = self
+                    .cpu_policies
+                    .iter()
+                    .filter(|policy| id.is_none_or(|id| policy.id == id))
+                    .try_for_each(|policy| cpufreq::set_governor(policy, &governor));
+                self.cpu_notice = result.err();
+                self.read_cpu_power();
+            }
+            Message::SetEpp(id, epp) => {
+                let result = self
+                    .cpu_policies
+                    .iter()
+                    .filter(|policy| id.is_none_or(|id| policy.id == id) && policy.epp.is_some())
+                    .try_for_each(|policy| cpufreq::set_epp(policy, &epp));
+                self.cpu_notice = result.err();
+                self.read_cpu_power();
+            }
+            Message::SetTurbo(enabled) => {
+                if let Some(turbo) = &self.turbo {
+                    self.cpu_notice = cpufreq::set_turbo(turbo, enabled).err();
+                }
+                self.read_cpu_power();
+            }
+            Message::FindRgbDevices => {
+                self.rgb = None;
+                self.rgb_retry = None;
+                self.rgb_notice = Some(match self.connect_rgb() {
+                    Ok(()) if self.rgb_devices.is_empty() => "OpenRGB has no devices".to_string(),
+                    Ok(()) => format!("Found {} OpenRGB devices", self.rgb_devices.len()),
+                    Err(e) => e,
+                });
+            }
+            Message::EditLighting(device, edit) => {
+                let rule = self.config.lighting_rule_mut(&device);
+                match edit {
+                    LightingEdit::Source(label) => {
+                        if let Some(source) = config::sensor_path(&label) {
+                            rule.source = source;
+                        }
+                    }
+                    LightingEdit::Colors(text) => {
+                        if let Some(stops) = openrgb::LightingRule::parse_stops(&text) {
+                            rule.stops = stops;
+                        }
+                        self.rgb_drafts.insert(device.clone(), text);
+                    }
+                    LightingEdit::Enabled(enabled) => rule.enabled = enabled,
+                }
+                self.rgb_sent.remove(&device);
+                self.drive_lighting();
+            }
+            Message::SaveLighting(device) => {
+                self.rgb_drafts.remove(&device);
+                let rule = self.config.lighting_rule_mut(&device);
+                self.rgb_notice = Some(match config::save_lighting_rule(&device, rule) {
+                    Ok(()) => format!("Saved the lighting of {}", device),
+                    Err(e) => e,
+                });
+            }
+            Message::EndOverride(id) => {
+                self.fan_overrides.remove(&id);
+                if !self.config.fan_curve(&id).is_some_and(|curve| curve.enabled) {
+                    if let Err(e) = self.release_fan(&id) {
+                        self.fan_notices.insert(id, e);
+                    }
+                }
+            }
+            Message::CopyDiagnostics => return iced::clipboard::write(diagnostics_report(&self.sensor_data)),
+            Message::ToggleRaw(name) => {
+                if !self.raw_expanded.remove(&name) {
+                    self.raw_expanded.insert(name);
+                }
+            }
+            Message::PaletteRun => {
+                if let Some(palette) = self.palette.take() {
+                    if let Some((_, message)) = self.palette_matches(&palette.query).into_iter().nth(palette.selected) {
+                        return self.update(message);
+                    }
+                }
+            }
+            Message::SaveUiState => {
+                if let Err(e) = self.ui_state.save() {
+                    self.error = Some(e);
+                    self.error_dismissed = false;
+                }
+            }
+        }
+        Command::none()
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        if !self.startup.is_empty() {
+            return container(startup_view(&self.startup))
+                .style(iced::theme::Container::Custom(Box::new(AppContainerStyle)))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x()
+                .center_y()
+                .into();
+        }
+
+        if self.mini {
+            return mini_view(&self.config.pinned, &self.sensor_data, self.config.palette);
+        }
+
+        let rows = self.row_context();
+        let content = match &self.setup_info {
+            Some(info) if self.sensor_data.is_empty() => {
+                column![empty_state(
+                    info,
+                    &self.backends,
+                    &self.missing_drivers,
+                    self.detect_notice.as_deref(),
+                    self.show_troubleshooting,
+                )]
+            }
+            _ => Column::with_children(
+                self.sensor_data
+                    .iter()
+                    .map(|section| {
+                        sensor_section(section, self.collapsed.contains(&section.name), &rows)
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+        };
+
+        let sections = scrollable(
+            column![content.spacing(SECTION_SPACING)]
+                .spacing(20)
+                .padding(CONTENT_PADDING),
+        )
+        .id(sections_scroll_id())
+        .on_scroll(Message::Scrolled);
+
+        let pinned = pinned_section(
+            &self.sensor_data,
+            &self.collapsed,
+            self.hide_disconnected,
+            &self.raw_expanded,
+            self.scroll_offset,
+        )
+        .map(pinned_header);
+
+        let banner = match &self.error {
+            Some(e) if !self.error_dismissed => Some(error_banner(e)),
+            _ => None,
+        };
+
+        let alerts = alerts(&self.sensor_data);
+        let alert_banner = (!alerts.is_empty()).then(|| alert_banner(&alerts, self.config.palette));
+        // The empty state lists them itself.
+        let missing_banner = (!self.missing_drivers.is_empty()
+            && !self.missing_drivers_dismissed
+            && !self.sensor_data.is_empty())
+        .then(|| missing_drivers_banner(&self.missing_drivers, self.detect_notice.as_deref()));
+        let unparsed = unparsed_count(&self.sensor_data);
+        let parse_banner = (self.config.strict_parsing && unparsed > 0).then(|| unparsed_banner(unparsed));
+
+        let main = if let Some(step) = &self.detect {
+            column![]
+                .push_maybe(banner)
+                .push(scrollable(
+                    column![detect_view(step, self.detect_notice.as_deref())].padding(CONTENT_PADDING),
+                ))
+        } else if self.show_settings {
+            column![]
+                .push_maybe(banner)
+                .push(scrollable(
+                    column![settings_view(
+                        &self.keymap,
+                        self.capturing,
+                        self.settings_notice.as_deref(),
+                        &self.config,
+                        lighting_view(self),
+                    )]
+                        .padding(CONTENT_PADDING),
+                ))
+        } else if self.show_cpu_power {
+            column![]
+                .push_maybe(banner)
+                .push(scrollable(
+                    column![cpu_power_view(&self.cpu_policies, self.turbo.as_ref(), self.cpu_notice.as_deref())]
+                        .padding(CONTENT_PADDING),
+                ))
+        } else if self.show_fans {
+            column![]
+                .push_maybe(banner)
+                .push(scrollable(
+                    column![fans_view(&self.fan_page(), &self.sensor_data)].padding(CONTENT_PADDING),
+                ))
+        } else if self.show_diagnostics {
+            column![]
+                .push_maybe(banner)
+                .push(scrollable(
+                    column![diagnostics_view(&self.sensor_data)].padding(CONTENT_PADDING),
+                ))
+        } else {
+            column![]
+                .push_maybe(banner)
+                .push_maybe(alert_banner)
+                .push_maybe(missing_banner)
+                .push_maybe(parse_banner)
+                .push(system_header(&self.system, self.uptime))
+                .push(column_header(self.ui_state.columns, &self.config.limit_columns, self.config.sparklines))
+                .push(Stack::new(sections, pinned))
+        };
+        let sidebar = self.show_sidebar.then(|| sidebar(&self.sensor_data, self.config.palette));
+
+        let app = column![
+            toolbar(
+                self.hide_disconnected,
+                self.show_sidebar,
+                self.paused,
+                unparsed,
+                (!self.fans.is_empty()).then(|| match self.active_fan_profile() {
+                    Some(profile) => format!("Fans: {}", profile),
+                    None => "Fans".to_string(),
+                }),
+                !self.cpu_policies.is_empty(),
+            ),
+            row![].push_maybe(sidebar).push(main),
+        ];
+        let palette = self
+            .palette
+            .as_ref()
+            .map(|palette| palette_view(palette, self.palette_matches(&palette.query)));
+
+        container(Stack::new(app, palette))
+        .style(iced::theme::Container::Custom(Box::new(AppContainerStyle)))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        let window_events = iced::event::listen_with(|event, _status| match event {
+            iced::Event::Window(_, event) => Some(Message::Window(event)),
+            _ => None,
+        });
+        if !self.startup.is_empty() || self.quitting {
+            return window_events;
+        }
+        let mut subscriptions = vec![
+            iced::keyboard::on_key_press(|key, modifiers| {
+                KeyCombo::from_event(&key, modifiers).map(Message::KeyPressed)
+            }),
+            // Raw, since the focused palette input captures Escape.
+            iced::event::listen_raw(palette_key),
+            window_events,
+        ];
+        if !self.paused {
+            subscriptions.push(iced::time::every(Duration::from_millis(500)).map(|_| Message::Refresh));
+        }
+        Subscription::batch(subscriptions)
+    }
+}
+
+impl SensorViewer {
+    // Stops sampling, writes out anything that must survive the session, then closes.
+    fn shutdown(&mut self) -> Command<Message> {
+        self.quitting = true;
+        for id in self.fan_takeover.taken() {
+            if let Err(e) = self.release_fan(&id) {
+                eprintln!("{}", e);
+            }
+        }
+        if let Err(e) = self.ui_state.save() {
+            eprintln!("{}", e);
+        }
+        iced::window::close(iced::window::Id::MAIN)
+    }
+
+    // Every action the palette can run, with the label it is searched by.
+    fn palette_commands(&self) -> Vec<(String, Message)> {
+        let mut commands = vec![
+            ("Collapse all sections".to_string(), Message::CollapseAll),
+            ("Expand all sections".to_string(), Message::ExpandAll),
+            (
+                if self.paused { "Resume sampling" } else { "Pause sampling" }.to_string(),
+                Message::TogglePause,
+            ),
+            ("Refresh now".to_string(), Message::Retry),
+            ("Detect sensor chips (sensors-detect)".to_string(), Message::OpenDetect),
+            (
+                if self.show_sidebar { "Hide section sidebar" } else { "Show section sidebar" }.to_string(),
+                Message::ToggleSidebar,
+            ),
+            (
+                if self.hide_disconnected { "Show unconnected channels" } else { "Hide unconnected channels" }
+                    .to_string(),
+                Message::HideDisconnected(!self.hide_disconnected),
+            ),
+            ("Use lm-sensors backend".to_string(), Message::SwitchBackend(Backend::LmSensors)),
+            ("Use lm-sensors raw (sensors -u) backend".to_string(), Message::SwitchBackend(Backend::LmSensorsRaw)),
+            ("Use lm-sensors JSON (sensors -j) backend".to_string(), Message::SwitchBackend(Backend::LmSensorsJson)),
+            ("Use hwmon backend".to_string(), Message::SwitchBackend(Backend::Hwmon)),
+            (
+                if self.backends.contains(&Backend::Smartctl) {
+                    "Stop reading drives through smartctl"
+                } else {
+                    "Read drives through smartctl"
+                }
+                .to_string(),
+                Message::ToggleExtra(Backend::Smartctl),
+            ),
+            (
+                if self.backends.contains(&Backend::Processes) {
+                    "Hide top processes"
+                } else {
+                    "Show top processes"
+                }
+                .to_string(),
+                Message::ToggleExtra(Backend::Processes),
+            ),
+            (
+                if self.backends.contains(&Backend::Upower) {
+                    "Stop reading power devices through UPower"
+                } else {
+                    "Read power devices through UPower"
+                }
+                .to_string(),
+                Message::ToggleExtra(Backend::Upower),
+            ),
+            (
+                if self.show_settings { "Close settings" } else { "Open settings" }.to_string(),
+                Message::ToggleSettings,
+            ),
+            (
+                if self.mini { "Leave mini mode" } else { "Mini mode (pinned sensors)" }.to_string(),
+                Message::ToggleMiniMode,
+            ),
+            ("Quit".to_string(), Message::Quit),
+        ];
+        let native = Backend::native();
+        if native != Backend::Hwmon {
+            commands.push((format!("Use {} backend", native.label()), Message::SwitchBackend(native)));
+        }
+        if !alerts(&self.sensor_data).is_empty() {
+            commands.push(("Jump to next alert".to_string(), Message::JumpToAlert));
+        }
+        if !self.fans.is_empty() {
+            commands.push((
+                if self.show_fans { "Close fan curves" } else { "Fan curves" }.to_string(),
+                Message::ToggleFans,
+            ));
+        }
+        if !self.cpu_policies.is_empty() {
+            commands.push((
+                if self.show_cpu_power { "Close CPU power" } else { "CPU power: governor, EPP and turbo" }.to_string(),
+                Message::ToggleCpuPower,
+            ));
+        }
+        if !self.config.fan_curves.is_empty() {
+            commands.push(("Export fan curves for fancontrol".to_string(), Message::ExportFancontrol));
+        }
+        if !self.fans.is_empty() {
+            for profile in &self.config.fan_profiles {
+                commands.push((
+                    format!("Fan profile: {}", profile.name),
+                    Message::SelectFanProfile(profile.name.clone()),
+                ));
+            }
+        }
+        if unparsed_count(&self.sensor_data) > 0 {
+            commands.push((
+                if self.show_diagnostics { "Close unparsed lines" } else { "Show unparsed lines" }.to_string(),
+                Message::ToggleDiagnostics,
+            ));
+            commands.push(("Copy unparsed lines report".to_string(), Message::CopyDiagnostics));
+        }
+        if self.config.headline.is_some() {
+            commands.push(("Clear window title sensor".to_string(), Message::SetHeadline(None)));
+        }
+        for section in &self.sensor_data {
+            commands.push((format!("Go to {}", section.name), Message::JumpToSection(section.name.clone())));
+            for entry in &section.entries {
+                commands.push((
+                    format!("Show in window title: {} {}", section.name, entry.key),
+                    Message::SetHeadline(Some((section.name.clone(), entry.key.clone()))),
+                ));
+                if entry.number.is_some() {
+                    let smoothed = self.config.smoothing_alpha(&section.name, &entry.key).is_some();
+                    let verb = if smoothed { "Stop smoothing" } else { "Smooth" };
+                    commands.push((
+                        format!("{}: {} {}", verb, section.name, entry.key),
+                        Message::ToggleSmoothing(section.name.clone(), entry.key.clone()),
+                    ));
+                }
+            }
+            commands.push((
+                format!("Collapse others than {}", section.name),
+                Message::CollapseOthers(section.name.clone()),
+            ));
+        }
+        commands
+    }
+
+    // Swaps between the full window and a small, undecorated, always-on-top one
+    // snapped to the configured corner.
+    fn set_mini(&mut self, mini: bool) -> Command<Message> {
+        use iced::window::{self, Id, Level};
+
+        self.mini = mini;
+        self.palette = None;
+        if mini {
+            return Command::batch([
+                window::toggle_decorations(Id::MAIN),
+                window::change_level(Id::MAIN, Level::AlwaysOnTop),
+                self.place_mini_window(),
+            ]);
+        }
+
+        let saved = self.ui_state.window;
+        let mut commands = vec![
+            window::toggle_decorations(Id::MAIN),
+            window::change_level(Id::MAIN, Level::Normal),
+            window::resize(Id::MAIN, iced::Size::new(saved.width, saved.height)),
+        ];
+        if let Some((x, y)) = saved.position {
+            commands.push(window::move_to(Id::MAIN, iced::Point::new(x as f32, y as f32)));
+        }
+        Command::batch(commands)
+    }
+
+    // Scrolls to the next reading in alarm after the one focused last, expanding its
+    // section and leaving the settings page if needed.
+    fn jump_to_alert(&mut self) -> Command<Message> {
+        let alerts = alerts(&self.sensor_data);
+        let current = self
+            .focused
+            .as_ref()
+            .and_then(|(focused, _)| alerts.iter().position(|alert| alert == focused));
+        let next = current.map_or(0, |i| (i + 1) % alerts.len().max(1));
+        let Some((section_name, key)) = alerts.get(next).cloned() else {
+            return Command::none();
+        };
+
+        let mut commands = Vec::new();
+        if self.mini {
+            commands.push(self.set_mini(false));
+        }
+        self.show_settings = false;
+        self.show_diagnostics = false;
+        self.show_fans = false;
+        self.show_cpu_power = false;
+        self.collapsed.remove(&section_name);
+
+        let offsets = section_offsets(&self.sensor_data, &self.collapsed, self.hide_disconnected, &self.raw_expanded);
+        let Some(position) = self.sensor_data.iter().position(|s| s.name == section_name) else {
+            return Command::none();
+        };
+        let row = visible_entries(&self.sensor_data[position], self.hide_disconnected)
+            .iter()
+            .position(|entry| entry.key == key)
+            .unwrap_or(0);
+        let (start, _) = offsets[position];
+        // Leave room above the row for the pinned section header.
+        let y = start + SECTION_PADDING + HEADER_HEIGHT + HEADER_ROWS_SPACING + row as f32 * (ROW_HEIGHT + ROW_SPACING)
+            - (HEADER_HEIGHT + 2.0 * SECTION_PADDING);
+
+        self.focused = Some(((section_name, key), Instant::now()));
+        commands.push(scrollable::scroll_to(
+            sections_scroll_id(),
+            scrollable::AbsoluteOffset { x: 0.0, y: y.max(0.0) },
+        ));
+        Command::batch(commands)
+    }
+
+    fn place_mini_window(&self) -> Command<Message> {
+        use iced::window::{self, Id};
+
+        if !self.mini {
+            return Command::none();
+        }
+        let size = mini::window_size(self.config.pinned.len());
+        let mut commands = vec![window::resize(Id::MAIN, size)];
+        if let Some(screen) = self.config.mini_screen.or_else(mini::detect_screen_size) {
+            commands.push(window::move_to(Id::MAIN, self.config.mini_corner.position(screen, size)));
+        }
+        Command::batch(commands)
+    }
+
+    fn headline_entry(&self) -> Option<&SensorEntry> {
+        let (section, key) = self.config.headline.as_ref()?;
+        find_entry(&self.sensor_data, section, key)
+    }
+
+    fn save_display(&mut self) {
+        let mut display = vec![
+            ("palette".to_string(), self.config.palette.config_name().to_string()),
+            ("sparklines".to_string(), self.config.sparklines.to_string()),
+        ];
+        if let Some((section, key)) = &self.config.headline {
+            display.push(("headline".to_string(), format!("{}/{}", section, key)));
+        }
+        let columns: Vec<&str> = self.config.limit_columns.iter().map(|column| column.config_name()).collect();
+        display.push((
+            "limit_columns".to_string(),
+            if columns.is_empty() { "none".to_string() } else { columns.join(", ") },
+        ));
+        if let Err(e) = config::save_section("display", &display) {
+            self.settings_notice = Some(e);
+        }
+    }
+
+    fn save_keymap(&mut self) {
+        if let Err(e) = config::save_section("keymap", &self.keymap.to_config()) {
+            self.settings_notice = Some(e);
+        }
+    }
+
+    fn palette_matches(&self, query: &str) -> Vec<(String, Message)> {
+        let commands = self.palette_commands();
+        palette::rank(query, commands.iter().map(|(label, _)| label.as_str()))
+            .into_iter()
+            .map(|i| commands[i].clone())
+            .collect()
+    }
+
+    // Writes `[sensors]` after the backend or the extras change, and reads again.
+    fn save_backends(&mut self) {
+        self.backends = active_backends(&self.config);
+        self.registry = Registry::new(self.backends.iter().map(|backend| backend.source(&self.config)).collect());
+        let mut pairs = vec![("backend".to_string(), self.config.backend.config_name().to_string())];
+        if let Some(extras) = &self.config.extra_backends {
+            let names: Vec<&str> = extras.iter().map(|extra| extra.config_name()).collect();
+            let names = if names.is_empty() { "none".to_string() } else { names.join(", ") };
+            pairs.push(("extra".to_string(), names));
+        }
+        if let Err(e) = config::save_section("sensors", &pairs) {
+            self.settings_notice = Some(e);
+        }
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        self.uptime = sysinfo::read_uptime();
+        let result = self.registry.poll();
+        self.apply(result);
+    }
+
+    fn apply(&mut self, result: ReadResult) {
+        match result {
+            Ok(mut data) => {
+                let config = &self.config;
+                self.smoother.apply(&mut data, |section, key| config.smoothing_alpha(section, key));
+                self.track_changes(&data);
+                self.record_history(&data);
+                self.sensor_data = data;
+                self.error = None;
+                self.error_dismissed = false;
+                self.drive_fans();
+                self.drive_lighting();
+            }
+            Err(e) => {
+                // A dismissed banner stays hidden until the error changes.
+                if self.error.as_ref() != Some(&e) {
+                    self.error_dismissed = false;
+                }
+                self.error = Some(e);
+            }
+        }
+
+        if self.sensor_data.is_empty() {
+            self.setup_info.get_or_insert_with(SetupInfo::gather);
+        } else {
+            self.setup_info = None;
+        }
+    }
+}
+
+impl SensorViewer {
+    fn read_cpu_power(&mut self) {
+        self.cpu_policies = cpufreq::read_policies();
+        self.turbo = cpufreq::read_turbo();
+    }
+
+    fn connect_rgb(&mut self) -> Result<(), String> {
+        if self.rgb.is_some() {
+            return Ok(());
+        }
+        let connected = openrgb::Client::connect(&self.config.openrgb_server)
+            .and_then(|mut client| client.controllers().map(|devices| (client, devices)));
+        match connected {
+            Ok((client, devices)) => {
+                self.rgb = Some(client);
+                self.rgb_devices = devices;
+                self.rgb_sent.clear();
+                Ok(())
+            }
+            Err(e) => {
+                self.rgb_retry = Some(Instant::now() + RGB_RETRY);
+                Err(e)
+            }
+        }
+    }
+
+    // Colors each device with an enabled rule from its temperature, sending only the
+    // colors that changed. Losing the server drops the connection until a retry.
+    fn drive_lighting(&mut self) {
+        let mut colors: Vec<(String, Rgb)> = self
+            .config
+            .lighting
+            .iter()
+            .filter(|(_, rule)| rule.enabled)
+            .filter_map(|(device, rule)| {
+                let temperature = find_entry(&self.sensor_data, &rule.source.0, &rule.source.1)?.number?;
+                Some((device.clone(), rule.color_at(temperature)))
+            })
+            .collect();
+        colors.retain(|(device, color)| self.rgb_sent.get(device) != Some(color));
+        if colors.is_empty() || self.rgb.is_none() && self.rgb_retry.is_some_and(|retry| Instant::now() < retry) {
+            return;
+        }
+
+        let sent = self.connect_rgb().and_then(|()| {
+            let client = self.rgb.as_mut().expect("connected");
+            for (device, color) in colors {
+                let controller = self
+                    .rgb_devices
+                    .iter()
+                    .find(|controller| controller.name == device)
+                    .ok_or_else(|| format!("OpenRGB has no device named {}", device))?;
+                client.set_color(controller, color)?;
+                self.rgb_sent.insert(device, color);
+            }
+            Ok(())
+        });
+        if let Err(e) = sent {
+            self.rgb = None;
+            self.rgb_retry = Some(Instant::now() + RGB_RETRY);
+            self.rgb_notice = Some(e);
+        }
+    }
+
+    // Sets every overridden fan to its manual duty and every fan with an enabled curve
+    // from its source temperature. A source that stopped reporting runs its fan at full
+    // duty rather than leaving it wherever it was.
+    fn drive_fans(&mut self) {
+        self.fans = fan::find_fans();
+        self.check_fan_safety();
+        self.check_profile_triggers();
+
+        let mut duties: Vec<(String, f64)> = self
+            .config
+            .fan_curves
+            .iter()
+            .filter(|(id, curve)| curve.enabled && !self.fan_overrides.contains_key(id))
+            .map(|(id, curve)| {
+                let duty = find_entry(&self.sensor_data, &curve.source.0, &curve.source.1)
+                    .and_then(|entry| entry.number)
+                    .map_or(100.0, |temperature| curve.duty_at(temperature));
+                (id.clone(), duty)
+            })
+            .collect();
+        duties.extend(self.fan_overrides.iter().map(|(id, duty)| (id.clone(), *duty)));
+
+        // Fans driven by a curve saved as enabled are taken over here on the first
+        // refresh, so they are handed back on exit like the ones set up this session.
+        for (id, result) in self.fan_takeover.drive(&self.fans, duties) {
+            match result {
+                Ok(()) => self.fan_notices.remove(&id),
+                Err(e) => self.fan_notices.insert(id, e),
+            };
+        }
+    }
+
+    // Ends overrides whose linked temperature reached the safety limit or stopped
+    // reporting. The fan goes back to its curve if it has one, else to the driver.
+    fn check_fan_safety(&mut self) {
+        let tripped: Vec<(String, String)> = self
+            .fan_overrides
+            .keys()
+            .filter_map(|id| {
+                let (section, key) = self.linked_temperature(id);
+                let Some(entry) = find_entry(&self.sensor_data, &section, &key).filter(|e| e.number.is_some()) else {
+                    return Some((id.clone(), format!("{} stopped reporting", key)));
+                };
+                let limit = fan_safety_limit(entry, self.config.fan_safety_limit);
+                let temperature = entry.number.unwrap_or_default();
+                (temperature >= limit).then(|| {
+                    let reason = format!("{} reached {} (limit {:.0}°C)", key, entry.display_reading(), limit);
+                    (id.clone(), reason)
+                })
+            })
+            .collect();
+
+        for (id, reason) in tripped {
+            self.fan_overrides.remove(&id);
+            let mut notice = format!("Manual override ended: {}", reason);
+            if !self.config.fan_curve(&id).is_some_and(|curve| curve.enabled) {
+                if let Err(e) = self.release_fan(&id) {
+                    notice = format!("{}; {}", notice, e);
+                }
+            }
+            self.fan_notices.insert(id, notice);
+        }
+    }
+
+    // Chosen and triggered profiles only change curves in memory; the config keeps
+    // the chosen profile's.
+    fn check_profile_triggers(&mut self) {
+        let triggered = fan::triggered_profile(&self.config.fan_profiles, self.triggered_profile.as_deref(), |source| {
+            find_entry(&self.sensor_data, &source.0, &source.1).and_then(|entry| entry.number)
+        })
+        .map(str::to_string);
+        if triggered == self.triggered_profile {
+            return;
+        }
+        self.triggered_profile = triggered;
+        if let Some(name) = self.active_fan_profile().map(str::to_string) {
+            self.apply_fan_profile(&name);
+        }
+    }
+
+    fn active_fan_profile(&self) -> Option<&str> {
+        self.triggered_profile.as_deref().or(self.config.fan_profile.as_deref())
+    }
+
+    // Copies the profile's points into the fans' curves; returns the fans it covers.
+    fn apply_fan_profile(&mut self, name: &str) -> Vec<String> {
+        let Some(profile) = self.config.fan_profiles.iter().find(|profile| profile.name == name).cloned() else {
+            return Vec::new();
+        };
+        for (id, points) in &profile.curves {
+            self.config.fan_curve_mut(id).points = points.clone();
+        }
+        profile.curves.into_iter().map(|(id, _)| id).collect()
+    }
+
+    fn fan_page(&self) -> FanPage<'_> {
+        FanPage {
+            fans: &self.fans,
+            config: &self.config,
+            drafts: &self.fan_drafts,
+            overrides: &self.fan_overrides,
+            notices: &self.fan_notices,
+            notice: self.fans_notice.as_deref(),
+            triggered_profile: self.triggered_profile.as_deref(),
+        }
+    }
+
+    // The temperature shown with a fan: its curve's source, edited or applied.
+    fn linked_temperature(&self, id: &str) -> (String, String) {
+        self.fan_drafts
+            .get(id)
+            .or(self.config.fan_curve(id))
+            .map(|curve| curve.source.clone())
+            .filter(|(section, _)| !section.is_empty())
+            .or_else(|| temperature_sources(&self.sensor_data).into_iter().next())
+            .unwrap_or_default()
+    }
+
+    // Remembers the fan's mode and arms its watchdog before the app first writes to it.
+    fn take_over_fan(&mut self, id: &str) {
+        let Some(fan) = self.fans.iter().find(|fan| fan.id == id) else {
+            return;
+        };
+        if let Err(e) = self.fan_takeover.take_over(fan) {
+            self.fan_notices.insert(id.to_string(), e);
+        }
+    }
+
+    // Hands the fan back to the mode it had before the app took it over.
+    fn release_fan(&mut self, id: &str) -> Result<(), String> {
+        self.fan_takeover.release(&self.fans, id)
+    }
+}
+
+// Everything besides the section itself that decides how its rows render.
+struct RowContext<'a> {
+    hide_disconnected: bool,
+    flashes: &'a HashMap<(String, String), Instant>,
+    flash_duration: Duration,
+    columns: ColumnWidths,
+    limit_columns: &'a [LimitColumn],
+    palette: StatusPalette,
+    pinned: &'a [(String, String)],
+    smoothing: &'a [((String, String), f64)],
+    // Set when sparklines are shown.
+    history: Option<&'a HashMap<(String, String), VecDeque<f64>>>,
+    focused: Option<&'a (String, String)>,
+    raw_expanded: &'a HashSet<String>,
+}
+
+impl SensorViewer {
+    fn row_context(&self) -> RowContext<'_> {
+        RowContext {
+            hide_disconnected: self.hide_disconnected,
+            flashes: &self.flashes,
+            flash_duration: self.config.flash_duration(),
+            columns: self.ui_state.columns,
+            limit_columns: &self.config.limit_columns,
+            palette: self.config.palette,
+            pinned: &self.config.pinned,
+            smoothing: &self.config.smoothing,
+            history: self.config.sparklines.then_some(&self.history),
+            focused: self
+                .focused
+                .as_ref()
+                .filter(|(_, at)| at.elapsed() < self.config.flash_duration())
+                .map(|(focused, _)| focused),
+            raw_expanded: &self.raw_expanded,
+        }
+    }
+
+    fn record_history(&mut self, data: &[SensorSection]) {
+        let mut history = HashMap::new();
+        for section in data {
+            // Averages over an interval would chart as smoother than the reading really is.
+            for entry in section.entries.iter().filter(|entry| entry.interval.is_none()) {
+                let Some(number) = entry.number else { continue };
+                let id = (section.name.clone(), entry.key.clone());
+                let mut samples = self.history.remove(&id).unwrap_or_default();
+                if samples.len() == SPARKLINE_SAMPLES {
+                    samples.pop_front();
+                }
+                samples.push_back(number);
+                history.insert(id, samples);
+            }
+        }
+        self.history = history;
+    }
+
+    fn track_changes(&mut self, data: &[SensorSection]) {
+        let now = Instant::now();
+        let duration = self.config.flash_duration();
+        self.flashes.retain(|_, changed| now.duration_since(*changed) < duration);
+
+        for section in data {
+            let Some(previous) = self.sensor_data.iter().find(|s| s.name == section.name) else {
+                continue;
+            };
+            for entry in &section.entries {
+                let old = previous
+                    .entries
+                    .iter()
+                    .find(|e| e.key == entry.key)
+                    .and_then(|e| e.number);
+                if let (Some(old), Some(new)) = (old, entry.number) {
+                    if (new - old).abs() > self.config.flash_delta {
+                        self.flashes.insert((section.name.clone(), entry.key.clone()), now);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// The colour of a reading and whether it gets the alarm icon: channels that could not be read are
+// dimmed, failed ones (FAULT, a dead fan) are shown like alarms so they get noticed.
+fn value_color(entry: &SensorEntry, palette: StatusPalette) -> (Color, bool) {
+    match entry.sensor_value() {
+        SensorValue::Faulty => (palette.alarm(), true),
+        SensorValue::Unavailable => (UNAVAILABLE_COLOR, false),
+        _ if entry.in_alarm() => (palette.alarm(), true),
+        _ => (palette.normal(), false),
+    }
+}
+
+// Limits not shown in a column of their own, plus the driver's flags since they are
+// hidden behind the alarm icon otherwise.
+fn entry_details(entry: &SensorEntry, limit_columns: &[LimitColumn]) -> String {
+    let flags = match (entry.alarm, entry.fault) {
+        _ if entry.status.is_some() => None,
+        (_, true) => Some("FAULT"),
+        (true, false) => Some("ALARM"),
+        _ => None,
+    };
+    let in_column = |part: &str| {
+        part.split_once('=').is_some_and(|(name, _)| {
+            limit_columns.iter().any(|column| column.limit_names().contains(&name.trim()))
+        })
+    };
+    let info = entry.additional_info.as_ref().map(|info| {
+        info.split(", ").filter(|part| !in_column(part)).collect::<Vec<_>>().join(", ")
+    });
+    match (flags, info.filter(|info| !info.is_empty())) {
+        (Some(flags), Some(info)) => format!("{} · {}", flags, info),
+        (Some(flags), None) => flags.to_string(),
+        (None, info) => info.unwrap_or_default(),
+    }
+}
+
+// A limit in the reading's unit, or blank where the entry has none.
+fn limit_cell(entry: &SensorEntry, column: LimitColumn, color: Color) -> Element<'static, Message> {
+    let limit = column
+        .value(&entry.limits)
+        .map(|limit| format!("{:.*}", model::unit_decimals(&entry.unit), limit))
+        .unwrap_or_default();
+    text(limit)
+        .style(color)
+        .width(Length::Fixed(LIMIT_COLUMN_WIDTH))
+        .horizontal_alignment(iced::alignment::Horizontal::Right)
+        .into()
+}
+
+// A switch-like channel's state as a colored chip.
+fn status_chip(status: Status, palette: StatusPalette) -> Element<'static, Message> {
+    let (label, color) = match status {
+        Status::Alarm | Status::Warning => (format!("{} {}", colors::ALARM_ICON, status.label()), palette.alarm()),
+        Status::Ok | Status::Enabled => (status.label().to_string(), palette.normal()),
+        Status::Disabled => (status.label().to_string(), UNAVAILABLE_COLOR),
+    };
+    container(text(label).size(12).style(BACKGROUND_COLOR))
+        .padding([1, 6])
+        .style(iced::theme::Container::Custom(Box::new(BadgeStyle(color))))
+        .into()
+}
+
+fn visible_entries(section: &SensorSection, hide_disconnected: bool) -> Vec<&SensorEntry> {
+    section
+        .entries
+        .iter()
+        .filter(|entry| !(hide_disconnected && entry.is_disconnected()))
+        .collect()
+}
+
+// Sections from every backend that succeeded; an error only if all of them failed.
+// The chosen backend, then the extras whose hardware is present.
+fn active_backends(config: &Config) -> Vec<Backend> {
+    let extras = config.extra_backends.as_deref().unwrap_or(&Backend::EXTRAS);
+    let extras = extras.iter().copied().filter(|extra| extra.available(config));
+    // Sections are shown in read order, and the load average goes on top.
+    let (top, rest): (Vec<Backend>, Vec<Backend>) = extras.partition(|extra| *extra == Backend::LoadAverage);
+    top.into_iter().chain(std::iter::once(config.backend)).chain(rest).collect()
+}
+
+fn startup_view(
+    startup: &[(Backend, Option<ReadResult>)],
+) -> Element<'static, Message> {
+    let mut backends = Column::new().spacing(5);
+    for (backend, slot) in startup {
+        let (status, color) = match slot {
+            None => ("probing…".to_string(), TEXT_COLOR),
+            Some(Ok(data)) => (format!("{} sections", data.len()), Color::from_rgb(0.4, 0.8, 0.4)),
+            Some(Err(e)) => (format!("failed: {}", e), ERROR_COLOR),
+        };
+        backends = backends.push(
+            row![
+                text(backend.label()).style(TEXT_COLOR).width(Length::Fixed(150.0)),
+                text(status).style(color),
+            ]
+            .spacing(20),
+        );
+    }
+
+    column![
+        text("Detecting sensors…")
+            .size(HEADER_FONT_SIZE)
+            .style(HEADER_COLOR),
+        backends,
+    ]
+    .spacing(20)
+    .into()
+}
+
+fn empty_state(
+    info: &SetupInfo,
+    backends: &[Backend],
+    missing: &[MissingDriver],
+    notice: Option<&str>,
+    show_troubleshooting: bool,
+) -> Element<'static, Message> {
+    let current = backends
+        .iter()
+        .map(|backend| backend.label())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let modules = if info.loaded_modules.is_empty() {
+        "none detected".to_string()
+    } else {
+        info.loaded_modules.join(", ")
+    };
+    let sensors_detect = match &info.sensors_detect_config {
+        Some(path) => format!("has been run (found {})", path),
+        None => "no configuration found; it has probably not been run".to_string(),
+    };
+
+    let alternative = if backends.contains(&Backend::Hwmon) {
+        button("Use lm-sensors").on_press(Message::SwitchBackend(Backend::LmSensors))
+    } else {
+        button("Use hwmon backend").on_press(Message::SwitchBackend(Backend::Hwmon))
+    };
+
+    let mut body = column![
+        text("No sensor data found")
+            .size(HEADER_FONT_SIZE)
+            .style(HEADER_COLOR),
+        text(format!("Backend: {}", current)).style(TEXT_COLOR),
+        text(format!("Loaded sensor modules: {}", modules)).style(TEXT_COLOR),
+        text(format!("sensors-detect: {}", sensors_detect)).style(TEXT_COLOR),
+        row![
+            button("Detect sensor chips…").on_press(Message::OpenDetect),
+            alternative,
+            button(if show_troubleshooting {
+                "Hide troubleshooting"
+            } else {
+                "Troubleshooting steps"
+            })
+            .style(iced::theme::Button::Secondary)
+            .on_press(Message::ToggleTroubleshooting),
+        ]
+        .spacing(10),
+    ]
+    .spacing(10);
+
+    for missing in missing {
+        body = body.push(
+            row![
+                text(format!("{} looks missing: {}", missing.module, missing.reason))
+                    .style(TEXT_COLOR)
+                    .width(Length::Fill),
+                button(text(format!("Load {}", missing.module)))
+                    .on_press(Message::LoadModules(vec![missing.module.to_string()])),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center),
+        );
+    }
+    body = body.push_maybe(notice.map(|notice| text(notice).style(TEXT_COLOR)));
+
+    if show_troubleshooting {
+        for (i, step) in setup::TROUBLESHOOTING_STEPS.iter().enumerate() {
+            body = body.push(text(format!("{}. {}", i + 1, step)).style(TEXT_COLOR));
+        }
+    }
+
+    container(body)
+        .padding(SECTION_PADDING)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(SectionContainerStyle)))
+        .into()
+}
+
+fn detect_view(step: &DetectStep, notice: Option<&str>) -> Element<'static, Message> {
+    let paragraph = |line: &str| text(line.to_string()).style(TEXT_COLOR);
+    let close = button("Close").style(iced::theme::Button::Secondary).on_press(Message::CloseDetect);
+
+    let mut body = column![text("Detect sensor chips").size(HEADER_FONT_SIZE).style(HEADER_COLOR)].spacing(10);
+    body = match step {
+        DetectStep::Consent => body
+            .push(paragraph(
+                "sensors-detect probes this machine for sensor chips and names the kernel drivers they need. \
+                 It runs as root, so you will be asked to authorize it.",
+            ))
+            .push(paragraph(
+                "Every question gets its default answer: the safe probes run, the riskier ISA and I2C bus \
+                 scans are skipped, and no file is changed.",
+            ))
+            .push(paragraph("Probing has been known to upset rare hardware, so save any open work first."))
+            .push(row![button("Run sensors-detect").on_press(Message::RunDetect), close].spacing(10)),
+        DetectStep::Running => body.push(paragraph("Running sensors-detect…")),
+        DetectStep::Failed(error) => body
+            .push(text(error).style(ERROR_COLOR))
+            .push(row![button("Try again").on_press(Message::RunDetect), close].spacing(10)),
+        DetectStep::Done(detection) if detection.drivers.is_empty() => body
+            .push(paragraph(
+                "No sensor chips were found. Virtual machines and containers usually have none; on real \
+                 hardware the chip may need a newer kernel or an out-of-tree driver.",
+            ))
+            .push(close),
+        DetectStep::Done(detection) => {
+            for driver in &detection.drivers {
+                let how = if driver.autoloaded { " (loads automatically)" } else { "" };
+                body = body.push(paragraph(&format!("{}{}: {}", driver.name, how, driver.chips.join(", "))));
+            }
+            if detection.modules.is_empty() {
+                body.push(paragraph("Every driver loads automatically; there is nothing to set up.")).push(close)
+            } else {
+                body.push(paragraph(&format!("Recommended modules: {}", detection.modules.join(", "))))
+                    .push(
+                        row![
+                            button("Load now").on_press(Message::LoadModules(detection.modules.clone())),
+                            button("Load at every boot")
+                                .style(iced::theme::Button::Secondary)
+                                .on_press(Message::SaveBootModules(detection.modules.clone())),
+                            close,
+                        ]
+                        .spacing(10),
+                    )
+            }
+        }
+    };
+
+    container(body.push_maybe(notice.map(|notice| text(notice).style(TEXT_COLOR))))
+        .padding(SECTION_PADDING)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(SectionContainerStyle)))
+        .into()
+}
+
+// (section, entry) of every reading past its limits, in display order.
+fn alerts(sections: &[SensorSection]) -> Vec<(String, String)> {
+    sections
+        .iter()
+        .flat_map(|section| {
+            section
+                .entries
+                .iter()
+                .filter(|entry| entry.in_alarm())
+                .map(|entry| (section.name.clone(), entry.key.clone()))
+        })
+        .collect()
+}
+
+fn alert_banner(alerts: &[(String, String)], palette: StatusPalette) -> Element<'static, Message> {
+    let (section, key) = &alerts[0];
+    let summary = match alerts.len() {
+        1 => format!("{} {} {} is past its limit", colors::ALARM_ICON, section, key),
+        n => format!("{} {} readings are past their limits, starting with {} {}", colors::ALARM_ICON, n, section, key),
+    };
+    button(text(summary).style(palette.alarm()))
+        .width(Length::Fill)
+        .padding([10.0, CONTENT_PADDING])
+        .style(iced::theme::Button::Text)
+        .on_press(Message::JumpToAlert)
+        .into()
+}
+
+fn missing_drivers_banner(missing: &[MissingDriver], notice: Option<&str>) -> Element<'static, Message> {
+    let mut bar = row![text(match notice {
+        Some(notice) => notice.to_string(),
+        None => missing
+            .iter()
+            .map(|missing| format!("{} looks missing ({})", missing.module, missing.reason))
+            .collect::<Vec<_>>()
+            .join("; "),
+    })
+    .style(TEXT_COLOR)
+    .width(Length::Fill)]
+    .spacing(10)
+    .align_items(Alignment::Center);
+    for missing in missing {
+        bar = bar.push(
+            button(text(format!("Load {}", missing.module)))
+                .on_press(Message::LoadModules(vec![missing.module.to_string()])),
+        );
+    }
+    container(bar.push(
+        button("Dismiss")
+            .style(iced::theme::Button::Secondary)
+            .on_press(Message::DismissMissingDrivers),
+    ))
+    .padding([10.0, CONTENT_PADDING])
+    .width(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(RowStyle(ROW_ALT_COLOR))))
+    .into()
+}
+
+// Strict parsing: the toolbar's unparsed count as a banner, for reporting parser bugs.
+fn unparsed_banner(count: usize) -> Element<'static, Message> {
+    let message = if count == 1 {
+        "1 line could not be parsed".to_string()
+    } else {
+        format!("{} lines could not be parsed", count)
+    };
+    container(
+        row![
+            text(message).style(TEXT_COLOR).width(Length::Fill),
+            button("View").on_press(Message::ToggleDiagnostics),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    )
+    .padding([10.0, CONTENT_PADDING])
+    .width(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(RowStyle(ROW_ALT_COLOR))))
+    .into()
+}
+
+fn error_banner(error: &str) -> Element<'static, Message> {
+    container(
+        row![
+            text(format!("Error: {}", error)).style(ERROR_COLOR).width(Length::Fill),
+            button("Retry").on_press(Message::Retry),
+            button("Dismiss")
+                .style(iced::theme::Button::Secondary)
+                .on_press(Message::DismissError),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    )
+    .padding([10.0, CONTENT_PADDING])
+    .width(Length::Fill)
+    .style(iced::theme::Container::Custom(Box::new(ErrorBannerStyle)))
+    .into()
+}
+
+fn action_message(action: Action) -> Message {
+    match action {
+        Action::CommandPalette => Message::OpenPalette,
+        Action::CollapseAll => Message::CollapseAll,
+        Action::ExpandAll => Message::ExpandAll,
+        Action::TogglePause => Message::TogglePause,
+        Action::ToggleSidebar => Message::ToggleSidebar,
+        Action::ToggleMiniMode => Message::ToggleMiniMode,
+        Action::JumpToAlert => Message::JumpToAlert,
+        Action::CycleFanProfile => Message::CycleFanProfile,
+        Action::Refresh => Message::Retry,
+        Action::Quit => Message::Quit,
+    }
+}
+
+fn settings_view(
+    keymap: &Keymap,
+    capturing: Option<Action>,
+    notice: Option<&str>,
+    config: &Config,
+    lighting: Element<'static, Message>,
+) -> Element<'static, Message> {
+    let palette = config.palette;
+    let mut shortcuts = Column::new().spacing(ROW_SPACING);
+    for (i, action) in Action::ALL.into_iter().enumerate() {
+        let binding = if capturing == Some(action) {
+            "press a key… (Esc cancels)".to_string()
+        } else {
+            keymap.binding(action).map_or("disabled".to_string(), |combo| combo.to_string())
+        };
+        let row_color = if i % 2 == 0 { BACKGROUND_COLOR } else { ROW_ALT_COLOR };
+
+        shortcuts = shortcuts.push(
+            container(
+                row![
+                    text(action.label()).style(TEXT_COLOR).width(Length::Fixed(220.0)),
+                    text(binding).style(Color::from_rgb(0.4, 0.8, 0.4)).width(Length::Fill),
+                    small_button("Rebind").on_press(Message::CaptureShortcut(action)),
+                    small_button("Disable").on_press(Message::ClearShortcut(action)),
+                ]
+                .spacing(COLUMN_SPACING)
+                .align_items(Alignment::Center),
+            )
+            .style(iced::theme::Container::Custom(Box::new(RowStyle(row_color))))
+            .padding([0.0, ROW_PADDING])
+            .width(Length::Fill)
+            .height(Length::Fixed(ROW_HEIGHT))
+            .center_y(),
+        );
+    }
+
+    let display = row![
+        text("Status colors").style(TEXT_COLOR).width(Length::Fixed(220.0)),
+        pick_list(&StatusPalette::ALL[..], Some(palette), Message::SelectPalette),
+        text("●").style(palette.normal()),
+        text("normal").style(TEXT_COLOR),
+        text(colors::ALARM_ICON).style(palette.alarm()),
+        text("alarm").style(TEXT_COLOR),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+
+    let limit_columns = row![text("Limit columns").style(TEXT_COLOR).width(Length::Fixed(220.0))]
+        .extend(LimitColumn::ALL.into_iter().map(|column| {
+            checkbox(column.title(), config.limit_columns.contains(&column))
+                .on_toggle(move |show| Message::ShowLimitColumn(column, show))
+                .text_size(14)
+                .into()
+        }))
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+    let sparklines = row![
+        text("Sparklines").style(TEXT_COLOR).width(Length::Fixed(220.0)),
+        checkbox("Chart the last 60 samples next to each reading", config.sparklines)
+            .on_toggle(Message::ShowSparklines)
+            .text_size(14),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+
+    let headline = match &config.headline {
+        Some((section, key)) => format!("{} {}", section, key),
+        None => "none (pick one from the command palette)".to_string(),
+    };
+    let headline = row![
+        text("Window title sensor").style(TEXT_COLOR).width(Length::Fixed(220.0)),
+        text(headline).style(TEXT_COLOR).width(Length::Fill),
+    ]
+    .push_maybe(
+        config
+            .headline
+            .is_some()
+            .then(|| small_button("Clear").on_press(Message::SetHeadline(None))),
+    )
+    .spacing(10)
+    .align_items(Alignment::Center);
+
+    let header = row![
+        text("Keyboard shortcuts").size(HEADER_FONT_SIZE).style(HEADER_COLOR),
+        Space::with_width(Length::Fill),
+        small_button("Reset to defaults").on_press(Message::ResetShortcuts),
+        small_button("Close").on_press(Message::ToggleSettings),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+
+    let notice = notice.map(|notice| text(notice).style(Color::from_rgb(0.8, 0.8, 0.4)));
+
+    container(
+        column![
+            text("Display").size(HEADER_FONT_SIZE).style(HEADER_COLOR),
+            display,
+            limit_columns,
+            sparklines,
+            headline,
+            lighting,
+            header
+        ]
+            .push_maybe(notice)
+            .push(shortcuts)
+            .spacing(HEADER_ROWS_SPACING),
+    )
+        .padding(SECTION_PADDING)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(SectionContainerStyle)))
+        .into()
+}
 
-const HEADER_COLOR: Color = Color::from_rgb(0.53, 0.81, 0.92);
-const TEXT_COLOR: Color = Color::from_rgb(0.9, 0.9, 0.9);
-const BACKGROUND_COLOR: Color = Color::from_rgb(0.1, 0.1, 0.1);
-const ROW_ALT_COLOR: Color = Color::from_rgb(0.15, 0.15, 0.15);
-const ERROR_COLOR: Color = Color::from_rgb(0.8, 0.2, 0.2);
+// Governor and EPP per cpufreq policy, with an "All CPUs" row offering what the first
+// policy offers (policies of one machine almost always match).
+fn cpu_power_view(policies: &[CpuPolicy], turbo: Option<&Turbo>, notice: Option<&str>) -> Element<'static, Message> {
+    let header = row![
+        text("CPU power").size(HEADER_FONT_SIZE).style(HEADER_COLOR),
+        Space::with_width(Length::Fill),
+        small_button("Close").on_press(Message::ToggleCpuPower),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
 
-// Font sizes (converted to u16)-(Also remember to add Body)
-const HEADER_FONT_SIZE: u16 = 18;
+    let mut body = column![header].spacing(HEADER_ROWS_SPACING);
+    if let Some(turbo) = turbo {
+        body = body.push(checkbox("Turbo boost", turbo.enabled).on_toggle(Message::SetTurbo).text_size(14));
+    }
 
-fn main() -> iced::Result {
-    SensorViewer::run(Settings {
-        window: iced::window::Settings {
-            size: iced::Size::new(700.0, 900.0), // Use iced::Size::new for the window size
-            resizable: true,  // You can toggle whether the window should be resizable
-            ..Default::default()
+    let label = |label: String| text(label).size(14).style(TEXT_COLOR).width(Length::Fixed(160.0));
+    let row_of = |i: usize, cells: iced::widget::Row<'static, Message>| {
+        let row_color = if i.is_multiple_of(2) { BACKGROUND_COLOR } else { ROW_ALT_COLOR };
+        container(cells.spacing(COLUMN_SPACING).align_items(Alignment::Center))
+            .style(iced::theme::Container::Custom(Box::new(RowStyle(row_color))))
+            .padding([4.0, ROW_PADDING])
+            .width(Length::Fill)
+    };
+
+    if let Some(first) = policies.first().filter(|_| policies.len() > 1) {
+        let governor = policies
+            .iter()
+            .all(|policy| policy.governor == first.governor)
+            .then(|| first.governor.clone())
+            .flatten();
+        let epp = policies.iter().all(|policy| policy.epp == first.epp).then(|| first.epp.clone()).flatten();
+        let mut cells = row![
+            label("All CPUs".to_string()),
+            Space::with_width(Length::Fixed(120.0)),
+            pick_list(first.governors.clone(), governor, |governor| Message::SetGovernor(None, governor))
+                .text_size(14)
+                .width(Length::Fixed(220.0)),
+        ];
+        if first.epp.is_some() {
+            cells = cells.push(
+                pick_list(first.epps.clone(), epp, |epp| Message::SetEpp(None, epp))
+                    .text_size(14)
+                    .width(Length::Fixed(220.0)),
+            );
+        }
+        body = body.push(row_of(1, cells));
+    }
+
+    for (i, policy) in policies.iter().enumerate() {
+        let id = policy.id;
+        let governors = pick_list(policy.governors.clone(), policy.governor.clone(), move |governor| {
+            Message::SetGovernor(Some(id), governor)
+        })
+        .text_size(14)
+        .width(Length::Fixed(220.0));
+        let mut cells = row![
+            label(format!("CPU {}", cpufreq::cpu_ranges(&policy.cpus))),
+            text(policy.driver.clone().unwrap_or_default())
+                .size(14)
+                .style(UNAVAILABLE_COLOR)
+                .width(Length::Fixed(120.0)),
+            governors,
+        ];
+        if policy.epp.is_some() {
+            cells = cells.push(
+                pick_list(policy.epps.clone(), policy.epp.clone(), move |epp| Message::SetEpp(Some(id), epp))
+                    .text_size(14)
+                    .width(Length::Fixed(220.0)),
+            );
+        }
+        body = body.push(row_of(i, cells));
+    }
+
+    container(body.push_maybe(notice.map(|notice| text(notice).style(ERROR_COLOR))))
+        .padding(SECTION_PADDING)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(SectionContainerStyle)))
+        .into()
+}
+
+// One row per OpenRGB device, and per configured device OpenRGB did not list.
+fn lighting_view(viewer: &SensorViewer) -> Element<'static, Message> {
+    let mut devices: Vec<String> = viewer.rgb_devices.iter().map(|device| device.name.clone()).collect();
+    for (device, _) in &viewer.config.lighting {
+        if !devices.contains(device) {
+            devices.push(device.clone());
+        }
+    }
+    let sources: Vec<String> = temperature_sources(&viewer.sensor_data)
+        .into_iter()
+        .map(|(section, key)| format!("{}/{}", section, key))
+        .collect();
+
+    let mut body = column![row![
+        text("Lighting (OpenRGB)").size(HEADER_FONT_SIZE).style(HEADER_COLOR),
+        text(&viewer.config.openrgb_server).size(14).style(UNAVAILABLE_COLOR),
+        Space::with_width(Length::Fill),
+        small_button("Find devices").on_press(Message::FindRgbDevices),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center)]
+    .spacing(ROW_SPACING);
+    if devices.is_empty() {
+        body = body.push(
+            text("Start the OpenRGB server (openrgb --server), then find its devices.").size(14).style(TEXT_COLOR),
+        );
+    }
+
+    let default_rule = openrgb::LightingRule::default();
+    for device in devices {
+        let rule = viewer
+            .config
+            .lighting
+            .iter()
+            .find(|(name, _)| *name == device)
+            .map_or(&default_rule, |(_, rule)| rule);
+        let source = format!("{}/{}", rule.source.0, rule.source.1);
+        let colors = viewer.rgb_drafts.get(&device).cloned().unwrap_or_else(|| rule.format_stops());
+        let swatch = viewer.rgb_sent.get(&device).map(|&Rgb(r, g, b)| text("●").style(Color::from_rgb8(r, g, b)));
+        let (on_source, on_colors, on_enabled) = (device.clone(), device.clone(), device.clone());
+        body = body.push(
+            row![text(&device).size(14).style(TEXT_COLOR).width(Length::Fixed(220.0))]
+                .push_maybe(swatch)
+                .push(
+                    pick_list(sources.clone(), sources.contains(&source).then_some(source), move |label| {
+                        Message::EditLighting(on_source.clone(), LightingEdit::Source(label))
+                    })
+                    .placeholder("Temperature")
+                    .text_size(14),
+                )
+                .push(
+                    text_input("40:#0080ff, 80:#ff0000", &colors)
+                        .on_input(move |text| Message::EditLighting(on_colors.clone(), LightingEdit::Colors(text)))
+                        .size(14)
+                        .width(Length::Fill),
+                )
+                .push(
+                    checkbox("Enabled", rule.enabled)
+                        .on_toggle(move |on| Message::EditLighting(on_enabled.clone(), LightingEdit::Enabled(on)))
+                        .text_size(14),
+                )
+                .push(small_button("Save").on_press(Message::SaveLighting(device)))
+                .spacing(10)
+                .align_items(Alignment::Center),
+        );
+    }
+    body.push_maybe(viewer.rgb_notice.as_deref().map(|notice| text(notice).size(14).style(TEXT_COLOR)))
+        .into()
+}
+
+fn find_entry<'a>(sections: &'a [SensorSection], section: &str, key: &str) -> Option<&'a SensorEntry> {
+    sections.iter().find(|s| s.name == section)?.entries.iter().find(|e| e.key == key)
+}
+
+// Every temperature reading a fan curve can follow.
+fn temperature_sources(sections: &[SensorSection]) -> Vec<(String, String)> {
+    sections
+        .iter()
+        .flat_map(|section| {
+            section
+                .entries
+                .iter()
+                .filter(|entry| entry.unit == model::Unit::Celsius && entry.number.is_some())
+                .map(|entry| (section.name.clone(), entry.key.clone()))
+        })
+        .collect()
+}
+
+// The configured limit, or the sensor's own high (or critical) limit when lower.
+fn fan_safety_limit(entry: &SensorEntry, configured: f64) -> f64 {
+    entry
+        .limits
+        .high
+        .or(entry.limits.crit)
+        .filter(|&limit| limit > 0.0)
+        .map_or(configured, |limit| limit.min(configured))
+}
+
+fn edit_curve(curve: &mut FanCurve, edit: CurveEdit) {
+    match edit {
+        CurveEdit::Source(label) => {
+            if let Some(source) = config::sensor_path(&label) {
+                curve.source = source;
+            }
+        }
+        CurveEdit::Duty(i, duty) => {
+            if let Some(point) = curve.points.get_mut(i) {
+                point.1 = duty.clamp(0.0, 100.0);
+            }
+        }
+        // Points keep their order: a point can not be moved past its neighbours.
+        CurveEdit::Shift(i, delta) => {
+            let low = i.checked_sub(1).and_then(|j| curve.points.get(j)).map_or(0.0, |p| p.0 + 1.0);
+            let high = curve.points.get(i + 1).map_or(120.0, |p| p.0 - 1.0);
+            if let Some(point) = curve.points.get_mut(i) {
+                point.0 = (point.0 + delta).clamp(low, high.max(low));
+            }
+        }
+        CurveEdit::AddPoint => {
+            let last = curve.points.last().copied().unwrap_or((30.0, 30.0));
+            curve.points.push(((last.0 + 10.0).min(120.0), 100.0));
+            curve.sort();
+        }
+        CurveEdit::RemovePoint(i) => {
+            if curve.points.len() > 2 && i < curve.points.len() {
+                curve.points.remove(i);
+            }
+        }
+        CurveEdit::ZeroRpm(threshold) => {
+            curve.zero_rpm = threshold.map(|threshold| threshold.clamp(0.0, 120.0));
+        }
+    }
+}
+
+// The fan control state the fans page renders.
+struct FanPage<'a> {
+    fans: &'a [PwmFan],
+    config: &'a Config,
+    drafts: &'a HashMap<String, FanCurve>,
+    overrides: &'a HashMap<String, f64>,
+    notices: &'a HashMap<String, String>,
+    notice: Option<&'a str>,
+    triggered_profile: Option<&'a str>,
+}
+
+fn fan_profiles_row(config: &Config, triggered: Option<&str>) -> Element<'static, Message> {
+    let names: Vec<String> = config.fan_profiles.iter().map(|profile| profile.name.clone()).collect();
+    let active = triggered.or(config.fan_profile.as_deref());
+    let status = match triggered.and_then(|name| config.fan_profiles.iter().find(|p| p.name == name)) {
+        Some(profile) => {
+            let (source, limit) = profile.trigger.clone().unwrap_or_default();
+            format!("{} is active: {} reached {:.0}°C", profile.name, source.1, limit)
+        }
+        None => match active.and_then(|name| config.fan_profiles.iter().find(|p| p.name == name)) {
+            Some(profile) if profile.curves.is_empty() => "No curves saved in this profile yet".to_string(),
+            _ => String::new(),
         },
-        ..Default::default()
-    })
+    };
+    row![
+        text("Profile").size(14).style(TEXT_COLOR),
+        pick_list(names, config.fan_profile.clone(), Message::SelectFanProfile).text_size(14),
+        small_button("Save curves to profile").on_press_maybe(active.map(|_| Message::SaveFanProfile)),
+        text(status).size(14).style(UNAVAILABLE_COLOR),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center)
+    .into()
 }
 
-#[derive(Debug, Clone)]
-enum Message {
-    Refresh,
+fn fans_view(page: &FanPage, sections: &[SensorSection]) -> Element<'static, Message> {
+    let FanPage { fans, config, drafts, overrides, notices, notice: export_notice, triggered_profile } = *page;
+    let sources: Vec<String> = temperature_sources(sections)
+        .into_iter()
+        .map(|(section, key)| format!("{}/{}", section, key))
+        .collect();
+    // Applied curves only; an unsaved draft is not part of the setup yet.
+    let export = small_button("Export for fancontrol")
+        .on_press_maybe((!config.fan_curves.is_empty()).then_some(Message::ExportFancontrol));
+    let mut page = column![
+        row![
+            text("Fan curves").size(HEADER_FONT_SIZE).style(HEADER_COLOR),
+            Space::with_width(Length::Fill),
+            export,
+        ]
+        .align_items(Alignment::Center),
+        text("Drag a point to set the duty cycle the fan runs at that temperature. Changes take effect when applied. Fans that need root are written through sensory-helper, which asks for authorization once per session.")
+            .size(14)
+            .style(TEXT_COLOR),
+    ]
+    .push(fan_profiles_row(config, triggered_profile))
+    .push_maybe(export_notice.map(|notice| text(notice).size(14).style(TEXT_COLOR)))
+    .spacing(SECTION_SPACING);
+
+    for fan in fans {
+        let applied = config.fan_curve(&fan.id);
+        let draft = drafts.get(&fan.id);
+        let active = applied.is_some_and(|curve| curve.enabled);
+        let curve = draft.or(applied).cloned().unwrap_or_else(|| FanCurve {
+            source: temperature_sources(sections).into_iter().next().unwrap_or_default(),
+            ..FanCurve::default()
+        });
+
+        let overridden = overrides.get(&fan.id).copied();
+        let mode = if overridden.is_some() {
+            "manual override".to_string()
+        } else if active {
+            "following curve".to_string()
+        } else if fan.is_manual() {
+            "manual".to_string()
+        } else {
+            "automatic".to_string()
+        };
+        let speed = fan
+            .tach
+            .as_ref()
+            .and_then(|tach| find_entry(sections, fan.section(), tach))
+            .map(|entry| format!(", {}", entry.display_reading()))
+            .unwrap_or_default();
+        let duty = fan.duty_percent().map(|duty| format!("{:.0}%", duty)).unwrap_or("N/A".to_string());
+        let header = row![
+            text(&fan.label).size(HEADER_FONT_SIZE).style(HEADER_COLOR),
+        ]
+        .push_maybe(fan.gpu.then(|| {
+            container(text("GPU").size(12).style(BACKGROUND_COLOR))
+                .padding([1, 6])
+                .style(iced::theme::Container::Custom(Box::new(BadgeStyle(HEADER_COLOR))))
+        }))
+        .push(text(&fan.id).size(12).style(UNAVAILABLE_COLOR))
+        .push(Space::with_width(Length::Fill))
+        .push(text(format!("{} — {}{}", mode, duty, speed)).size(14).style(TEXT_COLOR))
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        // Stopping below a temperature; GPUs do this on their own, case fans can too.
+        let zero_rpm = {
+            let id = fan.id.clone();
+            let default = curve.points.first().map_or(40.0, |point| point.0);
+            let edit = |threshold: Option<f64>| Message::EditCurve(fan.id.clone(), CurveEdit::ZeroRpm(threshold));
+            row![checkbox("Stop the fan below", curve.zero_rpm.is_some())
+                .on_toggle(move |on| Message::EditCurve(id.clone(), CurveEdit::ZeroRpm(on.then_some(default))))
+                .text_size(14)]
+            .push_maybe(curve.zero_rpm.map(|threshold| {
+                row![
+                    small_button("−").on_press(edit(Some(threshold - 1.0))),
+                    text(format!("{:.0}°C", threshold)).size(14).style(TEXT_COLOR),
+                    small_button("+").on_press(edit(Some(threshold + 1.0))),
+                ]
+                .spacing(6)
+                .align_items(Alignment::Center)
+            }))
+            .spacing(10)
+            .align_items(Alignment::Center)
+        };
+
+        let id = fan.id.clone();
+        let source_label = format!("{}/{}", curve.source.0, curve.source.1);
+        let source = row![
+            text("Temperature").size(14).style(TEXT_COLOR),
+            pick_list(
+                sources.clone(),
+                sources.contains(&source_label).then_some(source_label),
+                move |label| Message::EditCurve(id.clone(), CurveEdit::Source(label)),
+            )
+            .text_size(14),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        let mut points = row![].spacing(COLUMN_SPACING).align_items(Alignment::Center);
+        for (i, &(temperature, duty)) in curve.points.iter().enumerate() {
+            let id = fan.id.clone();
+            let shift = |delta: f64| Message::EditCurve(fan.id.clone(), CurveEdit::Shift(i, delta));
+            points = points.push(
+                column![
+                    text(format!("{:.0}%", duty)).size(14).style(TEXT_COLOR),
+                    vertical_slider(0.0..=100.0, duty, move |duty| Message::EditCurve(
+                        id.clone(),
+                        CurveEdit::Duty(i, duty)
+                    ))
+                    .step(1.0)
+                    .height(Length::Fixed(160.0)),
+                    text(format!("{:.0}°C", temperature)).size(14).style(TEXT_COLOR),
+                    row![small_button("−").on_press(shift(-5.0)), small_button("+").on_press(shift(5.0))].spacing(4),
+                    small_button("Remove").on_press_maybe(
+                        (curve.points.len() > 2).then(|| Message::EditCurve(fan.id.clone(), CurveEdit::RemovePoint(i)))
+                    ),
+                ]
+                .spacing(6)
+                .align_items(Alignment::Center),
+            );
+        }
+        points = points.push(small_button("Add point").on_press(Message::EditCurve(fan.id.clone(), CurveEdit::AddPoint)));
+
+        // What the curve on screen, applied or not, would set right now.
+        let preview = find_entry(sections, &curve.source.0, &curve.source.1)
+            .and_then(|entry| entry.number.map(|number| (entry, number)))
+            .map(|(entry, temperature)| {
+                let duty = curve.duty_at(temperature);
+                row![
+                    text(format!("At {}: {:.0}% duty", entry.display_reading(), duty))
+                        .size(14)
+                        .style(TEXT_COLOR)
+                        .width(Length::Fixed(220.0)),
+                    progress_bar(0.0..=100.0, duty as f32).height(Length::Fixed(8.0)),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center)
+            });
+
+        // Trying out a fixed duty; it ends by itself once the curve's temperature
+        // reaches the safety limit.
+        let limit = find_entry(sections, &curve.source.0, &curve.source.1)
+            .map_or(config.fan_safety_limit, |entry| fan_safety_limit(entry, config.fan_safety_limit));
+        let id = fan.id.clone();
+        let manual = row![
+            text("Manual").size(14).style(TEXT_COLOR).width(Length::Fixed(80.0)),
+            slider(
+                0.0..=100.0,
+                overridden.or(fan.duty_percent()).unwrap_or(100.0),
+                move |duty| Message::OverrideFan(id.clone(), duty),
+            )
+            .step(1.0),
+            text(match overridden {
+                Some(duty) => format!("{:.0}%, ends at {:.0}°C", duty, limit),
+                None => format!("ends at {:.0}°C", limit),
+            })
+            .size(14)
+            .style(TEXT_COLOR),
+        ]
+        .push_maybe(overridden.map(|_| small_button("End override").on_press(Message::EndOverride(fan.id.clone()))))
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        let actions = row![]
+            .push_maybe(draft.map(|_| small_button("Apply").on_press(Message::ApplyCurve(fan.id.clone()))))
+            .push_maybe(draft.map(|_| small_button("Revert").on_press(Message::RevertCurve(fan.id.clone()))))
+            .push_maybe(active.then(|| small_button("Return to automatic").on_press(Message::ReleaseFan(fan.id.clone()))))
+            .spacing(10);
+        let notice = notices
+            .get(&fan.id)
+            .map(|notice| text(notice).size(14).style(ERROR_COLOR));
+
+        page = page.push(
+            container(
+                column![header, source, points, zero_rpm]
+                    .push_maybe(preview)
+                    .push(actions)
+                    .push(manual)
+                    .push_maybe(notice)
+                    .spacing(HEADER_ROWS_SPACING),
+            )
+            .padding(SECTION_PADDING)
+            .width(Length::Fill)
+            .style(iced::theme::Container::Custom(Box::new(SectionContainerStyle))),
+        );
+    }
+    page.into()
 }
 
-struct SensorViewer {
-    sensor_data: Result<Vec<SensorSection>, String>,
+fn unparsed_count(sections: &[SensorSection]) -> usize {
+    sections.iter().map(|section| section.unparsed.len()).sum()
 }
 
-#[derive(Debug, Clone)]
-struct SensorSection {
-    name: String,
-    adapter: String,
-    entries: Vec<SensorEntry>,
+// Plain text for bug reports: one "chip: line  # reason" per skipped line.
+fn diagnostics_report(sections: &[SensorSection]) -> String {
+    let mut report = String::new();
+    for section in sections {
+        for unparsed in &section.unparsed {
+            report.push_str(&format!("{}: {}  # {}\n", section.name, unparsed.line, unparsed.reason));
+        }
+    }
+    report
 }
 
-#[derive(Debug, Clone)]
-struct SensorEntry {
-    key: String,
-    value: String,
-    additional_info: Option<String>,
+fn diagnostics_view(sections: &[SensorSection]) -> Element<'static, Message> {
+    let header = row![
+        text("Unparsed lines").size(HEADER_FONT_SIZE).style(HEADER_COLOR),
+        Space::with_width(Length::Fill),
+        small_button("Copy report").on_press(Message::CopyDiagnostics),
+        small_button("Close").on_press(Message::ToggleDiagnostics),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+
+    let mut body = column![
+        header,
+        text("These lines of sensor output were skipped. Including the report in a bug report shows exactly what your hardware prints.")
+            .size(14)
+            .style(TEXT_COLOR),
+    ]
+    .spacing(HEADER_ROWS_SPACING);
+
+    for section in sections.iter().filter(|section| !section.unparsed.is_empty()) {
+        body = body.push(section_title(section));
+        for (i, unparsed) in section.unparsed.iter().enumerate() {
+            let row_color = if i % 2 == 0 { BACKGROUND_COLOR } else { ROW_ALT_COLOR };
+            body = body.push(
+                container(
+                    row![
+                        text(&unparsed.line)
+                            .font(iced::Font::MONOSPACE)
+                            .size(14)
+                            .style(TEXT_COLOR)
+                            .width(Length::Fill),
+                        text(&unparsed.reason).size(14).style(UNAVAILABLE_COLOR),
+                    ]
+                    .spacing(COLUMN_SPACING)
+                    .align_items(Alignment::Center),
+                )
+                .style(iced::theme::Container::Custom(Box::new(RowStyle(row_color))))
+                .padding([8.0, ROW_PADDING])
+                .width(Length::Fill),
+            );
+        }
+    }
+
+    container(body)
+        .padding(SECTION_PADDING)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(SectionContainerStyle)))
+        .into()
 }
 
-impl Application for SensorViewer {
-    type Executor = iced::executor::Default;
-    type Message = Message;
-    type Theme = Theme;
-    type Flags = ();
+fn palette_key(event: iced::Event, _status: iced::event::Status) -> Option<Message> {
+    use iced::keyboard::{key::Named, Event, Key};
 
-    fn new(_flags: ()) -> (Self, Command<Self::Message>) {
-        (
-            SensorViewer {
-                sensor_data: read_sensor_data(),
-            },
-            Command::none(),
-        )
+    match event {
+        iced::Event::Keyboard(Event::KeyPressed { key: Key::Named(named), .. }) => match named {
+            Named::Escape => Some(Message::ClosePalette),
+            Named::ArrowUp => Some(Message::PaletteMove(-1)),
+            Named::ArrowDown => Some(Message::PaletteMove(1)),
+            _ => None,
+        },
+        _ => None,
     }
+}
 
-    fn title(&self) -> String {
-        String::from("Sensory")
+fn palette_input_id() -> text_input::Id {
+    text_input::Id::new("palette")
+}
+
+fn palette_view(palette: &PaletteState, matches: Vec<(String, Message)>) -> Element<'static, Message> {
+    const SHOWN: usize = 10;
+
+    // Keep the selection visible by scrolling the window of shown results.
+    let first = palette.selected.saturating_sub(SHOWN - 1);
+    let mut results = Column::new().spacing(2);
+    for (i, (label, message)) in matches.into_iter().enumerate().skip(first).take(SHOWN) {
+        let style = if i == palette.selected {
+            iced::theme::Button::Primary
+        } else {
+            iced::theme::Button::Text
+        };
+        results = results.push(
+            button(text(label).size(14))
+                .width(Length::Fill)
+                .style(style)
+                .on_press(message),
+        );
     }
 
-    fn update(&mut self, message: Message) -> Command<Self::Message> {
-        match message {
-            Message::Refresh => {
-                self.sensor_data = read_sensor_data();
-            }
+    let body = column![
+        text_input("Type a command…", &palette.query)
+            .id(palette_input_id())
+            .on_input(Message::PaletteInput)
+            .on_submit(Message::PaletteRun)
+            .padding(8),
+        results,
+    ]
+    .spacing(8);
+
+    container(
+        container(body)
+            .padding(10)
+            .width(Length::Fixed(500.0))
+            .style(iced::theme::Container::Custom(Box::new(PinnedHeaderStyle))),
+    )
+    .width(Length::Fill)
+    .padding([60, 0, 0, 0])
+    .center_x()
+    .into()
+}
+
+fn toolbar(
+    hide_disconnected: bool,
+    show_sidebar: bool,
+    paused: bool,
+    unparsed: usize,
+    // The fans button's label, with the active profile; `None` without fans.
+    fans: Option<String>,
+    cpu_power: bool,
+) -> Element<'static, Message> {
+    // Kept low-key: it is for bug reports, not a problem with the machine.
+    let unparsed = (unparsed > 0).then(|| {
+        button(text(format!("{} unparsed", unparsed)).size(12).style(UNAVAILABLE_COLOR))
+            .padding([2, 6])
+            .style(iced::theme::Button::Text)
+            .on_press(Message::ToggleDiagnostics)
+    });
+    container(
+        row![
+            small_button(if show_sidebar { "Hide sections" } else { "Sections" })
+                .on_press(Message::ToggleSidebar),
+            checkbox("Hide unconnected channels", hide_disconnected)
+                .on_toggle(Message::HideDisconnected)
+                .text_size(14),
+        ]
+        .push_maybe(unparsed)
+        .push(Space::with_width(Length::Fill))
+        .push(row![
+            small_button("Collapse all").on_press(Message::CollapseAll),
+            small_button("Expand all").on_press(Message::ExpandAll),
+            small_button(if paused { "Resume" } else { "Pause" }).on_press(Message::TogglePause),
+            small_button("Mini").on_press(Message::ToggleMiniMode),
+        ]
+        .push_maybe(fans.map(|label| small_button(&label).on_press(Message::ToggleFans)))
+        .push_maybe(cpu_power.then(|| small_button("CPU power").on_press(Message::ToggleCpuPower)))
+        .push(small_button("Settings").on_press(Message::ToggleSettings))
+        .push(small_button("Quit").on_press(Message::Quit))
+        .spacing(10))
+        .spacing(10)
+        .align_items(Alignment::Center),
+    )
+    .padding([10.0, CONTENT_PADDING])
+    .width(Length::Fill)
+    .into()
+}
+
+// Host, kernel, CPU and uptime, so a screenshot says which machine it is.
+fn system_header(system: &SystemInfo, uptime: Option<f64>) -> Element<'static, Message> {
+    let uptime = uptime.map(|seconds| format!("up {}", sysinfo::format_uptime(seconds)));
+    let hostname = system.hostname.clone().unwrap_or_else(|| "Unknown host".to_string());
+    let details = [system.kernel.clone(), system.cpu_model.clone(), uptime];
+    row![text(hostname).size(14).style(TEXT_COLOR)]
+        .extend(
+            details
+                .into_iter()
+                .flatten()
+                .map(|detail| text(detail).size(14).style(Color::from_rgb(0.6, 0.6, 0.6)).into()),
+        )
+        .spacing(15)
+        .padding([5.0, CONTENT_PADDING])
+        .into()
+}
+
+// Column titles with draggable dividers, offset to line up with the rows below.
+fn column_header(columns: ColumnWidths, limit_columns: &[LimitColumn], sparklines: bool) -> Element<'static, Message> {
+    let title = |label: &str, width: f32| {
+        text(label)
+            .size(14)
+            .style(Color::from_rgb(0.6, 0.6, 0.6))
+            .width(Length::Fixed(width))
+    };
+    let divider = |column: TableColumn| {
+        let gap = (COLUMN_SPACING - 7.0) / 2.0;
+        row![
+            Space::with_width(Length::Fixed(gap)),
+            Divider::new(
+                20.0,
+                move |delta| Message::ResizeColumn(column, delta),
+                Message::SaveUiState,
+            ),
+            Space::with_width(Length::Fixed(gap)),
+        ]
+    };
+
+    row![
+        Space::with_width(Length::Fixed(CONTENT_PADDING + SECTION_PADDING + ROW_PADDING)),
+        title("Sensor", columns.key),
+        divider(TableColumn::Key),
+        title("Value", columns.value).horizontal_alignment(iced::alignment::Horizontal::Right),
+        divider(TableColumn::Value),
+        title("Unit", columns.unit),
+        divider(TableColumn::Unit),
+    ]
+    .push_maybe(sparklines.then(|| title("Trend", SPARKLINE_WIDTH + COLUMN_SPACING)))
+    .extend(limit_columns.iter().flat_map(|column| {
+        [
+            title(column.title(), LIMIT_COLUMN_WIDTH)
+                .horizontal_alignment(iced::alignment::Horizontal::Right)
+                .into(),
+            Space::with_width(Length::Fixed(COLUMN_SPACING)).into(),
+        ]
+    }))
+    .push(title("Details", 0.0).width(Length::Fill))
+    .align_items(Alignment::Center)
+    .padding([0.0, 0.0, 5.0, 0.0])
+    .into()
+}
+
+fn small_button(label: &str) -> iced::widget::Button<'static, Message> {
+    button(text(label).size(14))
+        .padding([2, 8])
+        .style(iced::theme::Button::Secondary)
+}
+
+fn section_header(section: &SensorSection, collapsed: bool) -> Element<'static, Message> {
+    row![
+        small_button(if collapsed { "▸" } else { "▾" })
+            .on_press(Message::ToggleSection(section.name.clone())),
+        section_title(section),
+        Space::with_width(Length::Fill),
+        text(format!("Adapter: {}", section.adapter))
+            .style(Color::from_rgb(0.6, 0.6, 0.6)),
+        small_button("Collapse others").on_press(Message::CollapseOthers(section.name.clone())),
+    ]
+    .spacing(10)
+    .height(Length::Fixed(HEADER_HEIGHT))
+    .align_items(Alignment::Center)
+    .into()
+}
+
+// The chip name with its bus suffix dimmed: "nvme" + "-pci-0400". A labelled chip
+// leads with its label and dims the whole name: "sda (…)" + "drivetemp-scsi-0-0".
+fn section_title(section: &SensorSection) -> Element<'static, Message> {
+    let name = section.name.as_str();
+    let (chip, suffix) = match &section.label {
+        Some(label) => (label.as_str(), format!("  {}", name)),
+        None => {
+            let (chip, suffix) = name.split_at(name.find('-').unwrap_or(name.len()));
+            (chip, suffix.to_string())
         }
-        Command::none()
+    };
+    row![
+        text(chip).size(HEADER_FONT_SIZE).style(HEADER_COLOR),
+        text(suffix).size(HEADER_FONT_SIZE).style(Color::from_rgb(0.45, 0.6, 0.68)),
+    ]
+    .into()
+}
+
+// A chip with nothing parsed gets a "no readable entries" row, so a detected chip
+// never shows as a bare header, plus one row per raw line while expanded.
+fn parse_notice_rows(section: &SensorSection, expanded: bool) -> usize {
+    if !section.entries.is_empty() {
+        return 0;
     }
+    1 + if expanded { section.unparsed.len() } else { 0 }
+}
 
-    fn view(&self) -> Element<Message> {
-        let content = match &self.sensor_data {
-            Ok(data) => Column::with_children(
-                data.iter()
-                    .map(|section| sensor_section(section))
-                    .collect::<Vec<_>>(),
+fn section_height(visible_rows: usize, collapsed: bool) -> f32 {
+    if collapsed {
+        return 2.0 * SECTION_PADDING + HEADER_HEIGHT;
+    }
+    let rows = visible_rows as f32;
+    let rows_height = if rows > 0.0 {
+        rows * ROW_HEIGHT + (rows - 1.0) * ROW_SPACING
+    } else {
+        0.0
+    };
+    2.0 * SECTION_PADDING + HEADER_HEIGHT + HEADER_ROWS_SPACING + rows_height
+}
+
+// (top, bottom) of every section within the scrollable content.
+fn section_offsets(
+    sections: &[SensorSection],
+    collapsed: &HashSet<String>,
+    hide_disconnected: bool,
+    raw_expanded: &HashSet<String>,
+) -> Vec<(f32, f32)> {
+    let mut start = CONTENT_PADDING;
+    sections
+        .iter()
+        .map(|section| {
+            let rows = visible_entries(section, hide_disconnected).len()
+                + parse_notice_rows(section, raw_expanded.contains(&section.name));
+            let end = start + section_height(rows, collapsed.contains(&section.name));
+            let offsets = (start, end);
+            start = end + SECTION_SPACING;
+            offsets
+        })
+        .collect()
+}
+
+// The section whose own header has scrolled out of view while its rows are still visible.
+fn pinned_section<'a>(
+    sections: &'a [SensorSection],
+    collapsed: &HashSet<String>,
+    hide_disconnected: bool,
+    raw_expanded: &HashSet<String>,
+    offset: f32,
+) -> Option<&'a SensorSection> {
+    section_offsets(sections, collapsed, hide_disconnected, raw_expanded)
+        .into_iter()
+        .zip(sections)
+        .find(|((start, end), _)| offset > start + SECTION_PADDING && offset < *end)
+        .map(|(_, section)| section)
+}
+
+fn sections_scroll_id() -> scrollable::Id {
+    scrollable::Id::new("sections")
+}
+
+fn sidebar(sections: &[SensorSection], palette: StatusPalette) -> Element<'static, Message> {
+    let mut list = Column::new().spacing(2).padding(10);
+    for section in sections {
+        let alarms = section.entries.iter().filter(|entry| entry.in_alarm()).count();
+        let badge = (alarms > 0).then(|| {
+            container(text(format!("{} {}", colors::ALARM_ICON, alarms)).size(12).style(BACKGROUND_COLOR))
+                .padding([1, 6])
+                .style(iced::theme::Container::Custom(Box::new(BadgeStyle(palette.alarm()))))
+        });
+
+        list = list.push(
+            button(
+                row![text(&section.name).size(14).width(Length::Fill)]
+                    .push_maybe(badge)
+                    .spacing(5)
+                    .align_items(Alignment::Center),
             )
-            .spacing(20),
-            Err(e) => column![
-                horizontal_rule(1).style(iced::theme::Rule::Custom(Box::new(error_rule_style))),
-                text(format!("Error: {}", e))
-                    .size(HEADER_FONT_SIZE)
-                    .style(ERROR_COLOR)
-            ]
-            .spacing(10),
+            .width(Length::Fill)
+            .style(iced::theme::Button::Text)
+            .on_press(Message::JumpToSection(section.name.clone())),
+        );
+    }
+
+    container(scrollable(list))
+        .width(Length::Fixed(200.0))
+        .height(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(SectionContainerStyle)))
+        .into()
+}
+
+// The whole window in mini mode: a drag bar and one line per pinned reading.
+fn mini_view(
+    pinned: &[(String, String)],
+    sections: &[SensorSection],
+    palette: StatusPalette,
+) -> Element<'static, Message> {
+    let bar = mouse_area(
+        row![
+            text("Sensory").size(14).style(HEADER_COLOR),
+            Space::with_width(Length::Fill),
+            small_button("Corner").on_press(Message::CycleMiniCorner),
+            small_button("Full").on_press(Message::ToggleMiniMode),
+        ]
+        .spacing(5)
+        .height(Length::Fixed(mini::MINI_BAR_HEIGHT))
+        .align_items(Alignment::Center),
+    )
+    .on_press(Message::DragWindow);
+
+    let mut rows = Column::new();
+    if pinned.is_empty() {
+        rows = rows.push(
+            text("Pin readings with ☆ in the full view")
+                .size(14)
+                .style(TEXT_COLOR)
+                .height(Length::Fixed(mini::MINI_ROW_HEIGHT)),
+        );
+    }
+    for (section, key) in pinned {
+        let entry = sections
+            .iter()
+            .find(|s| &s.name == section)
+            .and_then(|s| s.entries.iter().find(|e| &e.key == key));
+        let (reading, color) = match entry {
+            Some(entry) => match value_color(entry, palette) {
+                (color, true) => (format!("{} {}", colors::ALARM_ICON, entry.display_reading()), color),
+                (color, false) => (entry.display_reading(), color),
+            },
+            None => ("—".to_string(), TEXT_COLOR),
         };
+        rows = rows.push(
+            row![
+                text(key).size(14).style(TEXT_COLOR).width(Length::Fill),
+                text(reading).size(14).style(color),
+            ]
+            .height(Length::Fixed(mini::MINI_ROW_HEIGHT))
+            .align_items(Alignment::Center),
+        );
+    }
 
-        container(scrollable(
-            column![content.spacing(20)]
-                .spacing(20)
-                .padding(20),
-        ))
-        .style(iced::theme::Container::Custom(Box::new(AppContainerStyle)))
+    container(column![bar, rows])
+        .padding([0, 8, 8, 8])
         .width(Length::Fill)
         .height(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(AppContainerStyle)))
         .into()
-    }
+}
 
-    fn subscription(&self) -> Subscription<Self::Message> {
-        iced::time::every(Duration::from_millis(500)).map(|_| Message::Refresh)
-    }
+fn pinned_header(section: &SensorSection) -> Element<'static, Message> {
+    container(
+        container(section_header(section, false))
+            .padding([10.0, SECTION_PADDING])
+            .width(Length::Fill)
+            .style(iced::theme::Container::Custom(Box::new(PinnedHeaderStyle))),
+    )
+    .padding([0.0, CONTENT_PADDING])
+    .width(Length::Fill)
+    .into()
 }
 
-fn sensor_section(section: &SensorSection) -> Element<'static, Message> {
-    let header = row![
-        text(&section.name)
-            .size(HEADER_FONT_SIZE)
-            .style(HEADER_COLOR),
-        Space::with_width(Length::Fill),
-        text(format!("Adapter: {}", section.adapter))
-            .style(Color::from_rgb(0.6, 0.6, 0.6))
-    ];
+fn sensor_section(
+    section: &SensorSection,
+    collapsed: bool,
+    context: &RowContext<'_>,
+) -> Element<'static, Message> {
+    let header = section_header(section, collapsed);
+    if collapsed {
+        return container(header)
+            .padding(SECTION_PADDING)
+            .style(iced::theme::Container::Custom(Box::new(SectionContainerStyle)))
+            .into();
+    }
+    let now = Instant::now();
+
+    let mut rows = Column::new().spacing(ROW_SPACING);
+    let columns = context.columns;
+    for (i, entry) in visible_entries(section, context.hide_disconnected).into_iter().enumerate() {
+        let flashing = context
+            .flashes
+            .get(&(section.name.clone(), entry.key.clone()))
+            .is_some_and(|changed| now.duration_since(*changed) < context.flash_duration);
 
-    let mut rows = Column::new().spacing(5);
-    for (i, entry) in section.entries.iter().enumerate() {
-        let row_color = if i % 2 == 0 {
+        let focused = context
+            .focused
+            .is_some_and(|(s, k)| *s == section.name && *k == entry.key);
+        let row_color = if focused {
+            FOCUS_COLOR
+        } else if flashing {
+            FLASH_COLOR
+        } else if i % 2 == 0 {
             BACKGROUND_COLOR
         } else {
             ROW_ALT_COLOR
         };
 
+        let key: Element<'static, Message> = match glossary::explain(&entry.key) {
+            Some(explanation) => tooltip(
+                text(&entry.key).style(TEXT_COLOR).width(Length::Fixed(columns.key)),
+                container(text(explanation).size(14).style(TEXT_COLOR))
+                    .padding(8)
+                    .max_width(320.0)
+                    .style(iced::theme::Container::Custom(Box::new(PinnedHeaderStyle))),
+                tooltip::Position::Bottom,
+            )
+            .into(),
+            None => text(&entry.key).style(TEXT_COLOR).width(Length::Fixed(columns.key)).into(),
+        };
+
+        let pinned = context
+            .pinned
+            .iter()
+            .any(|(s, k)| *s == section.name && *k == entry.key);
+        let palette = context.palette;
+        let (value_color, number) = match value_color(entry, palette) {
+            (color, true) => (color, format!("{} {}", colors::ALARM_ICON, entry.display_number())),
+            (color, false) => (color, entry.display_number()),
+        };
+
+        let value: Element<'static, Message> = match entry.status {
+            Some(status) => container(status_chip(status, palette))
+                .width(Length::Fixed(columns.value))
+                .align_x(iced::alignment::Horizontal::Right)
+                .into(),
+            None => text(number)
+                .style(value_color)
+                .width(Length::Fixed(columns.value))
+                .horizontal_alignment(iced::alignment::Horizontal::Right)
+                .into(),
+        };
+        let smoothing = context
+            .smoothing
+            .iter()
+            .find(|((s, k), _)| *s == section.name && *k == entry.key)
+            .map(|(_, alpha)| format!("Smoothed with alpha {}; read as {}", alpha, entry.value));
+        let interval =
+            entry.interval.map(|interval| format!("Average over {:.2} s, not an instantaneous reading", interval));
+        let notes: Vec<String> = [interval, smoothing].into_iter().flatten().collect();
+        let value = if notes.is_empty() {
+            value
+        } else {
+            tooltip(
+                value,
+                container(text(notes.join("\n")).size(14).style(TEXT_COLOR))
+                    .padding(8)
+                    .style(iced::theme::Container::Custom(Box::new(PinnedHeaderStyle))),
+                tooltip::Position::Bottom,
+            )
+            .into()
+        };
+
         let row = container(
             row![
-                text(&entry.key).style(TEXT_COLOR).width(Length::Fixed(200.0)),
-                text(&entry.value)
-                    .style(Color::from_rgb(0.4, 0.8, 0.4))
-                    .width(Length::Fixed(150.0)),
-                text(entry.additional_info.clone().unwrap_or_default())
-                    .style(Color::from_rgb(0.8, 0.8, 0.4))
-                    .width(Length::Fill),
+                key,
+                value,
+                text(entry.display_unit())
+                    .style(value_color)
+                    .width(Length::Fixed(columns.unit)),
             ]
-            .spacing(20)
+            .push_maybe(context.history.map(|history| sparkline_cell(history, section, entry, value_color)))
+            .extend(context.limit_columns.iter().map(|&column| limit_cell(entry, column, palette.info())))
+            .push(usage_details(entry, context.limit_columns, palette))
+            .push(
+                small_button(if pinned { "★" } else { "☆" })
+                    .on_press(Message::TogglePin(section.name.clone(), entry.key.clone())),
+            )
+            .spacing(COLUMN_SPACING)
             .align_items(Alignment::Center),
         )
         .style(iced::theme::Container::Custom(Box::new(RowStyle(row_color))))
-        .padding(10)
-        .width(Length::Fill);
+        .padding([0.0, ROW_PADDING])
+        .width(Length::Fill)
+        .height(Length::Fixed(ROW_HEIGHT))
+        .center_y();
 
         rows = rows.push(row);
     }
 
-    container(column![header, rows].spacing(10))
-        .padding(20)
+    let expanded = context.raw_expanded.contains(&section.name);
+    if parse_notice_rows(section, expanded) > 0 {
+        let notice = match section.unparsed.len() {
+            0 => "No readable entries".to_string(),
+            1 => "No readable entries (1 raw line)".to_string(),
+            lines => format!("No readable entries ({} raw lines)", lines),
+        };
+        let view_raw = (!section.unparsed.is_empty()).then(|| {
+            small_button(if expanded { "Hide raw" } else { "View raw" })
+                .on_press(Message::ToggleRaw(section.name.clone()))
+        });
+        rows = rows.push(raw_row(
+            row![text(notice).style(UNAVAILABLE_COLOR).width(Length::Fill)]
+                .push_maybe(view_raw)
+                .spacing(COLUMN_SPACING)
+                .align_items(Alignment::Center)
+                .into(),
+        ));
+        if expanded {
+            for line in &section.unparsed {
+                rows = rows.push(raw_row(
+                    text(&line.line).font(iced::Font::MONOSPACE).size(14).style(TEXT_COLOR).into(),
+                ));
+            }
+        }
+    }
+
+    container(column![header, rows].spacing(HEADER_ROWS_SPACING))
+        .padding(SECTION_PADDING)
         .style(iced::theme::Container::Custom(Box::new(SectionContainerStyle)))
         .into()
 }
 
-// Custom rule style function
-fn error_rule_style(_theme: &Theme) -> iced::widget::rule::Appearance {
-    iced::widget::rule::Appearance {
-        color: ERROR_COLOR,
-        width: 1,
-        radius: 0.0.into(),
-        fill_mode: iced::widget::rule::FillMode::Full,
+// The reading's recent samples; blank for readings without a number, so the columns
+// still line up.
+fn sparkline_cell(
+    history: &HashMap<(String, String), VecDeque<f64>>,
+    section: &SensorSection,
+    entry: &SensorEntry,
+    color: Color,
+) -> Element<'static, Message> {
+    match history.get(&(section.name.clone(), entry.key.clone())).filter(|samples| samples.len() > 1) {
+        Some(samples) => Sparkline::new(
+            samples.iter().copied().collect(),
+            SPARKLINE_SAMPLES,
+            color,
+            SPARKLINE_WIDTH,
+            ROW_HEIGHT / 2.0,
+        )
+        .into(),
+        None => Space::with_width(Length::Fixed(SPARKLINE_WIDTH)).into(),
+    }
+}
+
+// The details column, led by a usage bar and percentage for memory readings.
+fn usage_details(
+    entry: &SensorEntry,
+    limit_columns: &[LimitColumn],
+    palette: StatusPalette,
+) -> Element<'static, Message> {
+    let details = text(entry_details(entry, limit_columns)).style(palette.info()).width(Length::Fill);
+    match entry.usage() {
+        Some(usage) => row![
+            progress_bar(0.0..=1.0, usage as f32)
+                .width(Length::Fixed(USAGE_BAR_WIDTH))
+                .height(Length::Fixed(8.0)),
+            text(format!("{:.0}%", usage * 100.0)).style(palette.info()),
+            details,
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center)
+        .into(),
+        None => details.into(),
     }
 }
 
+fn raw_row(content: Element<'static, Message>) -> Element<'static, Message> {
+    container(content)
+        .style(iced::theme::Container::Custom(Box::new(RowStyle(ROW_ALT_COLOR))))
+        .padding([0.0, ROW_PADDING])
+        .width(Length::Fill)
+        .height(Length::Fixed(ROW_HEIGHT))
+        .center_y()
+        .into()
+}
+
 // Custom styles
 struct AppContainerStyle;
 struct SectionContainerStyle;
+struct PinnedHeaderStyle;
+struct ErrorBannerStyle;
+struct BadgeStyle(Color);
 struct RowStyle(Color);
 
 impl iced::widget::container::StyleSheet for AppContainerStyle {
@@ -197,85 +3571,65 @@ impl iced::widget::container::StyleSheet for SectionContainerStyle {
     }
 }
 
-impl iced::widget::container::StyleSheet for RowStyle {
+impl iced::widget::container::StyleSheet for PinnedHeaderStyle {
     type Style = Theme;
 
     fn appearance(&self, _style: &Self::Style) -> iced::widget::container::Appearance {
         iced::widget::container::Appearance {
-            background: Some(self.0.into()),
+            background: Some(Color::from_rgb(0.15, 0.15, 0.15).into()),
+            border: iced::Border {
+                radius: [0.0, 0.0, 8.0, 8.0].into(),
+                width: 1.0,
+                color: Color::from_rgb(0.3, 0.3, 0.3),
+            },
+            shadow: iced::Shadow {
+                color: Color::from_rgba(0.0, 0.0, 0.0, 0.5),
+                offset: iced::Vector::new(0.0, 2.0),
+                blur_radius: 6.0,
+            },
             ..Default::default()
         }
     }
 }
 
-// Sensor data reading and Parsing functions(add the graph too[real-time])
-fn read_sensor_data() -> Result<Vec<SensorSection>, String> {
-    let output = StdCommand::new("sensors")
-        .output()
-        .map_err(|e| format!("Failed to execute sensors command: {}", e))?;
+impl iced::widget::container::StyleSheet for ErrorBannerStyle {
+    type Style = Theme;
 
-    if !output.status.success() {
-        return Err(format!(
-            "sensors command failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
+    fn appearance(&self, _style: &Self::Style) -> iced::widget::container::Appearance {
+        iced::widget::container::Appearance {
+            background: Some(Color::from_rgb(0.2, 0.08, 0.08).into()),
+            border: iced::Border {
+                radius: 0.0.into(),
+                width: 1.0,
+                color: ERROR_COLOR,
+            },
+            ..Default::default()
+        }
     }
-
-    let output_str = String::from_utf8_lossy(&output.stdout);
-    parse_sensor_output(&output_str)
 }
 
-fn parse_sensor_output(input: &str) -> Result<Vec<SensorSection>, String> {
-    let mut sections = Vec::new();
-    let mut current_section: Option<SensorSection> = None;
-
-    static ENTRY_REGEX: Lazy<Regex> = Lazy::new(|| {
-        Regex::new(
-            r"(?x)
-            ^(?P<key>.+?):\s+
-            (?P<value>[+-]?\d+\.?\d*\s?(°C|RPM|V|W|%|mA)?)
-            (\s+\((?P<info>.+?)\))?$
-            ",
-        )
-        .unwrap()
-    });
-
-    for line in input.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
+impl iced::widget::container::StyleSheet for BadgeStyle {
+    type Style = Theme;
 
-        if !line.contains(':') && !line.starts_with("Adapter:") {
-            if let Some(section) = current_section.take() {
-                sections.push(section);
-            }
-            current_section = Some(SensorSection {
-                name: line.to_string(),
-                adapter: String::new(),
-                entries: Vec::new(),
-            });
-        } else if let Some(ref mut section) = current_section {
-            if line.starts_with("Adapter:") {
-                section.adapter = line.replace("Adapter:", "").trim().to_string();
-            } else if let Some(caps) = ENTRY_REGEX.captures(line) {
-                let entry = SensorEntry {
-                    key: caps["key"].to_string(),
-                    value: caps["value"].trim().to_string(),
-                    additional_info: caps.name("info").map(|m| m.as_str().to_string()),
-                };
-                section.entries.push(entry);
-            }
+    fn appearance(&self, _style: &Self::Style) -> iced::widget::container::Appearance {
+        iced::widget::container::Appearance {
+            background: Some(self.0.into()),
+            border: iced::Border {
+                radius: 8.0.into(),
+                ..Default::default()
+            },
+            ..Default::default()
         }
     }
+}
 
-    if let Some(section) = current_section.take() {
-        sections.push(section);
-    }
+impl iced::widget::container::StyleSheet for RowStyle {
+    type Style = Theme;
 
-    if sections.is_empty() {
-        Err("No sensor data found".to_string())
-    } else {
-        Ok(sections)
+    fn appearance(&self, _style: &Self::Style) -> iced::widget::container::Appearance {
+        iced::widget::container::Appearance {
+            background: Some(self.0.into()),
+            ..Default::default()
+        }
     }
 }
@@ -0,0 +1,85 @@
+// Placement for mini mode: a small undecorated window snapped to a screen corner.
+use iced::{Point, Size};
+use std::fs;
+
+pub const MINI_WIDTH: f32 = 260.0;
+pub const MINI_ROW_HEIGHT: f32 = 24.0;
+pub const MINI_BAR_HEIGHT: f32 = 30.0;
+const MARGIN: f32 = 12.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Corner {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomRight,
+    BottomLeft,
+}
+
+impl Corner {
+    pub fn config_name(self) -> &'static str {
+        match self {
+            Corner::TopLeft => "top-left",
+            Corner::TopRight => "top-right",
+            Corner::BottomRight => "bottom-right",
+            Corner::BottomLeft => "bottom-left",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Corner> {
+        [Corner::TopLeft, Corner::TopRight, Corner::BottomRight, Corner::BottomLeft]
+            .into_iter()
+            .find(|corner| corner.config_name() == name.trim())
+    }
+
+    // Clockwise, so repeatedly pressing the corner button walks around the screen.
+    pub fn next(self) -> Corner {
+        match self {
+            Corner::TopLeft => Corner::TopRight,
+            Corner::TopRight => Corner::BottomRight,
+            Corner::BottomRight => Corner::BottomLeft,
+            Corner::BottomLeft => Corner::TopLeft,
+        }
+    }
+
+    pub fn position(self, screen: Size, window: Size) -> Point {
+        let left = MARGIN;
+        let top = MARGIN;
+        let right = (screen.width - window.width - MARGIN).max(0.0);
+        let bottom = (screen.height - window.height - MARGIN).max(0.0);
+        match self {
+            Corner::TopLeft => Point::new(left, top),
+            Corner::TopRight => Point::new(right, top),
+            Corner::BottomRight => Point::new(right, bottom),
+            Corner::BottomLeft => Point::new(left, bottom),
+        }
+    }
+}
+
+pub fn window_size(rows: usize) -> Size {
+    Size::new(MINI_WIDTH, MINI_BAR_HEIGHT + rows.max(1) as f32 * MINI_ROW_HEIGHT + 8.0)
+}
+
+// iced does not expose monitor geometry, so read the preferred mode of the first
+// connected DRM output ("1920x1080"). Scaled desktops should set `[mini] screen`.
+pub fn detect_screen_size() -> Option<Size> {
+    let mut connectors: Vec<_> = fs::read_dir("/sys/class/drm")
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            fs::read_to_string(path.join("status")).is_ok_and(|status| status.trim() == "connected")
+        })
+        .collect();
+    connectors.sort();
+
+    connectors
+        .iter()
+        .find_map(|path| fs::read_to_string(path.join("modes")).ok()?.lines().next().and_then(parse_size))
+}
+
+// "1920x1080" -> 1920 x 1080.
+pub fn parse_size(text: &str) -> Option<Size> {
+    let (width, height) = text.trim().split_once('x')?;
+    Some(Size::new(width.trim().parse().ok()?, height.trim().parse().ok()?))
+}
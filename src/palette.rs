@@ -0,0 +1,38 @@
+// Fuzzy matching for the command palette.
+
+// Scores `label` against `query` as a case-insensitive subsequence match; `None` if
+// some query character is missing. Consecutive runs and word starts score higher,
+// skipped characters cost a little, so "cola" ranks "Collapse all" above "Copy label".
+pub fn fuzzy_score(query: &str, label: &str) -> Option<i32> {
+    let label: Vec<char> = label.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = (position..label.len()).find(|&i| label[i] == wanted)?;
+
+        score += 1;
+        if previous_match == Some(found.wrapping_sub(1)) {
+            score += 5;
+        }
+        if found == 0 || !label[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        score -= (found - position) as i32 / 2;
+
+        previous_match = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+// Indices of `labels` matching `query`, best first; ties keep their original order.
+pub fn rank<'a>(query: &str, labels: impl Iterator<Item = &'a str>) -> Vec<usize> {
+    let mut scored: Vec<(usize, i32)> = labels
+        .enumerate()
+        .filter_map(|(i, label)| fuzzy_score(query, label).map(|score| (i, score)))
+        .collect();
+    scored.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    scored.into_iter().map(|(i, _)| i).collect()
+}
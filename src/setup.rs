@@ -0,0 +1,73 @@
+// Gathers hints for the empty state: what sensor drivers are loaded and whether
+// lm-sensors has been configured on this machine.
+use std::fs;
+use std::path::Path;
+
+// Kernel modules that register hwmon chips on common desktop and laptop hardware.
+const HWMON_MODULES: &[&str] = &[
+    "coretemp",
+    "k10temp",
+    "zenpower",
+    "nct6775",
+    "nct6683",
+    "it87",
+    "w83627ehf",
+    "f71882fg",
+    "asus_ec_sensors",
+    "asus_wmi_sensors",
+    "dell_smm_hwmon",
+    "thinkpad_acpi",
+    "applesmc",
+    "amdgpu",
+    "nouveau",
+    "nvme",
+    "drivetemp",
+    "acpi_power_meter",
+    "lm75",
+    "lm78",
+    "lm85",
+    "jc42",
+];
+
+// Files written by `sensors-detect` on the major distributions.
+const SENSORS_DETECT_CONFIGS: &[&str] = &[
+    "/etc/sysconfig/lm_sensors",
+    "/etc/modules-load.d/lm_sensors.conf",
+    "/etc/conf.d/lm_sensors",
+];
+
+pub const TROUBLESHOOTING_STEPS: &[&str] = &[
+    "Install lm-sensors for sensors-detect (the package is `lm_sensors` on Fedora, `lm-sensors` on Debian/Ubuntu).",
+    "Run `sudo sensors-detect` and accept the suggested defaults.",
+    "Load the modules it recommends with `sudo modprobe <module>`, or reboot.",
+    "Run `sensors` in a terminal to confirm it prints readings.",
+    "Virtual machines and containers usually expose no hardware sensors.",
+];
+
+#[derive(Debug, Clone)]
+pub struct SetupInfo {
+    pub loaded_modules: Vec<String>,
+    pub sensors_detect_config: Option<String>,
+}
+
+impl SetupInfo {
+    pub fn gather() -> Self {
+        SetupInfo {
+            loaded_modules: loaded_hwmon_modules(),
+            sensors_detect_config: SENSORS_DETECT_CONFIGS
+                .iter()
+                .find(|path| Path::new(path).exists())
+                .map(|path| path.to_string()),
+        }
+    }
+}
+
+fn loaded_hwmon_modules() -> Vec<String> {
+    let modules = fs::read_to_string("/proc/modules").unwrap_or_default();
+    modules
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|name| HWMON_MODULES.contains(name))
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,84 @@
+// A small chart of a reading's recent samples, drawn as one thin column per sample
+// scaled between the lowest and highest of them.
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer;
+use iced::advanced::widget::{Tree, Widget};
+use iced::mouse;
+use iced::{Color, Element, Length, Rectangle, Size};
+
+pub struct Sparkline {
+    samples: Vec<f64>,
+    // Samples the width is divided into; the newest is on the right, so a short history
+    // grows in from there.
+    capacity: usize,
+    color: Color,
+    width: f32,
+    height: f32,
+}
+
+impl Sparkline {
+    pub fn new(samples: Vec<f64>, capacity: usize, color: Color, width: f32, height: f32) -> Self {
+        Sparkline { samples, capacity: capacity.max(1), color, width, height }
+    }
+}
+
+impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer> for Sparkline
+where
+    Renderer: iced::advanced::Renderer,
+{
+    fn size(&self) -> Size<Length> {
+        Size::new(Length::Fixed(self.width), Length::Fixed(self.height))
+    }
+
+    fn layout(&self, _tree: &mut Tree, _renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        layout::Node::new(limits.resolve(
+            Length::Fixed(self.width),
+            Length::Fixed(self.height),
+            Size::new(self.width, self.height),
+        ))
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        renderer: &mut Renderer,
+        _theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        let low = self.samples.iter().copied().fold(f64::INFINITY, f64::min);
+        let high = self.samples.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let step = bounds.width / self.capacity as f32;
+        for (i, &sample) in self.samples.iter().rev().take(self.capacity).enumerate() {
+            // A flat history sits in the middle rather than on the floor.
+            let level = if high > low { ((sample - low) / (high - low)) as f32 } else { 0.5 };
+            let top = bounds.y + (1.0 - level) * (bounds.height - 2.0);
+            let column = Rectangle {
+                x: bounds.x + bounds.width - (i + 1) as f32 * step,
+                y: top,
+                width: step.max(1.0),
+                height: bounds.y + bounds.height - top,
+            };
+            renderer.fill_quad(renderer::Quad { bounds: column, ..renderer::Quad::default() }, Color {
+                a: 0.3,
+                ..self.color
+            });
+            renderer.fill_quad(
+                renderer::Quad { bounds: Rectangle { height: 2.0, ..column }, ..renderer::Quad::default() },
+                self.color,
+            );
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<Sparkline> for Element<'a, Message, Theme, Renderer>
+where
+    Renderer: iced::advanced::Renderer + 'a,
+{
+    fn from(sparkline: Sparkline) -> Self {
+        Element::new(sparkline)
+    }
+}
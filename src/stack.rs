@@ -0,0 +1,222 @@
+// A minimal two-layer widget: `top` is drawn over `base`, aligned to its top edge.
+// iced 0.12 has no stack widget, and the pinned section header needs to float
+// above the scrollable without pushing its content around.
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer;
+use iced::advanced::widget::{Operation, Tree, Widget};
+use iced::advanced::{overlay, Clipboard, Shell};
+use iced::event::{self, Event};
+use iced::mouse;
+use iced::{Element, Length, Rectangle, Size, Vector};
+
+pub struct Stack<'a, Message, Theme, Renderer> {
+    base: Element<'a, Message, Theme, Renderer>,
+    top: Option<Element<'a, Message, Theme, Renderer>>,
+}
+
+impl<'a, Message, Theme, Renderer> Stack<'a, Message, Theme, Renderer> {
+    pub fn new(
+        base: impl Into<Element<'a, Message, Theme, Renderer>>,
+        top: Option<Element<'a, Message, Theme, Renderer>>,
+    ) -> Self {
+        Stack {
+            base: base.into(),
+            top,
+        }
+    }
+
+    fn layers(&self) -> impl Iterator<Item = &Element<'a, Message, Theme, Renderer>> {
+        std::iter::once(&self.base).chain(self.top.as_ref())
+    }
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for Stack<'a, Message, Theme, Renderer>
+where
+    Renderer: iced::advanced::Renderer,
+{
+    fn children(&self) -> Vec<Tree> {
+        self.layers().map(Tree::new).collect()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        match &self.top {
+            Some(top) => tree.diff_children(&[&self.base, top]),
+            None => tree.diff_children(&[&self.base]),
+        }
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.base.as_widget().size()
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        let base = self
+            .base
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits);
+        let size = base.size();
+
+        let mut children = vec![base];
+        if let Some(top) = &self.top {
+            let top_limits = layout::Limits::new(Size::ZERO, size);
+            children.push(top.as_widget().layout(&mut tree.children[1], renderer, &top_limits));
+        }
+
+        layout::Node::with_children(size, children)
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation<Message>,
+    ) {
+        operation.container(None, layout.bounds(), &mut |operation| {
+            self.layers()
+                .zip(&mut tree.children)
+                .zip(layout.children())
+                .for_each(|((child, state), layout)| {
+                    child.as_widget().operate(state, layout, renderer, operation);
+                });
+        });
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        let mut layouts = layout.children();
+        let base_layout = layouts.next().expect("stack base layout");
+
+        if let (Some(top), Some(top_layout)) = (&mut self.top, layouts.next()) {
+            let status = top.as_widget_mut().on_event(
+                &mut tree.children[1],
+                event.clone(),
+                top_layout,
+                cursor,
+                renderer,
+                clipboard,
+                shell,
+                viewport,
+            );
+            if status == event::Status::Captured {
+                return status;
+            }
+        }
+
+        self.base.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            base_layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.layers()
+            .zip(&tree.children)
+            .zip(layout.children())
+            .map(|((child, state), layout)| {
+                child
+                    .as_widget()
+                    .mouse_interaction(state, layout, cursor, viewport, renderer)
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let mut layouts = layout.children();
+        let base_layout = layouts.next().expect("stack base layout");
+
+        self.base.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            base_layout,
+            cursor,
+            viewport,
+        );
+
+        if let (Some(top), Some(top_layout)) = (&self.top, layouts.next()) {
+            // A separate layer so the top's quads land above the base's text.
+            renderer.with_layer(top_layout.bounds(), |renderer| {
+                top.as_widget().draw(
+                    &tree.children[1],
+                    renderer,
+                    theme,
+                    style,
+                    top_layout,
+                    cursor,
+                    viewport,
+                );
+            });
+        }
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        let children = std::iter::once(&mut self.base).chain(self.top.as_mut());
+        let overlays: Vec<_> = children
+            .zip(&mut tree.children)
+            .zip(layout.children())
+            .filter_map(|((child, state), layout)| {
+                child.as_widget_mut().overlay(state, layout, renderer, translation)
+            })
+            .collect();
+
+        (!overlays.is_empty()).then(|| overlay::Group::with_children(overlays).overlay())
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<Stack<'a, Message, Theme, Renderer>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: 'a,
+    Renderer: iced::advanced::Renderer + 'a,
+{
+    fn from(stack: Stack<'a, Message, Theme, Renderer>) -> Self {
+        Element::new(stack)
+    }
+}
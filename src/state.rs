@@ -0,0 +1,105 @@
+// UI state remembered between runs, stored in `$XDG_STATE_HOME/sensory/state.conf`.
+// Kept apart from the hand-edited config so saving it never rewrites a user's file.
+use crate::config;
+use std::fs;
+use std::path::PathBuf;
+
+pub const MIN_COLUMN_WIDTH: f32 = 30.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ColumnWidths {
+    pub key: f32,
+    pub value: f32,
+    pub unit: f32,
+}
+
+impl Default for ColumnWidths {
+    fn default() -> Self {
+        ColumnWidths {
+            key: 200.0,
+            value: 90.0,
+            unit: 40.0,
+        }
+    }
+}
+
+// Logical size and position of the main window when it was last closed.
+#[derive(Debug, Clone, Copy)]
+pub struct WindowState {
+    pub width: f32,
+    pub height: f32,
+    pub position: Option<(i32, i32)>,
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        WindowState {
+            width: 700.0,
+            height: 900.0,
+            position: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UiState {
+    pub columns: ColumnWidths,
+    pub window: WindowState,
+}
+
+impl UiState {
+    pub fn load() -> Self {
+        let mut state = UiState::default();
+        let Some(contents) = state_path().and_then(|path| fs::read_to_string(path).ok()) else {
+            return state;
+        };
+
+        let mut x = None;
+        let mut y = None;
+        for (section, key, value) in config::parse(&contents) {
+            let Ok(number) = value.parse::<f32>() else {
+                continue;
+            };
+            let width = number.max(MIN_COLUMN_WIDTH);
+            match (section.as_str(), key.as_str()) {
+                ("columns", "key") => state.columns.key = width,
+                ("columns", "value") => state.columns.value = width,
+                ("columns", "unit") => state.columns.unit = width,
+                ("window", "width") => state.window.width = number.max(200.0),
+                ("window", "height") => state.window.height = number.max(200.0),
+                ("window", "x") => x = Some(number as i32),
+                ("window", "y") => y = Some(number as i32),
+                _ => {}
+            }
+        }
+        state.window.position = x.zip(y);
+        state
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = state_path().ok_or("No state directory available")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+
+        let mut contents = format!(
+            "[columns]\nkey = {}\nvalue = {}\nunit = {}\n\n[window]\nwidth = {}\nheight = {}\n",
+            self.columns.key,
+            self.columns.value,
+            self.columns.unit,
+            self.window.width,
+            self.window.height,
+        );
+        if let Some((x, y)) = self.window.position {
+            contents.push_str(&format!("x = {}\ny = {}\n", x, y));
+        }
+        fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+fn state_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))?;
+    Some(base.join("sensory").join("state.conf"))
+}